@@ -0,0 +1,11 @@
+/* jaarg-embedded - Bare-metal jaarg example
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+fn main() {
+  // Let the linker find link.x in the package directory
+  println!("cargo:rustc-link-search={}", env!("CARGO_MANIFEST_DIR"));
+  println!("cargo:rustc-link-arg=-Tlink.x");
+  println!("cargo:rerun-if-changed=link.x");
+}
@@ -0,0 +1,44 @@
+/* jaarg-embedded - Memory layout of the lm3s6965 (Cortex-M3)
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 256K
+  RAM   : ORIGIN = 0x20000000, LENGTH = 64K
+}
+
+ENTRY(reset);
+
+SECTIONS
+{
+  /* Initial stack pointer followed by the reset vector, the only exception handled */
+  .vector_table ORIGIN(FLASH) :
+  {
+    LONG(ORIGIN(RAM) + LENGTH(RAM));
+    KEEP(*(.vector_table.reset));
+  } > FLASH
+
+  .text : { *(.text .text.*); } > FLASH
+  .rodata : { *(.rodata .rodata.*); } > FLASH
+
+  .data : ALIGN(4)
+  {
+    _sdata = .;
+    *(.data .data.*);
+    . = ALIGN(4);
+    _edata = .;
+  } > RAM AT > FLASH
+  _sidata = LOADADDR(.data);
+
+  .bss (NOLOAD) : ALIGN(4)
+  {
+    _sbss = .;
+    *(.bss .bss.*);
+    . = ALIGN(4);
+    _ebss = .;
+  } > RAM
+
+  /DISCARD/ : { *(.ARM.exidx .ARM.exidx.*); }
+}
@@ -0,0 +1,131 @@
+/* jaarg-embedded - Bare-metal jaarg example parsing a boot command line
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use core::panic::PanicInfo;
+use jaarg::{Opt, Opts, ParseControl, ParseResult, StandardErrorUsageWriter};
+
+/// Serial port writer, for UART0 of the lm3s6965 which QEMU connects to the console.
+struct Serial;
+
+impl Serial {
+  /// UART0 data register, QEMU doesn't require the UART to be configured before writing.
+  const DATA: *mut u32 = 0x4000_C000 as *mut u32;
+}
+
+impl Write for Serial {
+  fn write_str(&mut self, s: &str) -> core::fmt::Result {
+    for b in s.bytes() {
+      if b == b'\n' {
+        unsafe { Self::DATA.write_volatile(b'\r' as u32) };
+      }
+      unsafe { Self::DATA.write_volatile(b as u32) };
+    }
+    Ok(())
+  }
+}
+
+/// Exit QEMU through semihosting, the status is only distinguished as success or failure.
+fn exit(success: bool) -> ! {
+  // SYS_EXIT with ADP_Stopped_ApplicationExit or ADP_Stopped_RunTimeErrorUnknown
+  let reason: u32 = if success { 0x20026 } else { 0x20023 };
+  unsafe { core::arch::asm!("bkpt 0xAB", in("r0") 0x18u32, in("r1") reason, options(nostack)) };
+  loop {
+    // Halt on real hardware without a debugger attached
+    unsafe { core::arch::asm!("wfi") };
+  }
+}
+
+/// Command lines the firmware is "booted" with, as a bootloader would pass them, and the expected results.
+const BOOT_COMMAND_LINES: [(&str, ParseResult); 3] = [
+  ("--baud 115200 -v '/sbin/my init'", ParseResult::ContinueSuccess),
+  ("--help", ParseResult::ExitSuccess),
+  ("--baud fast", ParseResult::ExitFailure),
+];
+
+#[derive(Debug, Default)]
+struct BootConfig {
+  baud: u32,
+  verbose: bool,
+}
+
+enum Arg { Help, Baud, Verbose, Init }
+const OPTIONS: Opts<Arg> = Opts::new(&[
+  Opt::help_flag(Arg::Help, &["-h", "--help"]).help_text("Show this help and halt."),
+  Opt::value(Arg::Baud, &["--baud"], "rate").required().help_text("Console baud rate."),
+  Opt::flag(Arg::Verbose, &["-v", "--verbose"]).help_text("Log boot progress."),
+  Opt::positional(Arg::Init, "init").help_text("Program to start (default: /sbin/init)."),
+]).with_description("Example firmware configured by its boot command line.");
+
+/// Parse a boot command line, without an allocator or an operating system.
+/// Quoted arguments are unescaped into a buffer on the stack, which is reused for every token.
+fn boot(command_line: &str) -> ParseResult {
+  let mut config = BootConfig::default();
+  let mut buffer = [0; 64];
+  let result = OPTIONS.parse_command_line("firmware", command_line, &mut buffer, |ctx| {
+    match ctx.id {
+      Arg::Help => {
+        let _ = OPTIONS.print_full_help_to(&mut Serial, ctx.program_name);
+        return Ok(ParseControl::Quit);
+      }
+      Arg::Baud    => { config.baud = str::parse(ctx.arg)?; }
+      Arg::Verbose => { config.verbose = true; }
+      Arg::Init    => { let _ = writeln!(Serial, "init: {}", ctx.arg); }
+    }
+    Ok(ParseControl::Continue)
+  }, |program_name, error| {
+    let _ = OPTIONS.print_usage_to::<StandardErrorUsageWriter<'_, Arg>>(&mut Serial, program_name, error);
+  });
+  if matches!(result, ParseResult::ContinueSuccess) {
+    let _ = writeln!(Serial, "booting with {config:?}");
+  }
+  result
+}
+
+fn main() -> ! {
+  let mut success = true;
+  for (command_line, expected) in BOOT_COMMAND_LINES {
+    let _ = writeln!(Serial, "> {command_line}");
+    success &= boot(command_line) == expected;
+  }
+  exit(success)
+}
+
+/// Reset handler, initializes RAM then calls [main].
+#[no_mangle]
+unsafe extern "C" fn reset() -> ! {
+  extern "C" {
+    static mut _sbss: u32;
+    static mut _ebss: u32;
+    static mut _sdata: u32;
+    static mut _edata: u32;
+    static _sidata: u32;
+  }
+  let mut bss = core::ptr::addr_of_mut!(_sbss);
+  while bss < core::ptr::addr_of_mut!(_ebss) {
+    bss.write_volatile(0);
+    bss = bss.add(1);
+  }
+  let (mut data, mut init) = (core::ptr::addr_of_mut!(_sdata), core::ptr::addr_of!(_sidata));
+  while data < core::ptr::addr_of_mut!(_edata) {
+    data.write_volatile(init.read());
+    (data, init) = (data.add(1), init.add(1));
+  }
+  main()
+}
+
+/// Reset vector, placed after the initial stack pointer at the start of flash by `link.x`.
+#[link_section = ".vector_table.reset"]
+#[no_mangle]
+static RESET_VECTOR: unsafe extern "C" fn() -> ! = reset;
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+  let _ = writeln!(Serial, "panic: {}", info.message());
+  exit(false)
+}
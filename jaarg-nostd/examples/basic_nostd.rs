@@ -8,11 +8,8 @@
 
 extern crate alloc;
 
-use jaarg::{
-  ErrorUsageWriter, ErrorUsageWriterContext, HelpWriter, HelpWriterContext, Opt, Opts,
-  ParseControl, ParseResult, StandardErrorUsageWriter, StandardFullHelpWriter
-};
-use jaarg_nostd::{print, println, harness::ExitCode, simplepathbuf::SimplePathBuf};
+use jaarg::{HelpLayout, Opt, Opts, ParseControl, ParseResult, StandardErrorUsageWriter};
+use jaarg_nostd::{println, harness::{terminal_width, ExitCode, StandardErrorWriter, StandardOutWriter}, simplepathbuf::SimplePathBuf};
 
 #[no_mangle]
 #[allow(improper_ctypes_definitions)]
@@ -40,8 +37,9 @@ extern "C" fn safe_main(args: &[&str]) -> ExitCode {
     args.iter().skip(1), |ctx| {
       match ctx.id {
         Arg::Help => {
-          let ctx = HelpWriterContext { options: &OPTIONS, program_name: ctx.program_name };
-          print!("{}", StandardFullHelpWriter::<'_, Arg>::new(ctx));
+          // Wrap the description to the terminal
+          let layout = HelpLayout { width: terminal_width().unwrap_or(80), ..HelpLayout::DEFAULT };
+          OPTIONS.with_help_layout(layout).print_full_help_to(&mut StandardOutWriter, ctx.program_name).unwrap();
           return Ok(ParseControl::Quit);
         }
         Arg::Number => { number = str::parse(ctx.arg)?; }
@@ -50,8 +48,8 @@ extern "C" fn safe_main(args: &[&str]) -> ExitCode {
       }
       Ok(ParseControl::Continue)
     }, |program_name, error| {
-      let ctx = ErrorUsageWriterContext { options: &OPTIONS, program_name, error };
-      print!("{}", StandardErrorUsageWriter::<'_, Arg>::new(ctx));
+      OPTIONS.print_usage_to::<StandardErrorUsageWriter<'_, Arg>>(&mut StandardErrorWriter, program_name, error)
+        .unwrap();
     }
   ) {
     ParseResult::ContinueSuccess => (),
@@ -8,11 +8,8 @@
 
 extern crate alloc;
 
-use jaarg::{
-  alloc::ParseMapResult, ErrorUsageWriter, ErrorUsageWriterContext, HelpWriter, HelpWriterContext,
-  Opt, Opts, StandardErrorUsageWriter, StandardFullHelpWriter
-};
-use jaarg_nostd::{eprint, print, println, harness::ExitCode, simplepathbuf::SimplePathBuf};
+use jaarg::{alloc::ParseMapResult, Opt, Opts, StandardErrorUsageWriter};
+use jaarg_nostd::{println, harness::{ExitCode, StandardErrorWriter, StandardOutWriter}, simplepathbuf::SimplePathBuf};
 
 #[no_mangle]
 #[allow(improper_ctypes_definitions)]
@@ -28,12 +25,11 @@ extern "C" fn safe_main(args: &[&str]) -> ExitCode {
     SimplePathBuf::from(*args.first().unwrap()).basename(),
     args.iter().skip(1),
     |program_name| {
-      let ctx = HelpWriterContext { options: &OPTIONS, program_name };
-      print!("{}", StandardFullHelpWriter::new(ctx));
+      OPTIONS.print_full_help_to(&mut StandardOutWriter, program_name).unwrap();
     },
     |program_name, error| {
-      let ctx = ErrorUsageWriterContext { options: &OPTIONS, program_name, error };
-      eprint!("{}", StandardErrorUsageWriter::new(ctx));
+      OPTIONS.print_usage_to::<StandardErrorUsageWriter<'_, _>>(&mut StandardErrorWriter, program_name, error)
+        .unwrap();
     }
   ) {
     ParseMapResult::Map(map) => map,
@@ -22,6 +22,33 @@ impl FileDescriptor {
   const STDERR: Self = Self(2);
 }
 
+/// Width in columns of the terminal standard output is connected to, if it's a terminal.
+pub fn terminal_width() -> Option<usize> {
+  FileDescriptor::STDOUT.terminal_width()
+}
+
+impl FileDescriptor {
+  /// Query the terminal window size with `TIOCGWINSZ`.
+  #[cfg(not(target_family = "windows"))]
+  fn terminal_width(&self) -> Option<usize> {
+    let mut size = c::winsize::default();
+    match unsafe { c::ioctl(self.0, c::TIOCGWINSZ, &mut size as *mut c::winsize) } {
+      0 if size.ws_col > 0 => Some(size.ws_col as usize),
+      _ => None,
+    }
+  }
+
+  /// Query the visible console window of the handle behind the file descriptor.
+  #[cfg(target_family = "windows")]
+  fn terminal_width(&self) -> Option<usize> {
+    let mut info = c::CONSOLE_SCREEN_BUFFER_INFO::default();
+    match unsafe { c::GetConsoleScreenBufferInfo(c::get_osfhandle(self.0), &mut info) } {
+      0 => None,
+      _ => usize::try_from(info.srWindow.Right - info.srWindow.Left + 1).ok(),
+    }
+  }
+}
+
 pub struct StandardOutWriter;
 impl Write for StandardOutWriter {
   fn write_str(&mut self, s: &str) -> core::fmt::Result {
@@ -168,7 +195,7 @@ pub enum ExitCode {
 }
 
 /// C main entry point, collects argc/argv and calls `safe_main`.
-#[cfg(not(test))]
+#[cfg(all(not(test), not(target_family = "windows")))]
 #[no_mangle]
 pub unsafe extern "C" fn main(argc: core::ffi::c_int, argv: *const *const core::ffi::c_char) -> core::ffi::c_int {
   let mut args = alloc::vec::Vec::<&str>::with_capacity(argc as usize);
@@ -178,8 +205,24 @@ pub unsafe extern "C" fn main(argc: core::ffi::c_int, argv: *const *const core::
   safe_main(&args) as core::ffi::c_int
 }
 
+/// C main entry point for Windows, where argv is in the ANSI code page.
+/// Splits the UTF-16 command line from `GetCommandLineW` instead and calls `safe_main`.
+#[cfg(all(not(test), target_family = "windows"))]
+#[no_mangle]
+pub unsafe extern "C" fn main(_argc: core::ffi::c_int, _argv: *const *const core::ffi::c_char) -> core::ffi::c_int {
+  use alloc::{string::String, vec::Vec};
+  let command_line = c::GetCommandLineW();
+  let len = (0..).take_while(|&i| *command_line.add(i) != 0).count();
+  let command_line = String::from_utf16_lossy(core::slice::from_raw_parts(command_line, len));
+  let args: Vec<String> = jaarg::windows::CommandLineArgs::new(&command_line).collect();
+  let args: Vec<&str> = args.iter().map(String::as_str).collect();
+  safe_main(&args) as core::ffi::c_int
+}
+
 mod c {
   use core::ffi::{c_int, c_void};
+  #[cfg(not(target_family = "windows"))]
+  use core::ffi::{c_ulong, c_ushort};
 
   /// Until size_t is stabilised
   #[allow(non_camel_case_types)]
@@ -206,5 +249,57 @@ mod c {
     #[link_name = "_aligned_free"]
     pub(crate) fn aligned_free(memblock: *mut c_void);
     pub(crate) fn free(ptr: *mut c_void);
+    #[cfg(not(target_family = "windows"))]
+    pub(crate) fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    #[cfg(target_family = "windows")]
+    #[link_name = "_get_osfhandle"]
+    pub(crate) fn get_osfhandle(fd: c_int) -> isize;
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "android"))]
+  pub(crate) const TIOCGWINSZ: c_ulong = 0x5413;
+  #[cfg(not(any(target_os = "linux", target_os = "android", target_family = "windows")))]
+  pub(crate) const TIOCGWINSZ: c_ulong = 0x40087468;
+
+  #[cfg(not(target_family = "windows"))]
+  #[allow(non_camel_case_types)]
+  #[derive(Default)]
+  #[repr(C)]
+  pub(crate) struct winsize {
+    pub(crate) ws_row: c_ushort,
+    pub(crate) ws_col: c_ushort,
+    pub(crate) ws_xpixel: c_ushort,
+    pub(crate) ws_ypixel: c_ushort,
+  }
+
+  #[cfg(target_family = "windows")]
+  #[allow(non_snake_case)]
+  #[derive(Default)]
+  #[repr(C)]
+  pub(crate) struct COORD { pub(crate) X: i16, pub(crate) Y: i16 }
+
+  #[cfg(target_family = "windows")]
+  #[allow(non_snake_case)]
+  #[derive(Default)]
+  #[repr(C)]
+  pub(crate) struct SMALL_RECT { pub(crate) Left: i16, pub(crate) Top: i16, pub(crate) Right: i16, pub(crate) Bottom: i16 }
+
+  #[cfg(target_family = "windows")]
+  #[allow(non_snake_case)]
+  #[derive(Default)]
+  #[repr(C)]
+  pub(crate) struct CONSOLE_SCREEN_BUFFER_INFO {
+    pub(crate) dwSize: COORD,
+    pub(crate) dwCursorPosition: COORD,
+    pub(crate) wAttributes: u16,
+    pub(crate) srWindow: SMALL_RECT,
+    pub(crate) dwMaximumWindowSize: COORD,
+  }
+
+  #[cfg(target_family = "windows")]
+  #[link(name = "kernel32")]
+  extern "system" {
+    pub(crate) fn GetCommandLineW() -> *const u16;
+    pub(crate) fn GetConsoleScreenBufferInfo(console: isize, info: *mut CONSOLE_SCREEN_BUFFER_INFO) -> c_int;
   }
 }
@@ -0,0 +1,44 @@
+/* command_line - jaarg example program parsing a command line string
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use jaarg::{Opt, Opts, ParseControl, ParseResult};
+use std::io::BufRead;
+
+// Set up arguments table
+enum Arg { Help, Repeat, Text }
+const OPTIONS: Opts<Arg> = Opts::new(&[
+  Opt::help_flag(Arg::Help, &["-h", "--help"]).help_text("Show this help."),
+  Opt::value(Arg::Repeat, &["-r", "--repeat"], "count").help_text("Times to repeat the text (default: 1)"),
+  Opt::positional(Arg::Text, "text").required().help_text("Text to echo."),
+]).with_description("Echoes text, reading each command from a line of input.");
+
+/// Run one command line, the same way an exported function in a browser or WASI module would
+/// be handed a string instead of an argument vector.
+fn run(command_line: &str) {
+  let mut repeat = 1;
+  let mut text = String::new();
+  let mut buffer = [0; 256];
+  match OPTIONS.parse_command_line("echo", command_line, &mut buffer, |ctx| {
+    match ctx.id {
+      Arg::Help => {
+        OPTIONS.print_full_help(ctx.program_name);
+        return Ok(ParseControl::Quit);
+      }
+      Arg::Repeat => { repeat = str::parse(ctx.arg)?; }
+      Arg::Text   => { text = ctx.arg.into(); }
+    }
+    Ok(ParseControl::Continue)
+  }, |program_name, error| OPTIONS.eprint_usage::<jaarg::StandardErrorUsageWriter<_>>(program_name, error)) {
+    ParseResult::ContinueSuccess => println!("{}", vec![text; repeat].join(" ")),
+    ParseResult::ExitSuccess | ParseResult::ExitFailure => (),
+  }
+}
+
+fn main() {
+  // Example input: `--repeat 2 'hello world'`
+  for line in std::io::stdin().lock().lines() {
+    run(&line.expect("failed to read input"));
+  }
+}
@@ -0,0 +1,47 @@
+/* multicall - jaarg example program implementing busybox-style applets
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use jaarg::{Multicall, Opt, Opts, ParseControl};
+use std::env;
+use std::process::ExitCode;
+
+fn echo(program_name: &str, args: env::ArgsOs) -> ExitCode {
+  #[derive(Clone, Copy)]
+  enum Arg { Help, NoNewline, Text }
+  const OPTIONS: Opts<Arg> = Opts::new(&[
+    Opt::help_flag(Arg::Help, &["-h", "--help"]).help_text("Show this help and exit."),
+    Opt::flag(Arg::NoNewline, &["-n"]).help_text("Don't output a trailing newline."),
+    Opt::positional(Arg::Text, "text").help_text("Text to output."),
+  ]);
+  let (mut newline, mut text) = (true, String::new());
+  let result = OPTIONS.parse_os(program_name, args, |ctx| {
+    match ctx.id {
+      Arg::Help => {
+        OPTIONS.print_full_help(program_name);
+        return Ok(ParseControl::Quit);
+      }
+      Arg::NoNewline => { newline = false; }
+      Arg::Text => { text = ctx.arg.to_string_lossy().into(); }
+    }
+    Ok(ParseControl::Continue)
+  }, |name, e| OPTIONS.eprint_usage::<jaarg::StandardErrorUsageWriter<'_, _>>(name, e));
+  if let Err(code) = result.into_exit() {
+    return code;
+  }
+  print!("{text}{}", if newline { "\n" } else { "" });
+  ExitCode::SUCCESS
+}
+
+fn r#true(_: &str, _: env::ArgsOs) -> ExitCode { ExitCode::SUCCESS }
+fn r#false(_: &str, _: env::ArgsOs) -> ExitCode { ExitCode::FAILURE }
+
+fn main() -> ExitCode {
+  const APPLETS: Multicall<fn(&str, env::ArgsOs) -> ExitCode> = Multicall::new(&[
+    ("echo", echo),
+    ("true", r#true),
+    ("false", r#false),
+  ]);
+  APPLETS.dispatch_easy()
+}
@@ -0,0 +1,134 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{HandlerReturn, OptExtras, ParseHandlerContext};
+
+/// How [Opts::parse_map_typed](crate::Opts::parse_map_typed) stores an option, declared with
+/// [OptExtras::map_action]. Other parsers pass the option to the handler as usual.
+/// ```
+/// # use jaarg::{MapAction, Opt, OptExtras, Opts};
+/// # use jaarg::alloc::{ParseMapResult, Value};
+/// const OPTIONS: Opts<&str> = Opts::new(&[
+///   Opt::flag("verbose", &["-v"]).extras(&OptExtras::new().map_action(MapAction::Count)),
+///   Opt::value("include", &["-I"], "dir").extras(&OptExtras::new().map_action(MapAction::Append)),
+/// ]);
+/// let ParseMapResult::Map(map) = OPTIONS.parse_map_typed("", ["-v", "-I", "a", "-v", "-I", "b"].iter(), |_| {}, |_, _| {})
+///   else { unreachable!() };
+/// assert_eq!(map["verbose"], Value::Count(2));
+/// assert_eq!(map["include"], Value::List(vec![Value::Str("a".into()), Value::Str("b".into())]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapAction {
+  /// Store true when matched, for flags.
+  SetTrue,
+  /// Store the argument typed by the option's [ValueKind](crate::ValueKind), or a flag's
+  /// [constant](OptExtras::constant), replacing any earlier occurrence.
+  SetValue,
+  /// Collect the argument of every occurrence into a list, typed like [MapAction::SetValue].
+  Append,
+  /// Count the occurrences of the option.
+  Count,
+}
+
+/// The function set with [OptExtras::callback], compared by address so [OptExtras] can still derive [PartialEq].
+pub(crate) struct Callback<ID>(pub(crate) fn(ParseHandlerContext<ID>) -> HandlerReturn<'static>);
+
+impl<ID> Clone for Callback<ID> {
+  fn clone(&self) -> Self { *self }
+}
+
+impl<ID> Copy for Callback<ID> {}
+
+impl<ID> PartialEq for Callback<ID> {
+  fn eq(&self, other: &Self) -> bool {
+    core::ptr::fn_addr_eq(self.0, other.0)
+  }
+}
+
+impl<ID> core::fmt::Debug for Callback<ID> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("Callback")
+  }
+}
+
+impl<ID> OptExtras<'_, ID> {
+  /// Sets how [Opts::parse_map_typed](crate::Opts::parse_map_typed) stores the option, see [MapAction].
+  #[inline]
+  pub const fn map_action(mut self, action: MapAction) -> Self {
+    self.map_action = Some(action);
+    self
+  }
+
+  /// Call `callback` instead of the handler when the option is matched, by every parser,
+  /// so common options like `--version` don't need handler code.
+  /// ```
+  /// # use jaarg::{Opt, OptExtras, Opts, ParseControl, ParseResult};
+  /// const OPTIONS: Opts<()> = Opts::new(&[
+  ///   Opt::flag((), &["--version"]).extras(&OptExtras::new().callback(|_| {
+  ///     println!("1.0");
+  ///     Ok(ParseControl::Quit)
+  ///   })),
+  /// ]);
+  /// assert_eq!(OPTIONS.parse("", ["--version"].iter(), |_| unreachable!(), |_, _| {}), ParseResult::ExitSuccess);
+  /// ```
+  #[inline]
+  pub const fn callback(mut self, callback: fn(ParseHandlerContext<ID>) -> HandlerReturn<'static>) -> Self {
+    self.callback = Some(Callback(callback));
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Opt, Opts, ParseControl, ParseError, ParseResult};
+
+  #[test]
+  fn test_callback() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::flag(0, &["--version"]).extras(&OptExtras::new().callback(|_| Ok(ParseControl::Quit))),
+      Opt::value(1, &["--jobs"], "n").extras(&OptExtras::new().callback(|ctx| match ctx.arg {
+        "0" => Err(ctx.invalid_value("must be at least 1")),
+        _ => Ok(ParseControl::Continue),
+      })),
+      Opt::flag(2, &["-v"]).extras(&OptExtras::new().map_action(MapAction::Count)),
+    ]);
+    // Only options without a callback reach the handler
+    let mut handled = 0;
+    assert_eq!(OPTIONS.parse_slice("", &["--jobs=2", "-v", "--version", "-v"], |ctx| {
+      assert_eq!(*ctx.id, 2);
+      handled += 1;
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ExitSuccess);
+    assert_eq!(handled, 1);
+
+    // Errors from callbacks have the option filled in like handler errors
+    let mut failed = None;
+    assert_eq!(OPTIONS.parse_slice("", &["--jobs", "0"], |_| Ok(ParseControl::Continue),
+      |_, error| failed = Some(error == ParseError::InvalidValue("--jobs", "0", "must be at least 1"))),
+      ParseResult::ExitFailure);
+    assert_eq!(failed, Some(true));
+  }
+
+  #[test]
+  fn test_callback_eq() {
+    fn quit(_: ParseHandlerContext<()>) -> HandlerReturn<'static> { Ok(ParseControl::Quit) }
+    fn stop(_: ParseHandlerContext<()>) -> HandlerReturn<'static> { Ok(ParseControl::Stop) }
+    assert_eq!(OptExtras::new().callback(quit), OptExtras::new().callback(quit));
+    assert_ne!(OptExtras::new().callback(quit), OptExtras::new().callback(stop));
+  }
+
+  #[test]
+  #[should_panic(expected = "Only flags can be set to true")]
+  fn test_value_set_true_disallowed() {
+    Opt::value((), &["-o"], "out").extras(&OptExtras::new().map_action(MapAction::SetTrue));
+  }
+
+  #[test]
+  #[should_panic(expected = "Options with a callback aren't stored")]
+  fn test_callback_map_action_disallowed() {
+    Opt::flag((), &["-v"]).extras(&OptExtras::new().map_action(MapAction::Count).callback(|_| Ok(ParseControl::Quit)));
+  }
+}
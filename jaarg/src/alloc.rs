@@ -5,24 +5,123 @@
 
 extern crate alloc;
 
+use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
-use alloc::string::String;
-use crate::{Opts, ParseControl, ParseError, ParseResult};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::{
+  ErrorUsageWriter, ErrorUsageWriterContext, HandlerResult, HelpWriter, HelpWriterContext, Opt, Opts, OptsIssue, ParseControl,
+  ParseError, ParseHandlerContext, ParseResult, StandardErrorUsageWriter, StandardFullHelpWriter, StandardShortUsageWriter, ValueKind
+};
+use crate::option::OptType;
+use core::str::FromStr;
 
-impl Opts<&'static str> {
+impl<ID: Ord + Copy, const R: usize> Opts<'_, ID, R> {
   /// Parse an iterator of strings as arguments and return the results in a [`BTreeMap`].
+  /// Only the last value of options taking several [value names](crate::OptExtras::value_names) is kept,
+  /// use [Opts::parse_multimap] or [Opts::parse_collect] to keep each of their values.
   ///
   /// Requires `features = ["alloc"]`.
   pub fn parse_map<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
     help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
-  ) -> ParseMapResult {
-    let mut out: BTreeMap<&'static str, String> = BTreeMap::new();
+  ) -> ParseMapResult<ID> {
+    self.parse_map_with(program_name, args, BTreeMap::new(), |out, ctx| {
+      out.insert(*ctx.id, ctx.arg.into());
+      Ok(())
+    }, help, error)
+  }
+
+  /// Parse an iterator of strings as arguments and return the results in a [`BTreeMap`],
+  /// keeping every occurrence of repeated options in the order they were given.
+  /// Flags get an empty string for each occurrence.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn parse_multimap<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseMapResult<ID, Vec<String>> {
+    self.parse_map_with(program_name, args, BTreeMap::new(), |out, ctx| {
+      out.entry(*ctx.id).or_default().push(ctx.arg.into());
+      Ok(())
+    }, help, error)
+  }
+
+  /// Parse an iterator of strings as arguments and return the results in a [`BTreeMap`] of [Value]s,
+  /// typed according to each option's [crate::ValueKind]. Flags are always present in the map,
+  /// as [Value::Bool] holding whether they were specified, except flags carrying a
+  /// [constant](crate::OptExtras::constant) which insert it as a [Value::Str] when present.
+  /// Options with a [MapAction](crate::MapAction) are stored as it describes, counts and lists are always present.
+  /// Options taking several [value names](crate::OptExtras::value_names) store a [Value::List] of the values
+  /// of each occurrence.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn parse_map_typed<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseMapResult<ID, Value> {
+    use crate::MapAction;
+    // Options with a callback never reach the handler, so aren't stored
+    let stored = self.iter().filter(|o| !o.is_help() && o.callback().is_none());
+    let initial = stored.filter_map(|o| match (o.map_action(), o.r#type) {
+      (Some(MapAction::SetTrue), _) => Some((o.id, Value::Bool(false))),
+      (Some(MapAction::Count), _) => Some((o.id, Value::Count(0))),
+      (Some(MapAction::Append), _) => Some((o.id, Value::List(Vec::new()))),
+      (None, OptType::Flag) if o.constant().is_none() => Some((o.id, Value::Bool(false))),
+      _ => None,
+    }).collect();
+    self.parse_map_with(program_name, args, initial, |out, ctx| {
+      // Each value after the first of an option taking several is added to the list started by the first
+      let (several, next) = (ctx.option.value_names().len() > 1, ctx.value_index > 0);
+      let value = || Ok::<_, ParseError>(match (ctx.option.r#type, ctx.option.value_kind) {
+        (OptType::Flag, _) if ctx.option.constant().is_none() => Value::Bool(true),
+        (_, ValueKind::String) => Value::Str(ctx.arg.into()),
+        (_, ValueKind::Integer) => Value::Int(ctx.parse_int()?),
+        (_, ValueKind::Float) => Value::Float(ctx.arg.parse()?),
+        (_, ValueKind::Toggle) => Value::Toggle(ctx.arg.parse()?),
+      });
+      match ctx.option.map_action() {
+        Some(MapAction::SetTrue) => { out.insert(*ctx.id, Value::Bool(true)); }
+        Some(MapAction::Count) => match out.entry(*ctx.id).or_insert(Value::Count(0)) {
+          Value::Count(count) => *count = count.saturating_add(1),
+          other => *other = Value::Count(1),
+        }
+        Some(MapAction::Append) => {
+          let value = value()?;
+          let entry = out.entry(*ctx.id).or_insert(Value::List(Vec::new()));
+          if !matches!(entry, Value::List(_)) {
+            *entry = Value::List(Vec::new());
+          }
+          if let Value::List(list) = entry {
+            match (several, next, list.last_mut()) {
+              (true, true, Some(Value::List(values))) => values.push(value),
+              (true, _, _) => list.push(Value::List(Vec::from([value]))),
+              (false, _, _) => list.push(value),
+            }
+          }
+        }
+        Some(MapAction::SetValue) | None => {
+          let value = value()?;
+          match (several, next, out.get_mut(ctx.id)) {
+            (true, true, Some(Value::List(values))) => values.push(value),
+            (true, _, _) => { out.insert(*ctx.id, Value::List(Vec::from([value]))); }
+            (false, _, _) => { out.insert(*ctx.id, value); }
+          }
+        }
+      }
+      Ok(())
+    }, help, error)
+  }
+
+  fn parse_map_with<'a, V, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    mut out: BTreeMap<ID, V>,
+    mut insert: impl FnMut(&mut BTreeMap<ID, V>, &ParseHandlerContext<ID>) -> HandlerResult<'a, ()>,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseMapResult<ID, V> {
     match self.parse(program_name, args, |ctx| {
       if ctx.option.is_help() {
         help(program_name);
         Ok(ParseControl::Quit)
       } else {
-        out.insert(ctx.id, ctx.arg.into());
+        insert(&mut out, &ctx)?;
         Ok(ParseControl::Continue)
       }
     }, error) {
@@ -33,8 +132,964 @@ impl Opts<&'static str> {
   }
 }
 
-/// The result of parsing commands with [Opts::parse_map].
-pub enum ParseMapResult {
-  Map(BTreeMap<&'static str, String>),
+impl<ID: Copy, const R: usize> Opts<'_, ID, R> {
+  /// Parse an iterator of strings as arguments and return every match in the order it appeared,
+  /// paired with its argument, or [None] for flags without a [constant](crate::OptExtras::constant).
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn parse_collect<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseCollectResult<ID> {
+    let mut out = Vec::new();
+    match self.parse(program_name, args, |ctx| {
+      if ctx.option.is_help() {
+        help(program_name);
+        return Ok(ParseControl::Quit);
+      }
+      out.push((*ctx.id, match ctx.option.r#type {
+        OptType::Flag if ctx.option.constant().is_none() => None,
+        _ => Some(ctx.arg.into()),
+      }));
+      Ok(ParseControl::Continue)
+    }, error) {
+      ParseResult::ContinueSuccess => ParseCollectResult::List(out),
+      ParseResult::ExitSuccess => ParseCollectResult::ExitSuccess,
+      ParseResult::ExitFailure => ParseCollectResult::ExitFailure,
+    }
+  }
+
+  /// Parse an iterator of strings as arguments and return the results as [Matches] for querying.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn parse_matches<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseMatchesResult<ID> {
+    match self.parse_collect(program_name, args, help, error) {
+      ParseCollectResult::List(list) => ParseMatchesResult::Matches(Matches(list)),
+      ParseCollectResult::ExitSuccess => ParseMatchesResult::ExitSuccess,
+      ParseCollectResult::ExitFailure => ParseMatchesResult::ExitFailure,
+    }
+  }
+}
+
+/// The result of parsing commands with [Opts::parse_map], [Opts::parse_multimap] or [Opts::parse_map_typed].
+pub enum ParseMapResult<ID = &'static str, V = String> {
+  Map(BTreeMap<ID, V>),
   ExitSuccess, ExitFailure
 }
+
+/// The result of parsing commands with [Opts::parse_collect].
+pub enum ParseCollectResult<ID = &'static str> {
+  List(Vec<(ID, Option<String>)>),
+  ExitSuccess, ExitFailure
+}
+
+/// The result of parsing commands with [Opts::parse_matches].
+pub enum ParseMatchesResult<ID = &'static str> {
+  Matches(Matches<ID>),
+  ExitSuccess, ExitFailure
+}
+
+/// Parsed arguments that can be queried by option ID, produced by [Opts::parse_matches].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matches<ID = &'static str>(Vec<(ID, Option<String>)>);
+
+impl<ID: PartialEq> Matches<ID> {
+  /// Returns true if the option was matched at least once.
+  pub fn contains(&self, id: ID) -> bool {
+    self.0.iter().any(|(i, _)| *i == id)
+  }
+
+  /// Returns the number of times the option was matched.
+  pub fn count(&self, id: ID) -> usize {
+    self.0.iter().filter(|(i, _)| *i == id).count()
+  }
+
+  /// Gets the argument of the last occurrence of an option, if any, without conversion.
+  pub fn get_str(&self, id: ID) -> Option<&str> {
+    self.values(id).next_back()
+  }
+
+  /// Converts the argument of the last occurrence of an option, if any, to `T`.
+  pub fn get_one<T: FromStr>(&self, id: ID) -> Result<Option<T>, T::Err> {
+    self.get_str(id).map(str::parse).transpose()
+  }
+
+  /// Converts the arguments of every occurrence of an option, in order, to `T`.
+  pub fn get_many<T: FromStr>(&self, id: ID) -> Result<Vec<T>, T::Err> {
+    self.values(id).map(str::parse).collect()
+  }
+
+  /// Every match in the order it appeared, paired with its argument, or [None] for flags.
+  #[inline(always)]
+  pub fn as_slice(&self) -> &[(ID, Option<String>)] {
+    &self.0
+  }
+
+  fn values(&self, id: ID) -> impl DoubleEndedIterator<Item = &str> {
+    self.0.iter().filter(move |(i, _)| *i == id).filter_map(|(_, arg)| arg.as_deref())
+  }
+}
+
+impl<ID> Matches<ID> {
+  /// Display the matches as shell variable assignments named by `name`, see [ShellExports].
+  pub fn shell_exports<'m, N: core::fmt::Display>(&'m self, name: impl Fn(&ID) -> N + Clone + 'm
+  ) -> ShellExports<impl Iterator<Item = (N, Option<&'m str>)> + Clone + 'm> {
+    ShellExports(self.0.iter().map(move |(id, arg)| (name(id), arg.as_deref())))
+  }
+}
+
+impl<ID> From<Matches<ID>> for Vec<(ID, Option<String>)> {
+  fn from(matches: Matches<ID>) -> Self {
+    matches.0
+  }
+}
+
+/// Writes pairs of variable names and arguments as shell assignments, for helpers that are meant to be
+/// used as `eval "$(tool ...)"`. Each variable is exported, flags (arguments of [None]) are set to `1`,
+/// and arguments are quoted with [shlex::quote](crate::shlex::quote). Names are written as is.
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use jaarg::alloc::ShellExports;
+/// let map = BTreeMap::from([("name", "jaarg"), ("path", "a b")]);
+/// let exports = ShellExports(map.iter().map(|(id, arg)| (id.to_uppercase(), Some(arg))));
+/// assert_eq!(exports.to_string(), "NAME=jaarg; export NAME;\nPATH='a b'; export PATH;\n");
+/// ```
+///
+/// Requires `features = ["alloc"]`.
+#[derive(Debug, Clone)]
+pub struct ShellExports<I>(pub I);
+
+impl<N: core::fmt::Display, S: AsRef<str>, I: Iterator<Item = (N, Option<S>)> + Clone> core::fmt::Display
+  for ShellExports<I> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for (name, arg) in self.0.clone() {
+      match arg {
+        Some(arg) => write!(f, "{name}={}; ", crate::shlex::quote(arg.as_ref()))?,
+        None => write!(f, "{name}=1; ")?,
+      }
+      writeln!(f, "export {name};")?;
+    }
+    Ok(())
+  }
+}
+
+/// A typed argument value produced by [Opts::parse_map_typed].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Value {
+  /// A string argument.
+  Str(String),
+  /// A signed integer argument, for options declared as [ValueKind::Integer].
+  Int(i64),
+  /// A floating-point argument, for options declared as [ValueKind::Float].
+  Float(f64),
+  /// A flag, true if it was specified.
+  Bool(bool),
+  /// A switch argument, for options declared as [ValueKind::Toggle].
+  Toggle(crate::Toggle),
+  /// The number of occurrences, for options with [MapAction::Count](crate::MapAction::Count).
+  Count(u32),
+  /// The argument of every occurrence, for options with [MapAction::Append](crate::MapAction::Append),
+  /// or the values of an option taking several.
+  List(Vec<Value>),
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Audit the options table with the same checks as [Opts::assert_valid],
+  /// returning a list of every problem found instead of stopping at the first.
+  /// Intended for use in unit tests.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn validate(&self) -> Vec<OptsIssue<'o>> {
+    (0..).map_while(|skip| self.issue(skip)).collect()
+  }
+}
+
+/// A change between two versions of an options table that can break existing invocations,
+/// found by [Opts::breaking_changes].
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum BreakingChange<'o> {
+  /// The option or positional argument was removed.
+  Removed(&'o str),
+  /// The option no longer accepts this name, eg; because it was renamed.
+  RemovedName(&'o str),
+  /// The option or positional argument is required, but was optional or didn't exist before.
+  NewlyRequired(&'o str),
+  /// The option changed between a flag, a value option, and a positional argument.
+  ChangedType(&'o str),
+  /// The positional argument is filled in a different order.
+  MovedPositional(&'o str),
+  /// The option takes a different number of values.
+  ChangedArity(&'o str),
+  /// The option accepts fewer values, because its [ValueKind] narrowed, eg; from a string to an integer,
+  /// or because it now only accepts choices.
+  ChangedKind(&'o str),
+  /// The option no longer accepts this choice or synonym.
+  RemovedChoice(&'o str, &'o str),
+}
+
+impl core::fmt::Display for BreakingChange<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Removed(n) => write!(f, "Option '{n}' was removed"),
+      Self::RemovedName(n) => write!(f, "Option name '{n}' was removed"),
+      Self::NewlyRequired(n) => write!(f, "Option '{n}' is now required"),
+      Self::ChangedType(n) => write!(f, "Option '{n}' changed type"),
+      Self::MovedPositional(n) => write!(f, "Positional argument '{n}' was moved"),
+      Self::ChangedArity(n) => write!(f, "Option '{n}' takes a different number of values"),
+      Self::ChangedKind(n) => write!(f, "Option '{n}' accepts fewer values"),
+      Self::RemovedChoice(n, c) => write!(f, "Option '{n}' no longer accepts '{c}'"),
+    }
+  }
+}
+
+impl<'o, ID: PartialEq, const R: usize> Opts<'o, ID, R> {
+  /// Compare the options table with a `newer` version of it, returning every change that could break
+  /// existing invocations, eg; to gate releases on backward compatibility of the command-line interface.
+  /// Options are matched between the tables by ID, so IDs must be unique and stable across versions.
+  /// Serialized specs can be compared by deserializing them into [Opts] first.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn breaking_changes<const N: usize>(&self, newer: &Opts<'o, ID, N>) -> Vec<BreakingChange<'o>> {
+    let is_positional = |option: &&Opt<'o, ID>| matches!(option.r#type, OptType::Positional);
+    let old_positionals: Vec<_> = self.iter().filter(is_positional).map(|o| &o.id).collect();
+    let new_positionals: Vec<_> = newer.iter().filter(is_positional).map(|o| &o.id).collect();
+    let mut changes = Vec::new();
+    for option in self.iter() {
+      let Some(new) = newer.iter().find(|o| o.id == option.id) else {
+        changes.push(BreakingChange::Removed(option.display_name()));
+        continue;
+      };
+      if new.r#type != option.r#type {
+        changes.push(BreakingChange::ChangedType(option.display_name()));
+        continue;
+      }
+      if !matches!(option.r#type, OptType::Flag) {
+        let (choices, new_choices) = (option.choices(), new.choices());
+        if narrows(option.value_kind, new.value_kind) || (choices.is_empty() && !new_choices.is_empty()) {
+          changes.push(BreakingChange::ChangedKind(option.display_name()));
+        }
+        if !new_choices.is_empty() {
+          changes.extend(choices.iter().flat_map(|c| core::iter::once(&c.value).chain(c.synonyms))
+            .filter(|&value| !new_choices.iter().any(|c| c.matches(value)))
+            .map(|&value| BreakingChange::RemovedChoice(option.display_name(), value)));
+        }
+      }
+      if let OptType::Positional = option.r#type {
+        let position = |ids: &[&ID]| ids.iter().position(|&id| *id == option.id);
+        if position(&old_positionals) != position(&new_positionals) {
+          changes.push(BreakingChange::MovedPositional(option.display_name()));
+        }
+      } else {
+        changes.extend(option.names().iter().filter(|name| !new.names().contains(name))
+          .map(|&name| BreakingChange::RemovedName(name)));
+        if new.value_names().len() != option.value_names().len() {
+          changes.push(BreakingChange::ChangedArity(option.display_name()));
+        }
+      }
+      if new.is_required() && !option.is_required() {
+        changes.push(BreakingChange::NewlyRequired(new.display_name()));
+      }
+    }
+    changes.extend(newer.iter().filter(|new| new.is_required() && !self.iter().any(|o| o.id == new.id))
+      .map(|new| BreakingChange::NewlyRequired(new.display_name())));
+    changes
+  }
+}
+
+/// Returns true if arguments of the `old` kind can fail to parse as the `new` kind.
+fn narrows(old: ValueKind, new: ValueKind) -> bool {
+  old != new && !matches!((old, new), (_, ValueKind::String) | (ValueKind::Integer, ValueKind::Float))
+}
+
+impl<ID: PartialEq, const R: usize> Opts<'_, ID, R> {
+  /// Render pairs of option IDs and arguments back into argument tokens that parse to the same results,
+  /// the inverse of [Opts::parse_collect]. A [Matches] can be rendered by converting it into a [Vec].
+  /// Options are written using their first long name, with any argument attached by an equals sign,
+  /// except options taking several [value names](crate::OptExtras::value_names) which are followed by each value
+  /// as its own token, taken from that many consecutive pairs.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn unparse<S: AsRef<str>>(&self, values: impl IntoIterator<Item = (ID, Option<S>)>
+  ) -> Result<Vec<String>, &'static str> {
+    let mut args = Vec::new();
+    // The option taking several values whose remaining values are still to come, and how many
+    let mut pending: Option<(&ID, usize)> = None;
+    for (id, arg) in values {
+      if let Some((option, remaining)) = pending.take() {
+        let Some(arg) = arg.filter(|_| id == *option) else {
+          return Err("Options taking several values require every value");
+        };
+        args.push(arg.as_ref().into());
+        pending = (remaining > 1).then_some((option, remaining - 1));
+        continue;
+      }
+      // Flags sharing an ID are told apart by the constant they carry
+      let option = self.iter().filter(|o| o.id == id)
+        .find(|o| o.constant().is_none() || o.constant() == arg.as_ref().map(AsRef::as_ref))
+        .or_else(|| self.iter().find(|o| o.id == id)).ok_or("Unknown option ID")?;
+      let name = option.first_long_name().unwrap_or(option.first_name());
+      match (&option.r#type, arg) {
+        (OptType::Flag, arg) if arg.as_ref().map(AsRef::as_ref) == option.constant() => args.push(name.into()),
+        (OptType::Flag, _) if option.constant().is_some() => return Err("No flag carries that constant"),
+        (OptType::Flag, _) => return Err("Flags don't take an argument"),
+        (OptType::Value, Some(arg)) => match option.value_names().len() {
+          several @ 2.. => {
+            args.extend([name.into(), arg.as_ref().into()]);
+            pending = Some((&option.id, several - 1));
+          }
+          _ => args.push(format!("{name}={}", arg.as_ref())),
+        }
+        (OptType::Value, None) => return Err("Value options require an argument"),
+        (OptType::Positional, Some(arg)) => if self.starts_with_flag(arg.as_ref()) {
+          return Err("Positional arguments can't start with a flag character");
+        } else {
+          args.push(arg.as_ref().into());
+        }
+        (OptType::Positional, None) => return Err("Positional arguments require an argument"),
+      }
+    }
+    match pending {
+      Some(_) => Err("Options taking several values require every value"),
+      None => Ok(args),
+    }
+  }
+
+  /// Render pairs of option IDs and arguments as a command line string, quoted for a POSIX shell
+  /// or [shlex::tokenize](crate::shlex::tokenize), see [Opts::unparse].
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn unparse_command_line<S: AsRef<str>>(&self, values: impl IntoIterator<Item = (ID, Option<S>)>
+  ) -> Result<String, &'static str> {
+    let args = self.unparse(values)?;
+    Ok(args.iter().map(|arg| crate::shlex::quote(arg).to_string()).collect::<Vec<_>>().join(" "))
+  }
+}
+
+impl<ID, const R: usize> Opts<'_, ID, R> {
+  /// Render the full help text from the standard full help writer into a [String],
+  /// eg; for showing in a GUI or snapshot testing.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn full_help_string(&self, program_name: &str) -> String {
+    StandardFullHelpWriter::new(HelpWriterContext { options: self, program_name }).to_string()
+  }
+
+  /// Render the short usage line from the standard short usage writer into a [String].
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn short_usage_string(&self, program_name: &str) -> String {
+    StandardShortUsageWriter::new(HelpWriterContext { options: self, program_name }).to_string()
+  }
+
+  /// Render `error` from the standard error writer into a [String], with any usage it adds, as printed by
+  /// [Opts::parse_easy](crate::Opts::parse_easy), eg; for logging parse failures.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn error_string(&self, program_name: &str, error: ParseError) -> String {
+    StandardErrorUsageWriter::new(ErrorUsageWriterContext { options: self, program_name, error }).to_string()
+  }
+}
+
+impl Opts<'static, char> {
+  /// Build an options table that's leaked for the rest of the program from a getopt(3) style optstring.
+  ///
+  /// Options in an optstring such as `"ab:c"` take a value when followed by `:`,
+  /// optional `(name, short)` pairs add `--name` to existing short options.
+  /// Each option's ID is its short option character.
+  /// Leading `+` and `:` mode characters are ignored, optional arguments (`::`) are not supported.
+  ///
+  /// The generated names and option list are never freed, as [Opts] only borrows them,
+  /// so this is intended to be called once at startup.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn from_optstring(optstring: &str, long_options: &[(&str, char)]) -> Result<Self, &'static str> {
+    let mut specs: Vec<(char, bool, Vec<String>)> = Vec::new();
+    let mut chars = optstring.trim_start_matches(['+', ':']).chars().peekable();
+    while let Some(c) = chars.next() {
+      if !c.is_ascii_graphic() || c == '-' || c == ':' {
+        return Err("Invalid option character in optstring");
+      }
+      if specs.iter().any(|(id, ..)| *id == c) {
+        return Err("Option characters must be unique");
+      }
+      let value = chars.next_if_eq(&':').is_some();
+      if value && chars.next_if_eq(&':').is_some() {
+        return Err("Optional arguments are not supported");
+      }
+      specs.push((c, value, Vec::from([format!("-{c}")])));
+    }
+    for &(name, id) in long_options {
+      if name.is_empty() || name.starts_with('-') {
+        return Err("Long option names must be non-empty and given without dashes");
+      }
+      if long_options.iter().filter(|(other, _)| *other == name).count() > 1 {
+        return Err("Long option names must be unique");
+      }
+      let Some((.., names)) = specs.iter_mut().find(|(c, ..)| *c == id) else {
+        return Err("Long option refers to a character missing from the optstring");
+      };
+      names.push(format!("--{name}"));
+    }
+
+    let options = specs.into_iter().map(|(id, value, names)| {
+      let names = Vec::leak(names.into_iter().map(|n| &*Box::leak(n.into_boxed_str())).collect());
+      if value { Opt::value(id, names, "arg") } else { Opt::flag(id, names) }
+    }).collect();
+    Ok(Opts::new(Vec::leak(options)))
+  }
+}
+
+impl Opts<'static, &'static str> {
+  /// Build an options table that's leaked for the rest of the program from docopt style help text,
+  /// so the help text is the specification.
+  ///
+  /// Positional arguments are taken from `<name>` placeholders in the lines following `Usage:`
+  /// that aren't the value of an option, they're required unless they first appear inside `[...]`.
+  /// Options are taken from lines starting with `-`, with the names and value placeholder separated
+  /// from the help text by two or more spaces, eg; `-n, --number=<value>  Number value.`.
+  /// `-h`/`--help` becomes the help flag, any text preceding `Usage:` becomes the description.
+  /// Each option's ID is its last name without leading dashes or angle brackets.
+  ///
+  /// Names and help are borrowed from the text, while the option list and the name lists are never freed,
+  /// as [Opts] only borrows them, so this is intended to be called once at startup.
+  ///
+  /// Requires `features = ["alloc"]`.
+  pub fn from_usage(text: &'static str) -> Result<Self, &'static str> {
+    let mut options: Vec<Opt<'static, &'static str>> = Vec::new();
+    let mut usage: Vec<&'static str> = Vec::new();
+    let mut description = None;
+    let mut in_usage = false;
+    let mut offset = 0;
+    for raw_line in text.split_inclusive('\n') {
+      let line = raw_line.trim();
+      if let Some(pattern) = line.get(..6).filter(|p| p.eq_ignore_ascii_case("usage:")).and(line.get(6..)) {
+        if usage.is_empty() {
+          description = text.get(..offset).unwrap_or_default().trim().split("\n\n").next().filter(|d| !d.is_empty());
+        }
+        in_usage = true;
+        usage.push(pattern);
+      } else if line.starts_with('-') {
+        in_usage = false;
+        options.push(Self::usage_option(line)?);
+      } else if line.is_empty() {
+        in_usage = false;
+      } else if in_usage {
+        usage.push(line);
+      }
+      offset += raw_line.len();
+    }
+
+    for (i, option) in options.iter().enumerate() {
+      if options.iter().take(i).any(|o| o.names().iter().any(|n| option.names().contains(n))) {
+        return Err("Option names must be unique");
+      }
+    }
+    let mut positionals = Vec::new();
+    for pattern in usage {
+      Self::usage_positionals(pattern, &options, &mut positionals)?;
+    }
+    positionals.append(&mut options);
+    let opts = Opts::new(Vec::leak(positionals));
+    Ok(match description {
+      Some(description) => opts.with_description(description),
+      None => opts,
+    })
+  }
+
+  fn usage_positionals(pattern: &'static str, options: &[Opt<'static, &'static str>],
+    positionals: &mut Vec<Opt<'static, &'static str>>,
+  ) -> Result<(), &'static str> {
+    let mut depth = 0usize;
+    let mut rest = pattern;
+    while let Some(i) = rest.find(['[', ']', '<']) {
+      let (before, after) = rest.split_at(i);
+      let mut after = after.chars();
+      let bracket = after.next();
+      rest = after.as_str();
+      match bracket {
+        Some('[') => depth += 1,
+        Some(']') => depth = depth.checked_sub(1).ok_or("Unbalanced brackets in usage")?,
+        _ => {
+          let (name, next) = rest.split_once('>').ok_or("Unterminated placeholder in usage")?;
+          rest = next;
+          // Placeholders following the name of an option that takes a value are that option's value
+          let word = before.trim_end_matches([' ', '=']).rsplit([' ', '[', '(']).next().unwrap_or("");
+          let is_value = options.iter().any(|o| o.value_name().is_some() && o.names().contains(&word));
+          if !is_value && !name.is_empty() && !positionals.iter().any(|o| o.id == name) {
+            let option = Opt::positional(name, name);
+            positionals.push(if depth == 0 { option.required() } else { option });
+          }
+        }
+      }
+    }
+    if depth != 0 { Err("Unbalanced brackets in usage") } else { Ok(()) }
+  }
+
+  fn usage_option(line: &'static str) -> Result<Opt<'static, &'static str>, &'static str> {
+    let (spec, help) = match line.find("  ").or_else(|| line.find('\t')) {
+      Some(i) => line.split_at(i),
+      None => (line, ""),
+    };
+    let help = Some(help.trim()).filter(|h| !h.is_empty());
+    let mut names = Vec::new();
+    let mut value_name = None;
+    for token in spec.split([',', ' ']).filter(|t| !t.is_empty()) {
+      if token.starts_with('-') {
+        let (name, value) = match token.split_once('=') {
+          Some((name, value)) => (name, Some(value)),
+          None => (token, None),
+        };
+        if name.trim_start_matches('-').is_empty() {
+          return Err("Option names must not be empty");
+        }
+        names.push(name);
+        value_name = value.or(value_name);
+      } else if value_name.is_none() {
+        value_name = Some(token);
+      } else {
+        return Err("Unexpected token in option specification");
+      }
+    }
+    let id = names.last().ok_or("Option specification has no names")?.trim_start_matches('-');
+    let is_help = names.iter().any(|n| *n == "-h" || *n == "--help");
+    let names = Vec::leak(names);
+    let option = match (value_name, is_help) {
+      (Some(value_name), _) => Opt::value(id, names,
+        value_name.strip_prefix('<').and_then(|v| v.strip_suffix('>')).unwrap_or(value_name)),
+      (None, true) => Opt::help_flag(id, names),
+      (None, false) => Opt::flag(id, names),
+    };
+    Ok(match help {
+      Some(help) => option.help_text(help),
+      None => option,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Choice, Opt, OptExtras};
+
+  #[test]
+  fn test_shell_exports() {
+    const OPTIONS: Opts<&str> = Opts::new(&[
+      Opt::flag("--dry-run", &["-n", "--dry-run"]),
+      Opt::value("--name", &["--name"], "name"),
+    ]);
+    let ParseMatchesResult::Matches(matches) = OPTIONS.parse_matches("", ["--name", "it's", "-n"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    let name = |id: &&str| id.trim_start_matches('-').replace('-', "_").to_uppercase();
+    assert_eq!(matches.shell_exports(name).to_string(),
+      "NAME='it'\\''s'; export NAME;\nDRY_RUN=1; export DRY_RUN;\n");
+  }
+
+  #[test]
+  fn test_help_strings() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::help_flag((), &["-h", "--help"]).help_text("Show help"),
+      Opt::positional((), "file").required(),
+    ]);
+    assert_eq!(OPTIONS.full_help_string("test"), "Usage: test [-h|--help] <file>\n\n\
+      Positional arguments:\n  file\n\n\
+      Options:\n  -h | --help .. Show help\n");
+    assert_eq!(OPTIONS.short_usage_string("test"), "Usage: test [-h|--help] <file>");
+    assert_eq!(OPTIONS.error_string("test", ParseError::UnknownOption("-x")), "test: Unrecognised option '-x'\n");
+    assert_eq!(OPTIONS.error_string("test", ParseError::RequiredPositional("file")),
+      "test: Missing required positional argument 'file'\n\
+      Usage: test [-h|--help] <file>\n\
+      Run 'test --help' to view all available options.\n");
+  }
+
+  #[test]
+  fn test_unparse() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::flag(0, &["-v", "--verbose"]),
+      Opt::value(1, &["-o"], "out"),
+      Opt::positional(2, "file"),
+      Opt::flag(3, &["--json"]).extras(&OptExtras::new().constant("json")),
+      Opt::flag(3, &["--yaml"]).extras(&OptExtras::new().constant("yaml")),
+    ]);
+    let args = ["-v", "-o", "-a b", "in put", "--yaml", "-o=x=y"];
+    let ParseMatchesResult::Matches(matches) = OPTIONS.parse_matches("", args.iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    let values = Vec::from(matches.clone());
+    let unparsed = OPTIONS.unparse(values.clone()).unwrap();
+    assert_eq!(unparsed, ["--verbose", "-o=-a b", "in put", "--yaml", "-o=x=y"]);
+    let ParseMatchesResult::Matches(reparsed) = OPTIONS.parse_matches("", unparsed.iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(reparsed, matches);
+    assert_eq!(OPTIONS.unparse_command_line(values).unwrap(), "--verbose '-o=-a b' 'in put' --yaml -o=x=y");
+
+    assert_eq!(OPTIONS.unparse([(4, None::<&str>)]), Err("Unknown option ID"));
+    assert_eq!(OPTIONS.unparse([(3, Some("toml"))]), Err("No flag carries that constant"));
+    assert_eq!(OPTIONS.unparse([(0, Some(""))]), Err("Flags don't take an argument"));
+    assert_eq!(OPTIONS.unparse([(1, None::<&str>)]), Err("Value options require an argument"));
+    assert_eq!(OPTIONS.unparse([(2, Some("-x"))]), Err("Positional arguments can't start with a flag character"));
+  }
+
+  #[test]
+  fn test_unparse_multiple_values() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--copy"], "SRC").extras(&OptExtras::new().value_names(&["SRC", "DST"])),
+      Opt::flag(1, &["-v"]),
+    ]);
+    let args = ["--copy", "a", "-b", "-v", "--copy=c", "d"];
+    let ParseCollectResult::List(list) = OPTIONS.parse_collect("", args.iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    let unparsed = OPTIONS.unparse(list.clone()).unwrap();
+    assert_eq!(unparsed, ["--copy", "a", "-b", "-v", "--copy", "c", "d"]);
+    let ParseCollectResult::List(reparsed) = OPTIONS.parse_collect("", unparsed.iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(reparsed, list);
+
+    for values in [&[(0, Some("a"))][..], &[(0, Some("a")), (1, None)], &[(0, Some("a")), (0, None)]] {
+      assert_eq!(OPTIONS.unparse(values.iter().copied()), Err("Options taking several values require every value"));
+    }
+  }
+
+  #[test]
+  fn test_validate_clean() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::help_flag((), &["-h", "--help"]),
+      Opt::positional((), "in").required(),
+      Opt::positional((), "out"),
+      Opt::value((), &["-o", "--output"], "path"),
+      Opt::flag((), &["/f"]),
+    ]).with_flag_chars("-/");
+    assert_eq!(OPTIONS.validate(), []);
+  }
+
+  #[test]
+  fn test_validate_issues() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::help_flag((), &["-h", "--help"]),
+      Opt::positional((), "one").group_with_next(),
+      Opt::positional((), "two").required(),
+      Opt::value((), &["-o", "--output"], "path"),
+      Opt::flag((), &["/o", "/x", "-"]),
+      Opt::flag((), &[""]),
+      Opt::help_flag((), &["-?"]),
+      Opt::positional((), "rest").collect_extra(),
+      Opt::positional((), "last").group_with_next(),
+    ]).with_flag_chars("-/");
+    assert_eq!(OPTIONS.validate(), [
+      OptsIssue::RequiredAfterOptional("two"),
+      OptsIssue::GroupedRequired("two"),
+      OptsIssue::DuplicateName("/o"),
+      OptsIssue::BareFlagChar("-"),
+      OptsIssue::Unnamed(5),
+      OptsIssue::MultipleHelp("-?"),
+      OptsIssue::ExtraNotLast("rest"),
+      OptsIssue::UnfinishedGroup("last"),
+    ]);
+    assert_eq!(Opts::new(&[Opt::flag((), &["/x"])]).validate(), [OptsIssue::MissingFlagChar("/x")]);
+    assert_eq!(OptsIssue::DuplicateName("/o").to_string(),
+      "Option name '/o' matches the same arguments as a preceding name");
+  }
+
+  #[test]
+  fn test_breaking_changes() {
+    const V1: Opts<&str> = Opts::new(&[
+      Opt::help_flag("help", &["-h", "--help"]),
+      Opt::flag("verbose", &["-v", "--verbose"]),
+      Opt::value("output", &["-o", "--output"], "path"),
+      Opt::flag("color", &["--color"]),
+      Opt::flag("quiet", &["-q"]),
+      Opt::positional("in", "in").required(),
+      Opt::positional("out", "out"),
+    ]);
+    const V2: Opts<&str> = Opts::new(&[
+      Opt::help_flag("help", &["-h", "--help"]),
+      Opt::flag("verbose", &["-v", "--verbose", "--loud"]),
+      Opt::value("output", &["-o", "--out"], "path").required(),
+      Opt::toggle("color", &["--color"]),
+      Opt::value("format", &["-f"], "format").required(),
+      Opt::positional("out", "out"),
+      Opt::positional("in", "in").extras(&OptExtras::new().metavar("INPUT")).required(),
+      Opt::flag("new", &["-n"]),
+    ]);
+    assert_eq!(V1.breaking_changes(&V1), []);
+    // Adding names and optional options is compatible, removing either isn't
+    assert_eq!(V1.breaking_changes(&Opts::new(&V2.options[..2])), [
+      BreakingChange::Removed("-o"), BreakingChange::Removed("--color"), BreakingChange::Removed("-q"),
+      BreakingChange::Removed("in"), BreakingChange::Removed("out"),
+    ]);
+    assert_eq!(V1.breaking_changes(&V2), [
+      BreakingChange::RemovedName("--output"), BreakingChange::NewlyRequired("-o"),
+      BreakingChange::ChangedType("--color"),
+      BreakingChange::Removed("-q"),
+      BreakingChange::MovedPositional("in"),
+      BreakingChange::MovedPositional("out"),
+      BreakingChange::NewlyRequired("-f"),
+    ]);
+    const PATH_MODE: Opts<&str> = Opts::new(&[
+      Opt::value("output", &["-o", "--output"], "path").extras(&OptExtras::new().value_names(&["path", "mode"])),
+    ]);
+    assert!(V1.breaking_changes(&PATH_MODE).contains(&BreakingChange::ChangedArity("-o")));
+  }
+
+  #[test]
+  fn test_breaking_changes_values() {
+    const V1: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-n"], "n").value_kind(ValueKind::Integer),
+      Opt::value(1, &["--color"], "when")
+        .extras(&OptExtras::new().choices(&[Choice::new("always").synonyms(&["yes"]), Choice::new("never")])),
+      Opt::positional(2, "shape"),
+    ]);
+    // Widening is compatible, eg; integers still parse as floats
+    assert_eq!(V1.breaking_changes(&Opts::new(&[
+      Opt::value(0, &["-n"], "n").value_kind(ValueKind::Float),
+      Opt::value(1, &["--color"], "when").extras(&OptExtras::new()
+        .choices(&[Choice::new("always").synonyms(&["yes"]), Choice::new("never"), Choice::new("auto")])),
+      Opt::positional(2, "shape"),
+    ])), []);
+    assert_eq!(V1.breaking_changes(&Opts::new(&[
+      Opt::value(0, &["-n"], "n").value_kind(ValueKind::Toggle),
+      Opt::value(1, &["--color"], "when").extras(&OptExtras::new().choices(&[Choice::new("always")])),
+      Opt::positional(2, "shape").extras(&OptExtras::new().choices(&[Choice::new("circle")])),
+    ])), [
+      BreakingChange::ChangedKind("-n"),
+      BreakingChange::RemovedChoice("--color", "yes"), BreakingChange::RemovedChoice("--color", "never"),
+      BreakingChange::ChangedKind("shape"),
+    ]);
+    assert_eq!(BreakingChange::RemovedChoice("--color", "never").to_string(), "Option '--color' no longer accepts 'never'");
+  }
+
+  #[test]
+  fn test_parse_map_typed() {
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::help_flag("help", &["-h"]),
+      Opt::flag("flag", &["-f"]),
+      Opt::flag("absent", &["-a"]),
+      Opt::value("int", &["-i"], "int").value_kind(ValueKind::Integer),
+      Opt::value("float", &["-x"], "float").value_kind(ValueKind::Float),
+      Opt::toggle("toggle", &["-t"]),
+      Opt::positional("str", "str"),
+      Opt::flag("format", &["--json"]).extras(&OptExtras::new().constant("json")),
+      Opt::flag("format", &["--yaml"]).extras(&OptExtras::new().constant("yaml")),
+    ]);
+    let ParseMapResult::Map(map) = OPTIONS.parse_map_typed("", ["-f", "-i", "-42", "-x=0.5", "-t", "no", "--yaml", "pizza"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map, BTreeMap::from([
+      ("flag", Value::Bool(true)),
+      ("absent", Value::Bool(false)),
+      ("int", Value::Int(-42)),
+      ("float", Value::Float(0.5)),
+      ("toggle", Value::Toggle(crate::Toggle::Off)),
+      ("str", Value::Str("pizza".into())),
+      ("format", Value::Str("yaml".into())),
+    ]));
+
+    assert!(matches!(OPTIONS.parse_map_typed("", ["-i", "4.2"].iter(), |_| panic!("unreachable"),
+      |_, error| assert!(matches!(error, ParseError::ArgumentError("-i", "4.2", crate::ParseErrorKind::InvalidInteger)))),
+      ParseMapResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_parse_multimap() {
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::flag("flag", &["-f"]),
+      Opt::value("include", &["-I"], "dir"),
+      Opt::positional("file", "file"),
+    ]);
+    let ParseMapResult::Map(map) = OPTIONS.parse_multimap("", ["-I", "a", "-f", "-I=b", "-f", "c"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map, BTreeMap::from([
+      ("flag", ["", ""].map(String::from).to_vec()),
+      ("include", ["a", "b"].map(String::from).to_vec()),
+      ("file", ["c"].map(String::from).to_vec()),
+    ]));
+  }
+
+  #[test]
+  fn test_parse_map_multiple_values() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--copy"], "SRC").extras(&OptExtras::new().value_names(&["SRC", "DST"])),
+      Opt::value(1, &["--move"], "SRC")
+        .extras(&OptExtras::new().value_names(&["SRC", "DST"]).map_action(crate::MapAction::Append)),
+    ]);
+    const ARGS: [&str; 6] = ["--copy", "a b", "c", "--move=d", "e", "--move"];
+    let ParseMapResult::Map(map) = OPTIONS.parse_map("", ARGS.iter().chain(["f", "g"].iter()),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map, BTreeMap::from([(0, "c".into()), (1, "g".into())]));
+    let ParseMapResult::Map(map) = OPTIONS.parse_multimap("", ARGS.iter().chain(["f", "g"].iter()),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map[&0], ["a b", "c"]);
+    assert_eq!(map[&1], ["d", "e", "f", "g"]);
+
+    let ParseMapResult::Map(map) = OPTIONS.parse_map_typed("", ARGS.iter().chain(["f", "g"].iter()),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    let list = |values: &[&str]| Value::List(values.iter().map(|v| Value::Str(v.to_string())).collect());
+    assert_eq!(map[&0], list(&["a b", "c"]));
+    assert_eq!(map[&1], Value::List(Vec::from([list(&["d", "e"]), list(&["f", "g"])])));
+  }
+
+  #[test]
+  fn test_parse_map_enum_ids() {
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    enum Arg { Flag, Value, Positional }
+    const OPTIONS: Opts<Arg> = Opts::new(&[
+      Opt::flag(Arg::Flag, &["-f"]),
+      Opt::value(Arg::Value, &["-v"], "value"),
+      Opt::positional(Arg::Positional, "positional"),
+    ]);
+    let ParseMapResult::Map(map) = OPTIONS.parse_map("", ["-v", "a", "b"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map, BTreeMap::from([(Arg::Value, "a".into()), (Arg::Positional, "b".into())]));
+  }
+
+  #[test]
+  fn test_parse_map_actions() {
+    use crate::MapAction;
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::flag("dry", &["-n"]).extras(&OptExtras::new().map_action(MapAction::SetTrue)),
+      Opt::flag("quiet", &["-q"]).extras(&OptExtras::new().map_action(MapAction::Count)),
+      Opt::flag("verbose", &["-v"]).extras(&OptExtras::new().map_action(MapAction::Count)),
+      Opt::value("port", &["-p"], "port").value_kind(ValueKind::Integer).extras(&OptExtras::new().map_action(MapAction::Append)),
+      Opt::value("tag", &["-t"], "tag").extras(&OptExtras::new().map_action(MapAction::Append)),
+      Opt::flag("format", &["--json"]).extras(&OptExtras::new().constant("json").map_action(MapAction::SetValue)),
+      Opt::flag("format", &["--yaml"]).extras(&OptExtras::new().constant("yaml").map_action(MapAction::SetValue)),
+      Opt::flag("version", &["-V"]).extras(&OptExtras::new().callback(|_| Ok(ParseControl::Continue))),
+    ]);
+    let ParseMapResult::Map(map) = OPTIONS.parse_map_typed("", ["-v", "-p", "80", "--json", "-v", "-p=0x1bb", "--yaml", "-V"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map, BTreeMap::from([
+      ("dry", Value::Bool(false)),
+      ("quiet", Value::Count(0)),
+      ("verbose", Value::Count(2)),
+      ("port", Value::List(Vec::from([Value::Int(80), Value::Int(443)]))),
+      ("tag", Value::List(Vec::new())),
+      ("format", Value::Str("yaml".into())),
+    ]));
+
+    assert!(matches!(OPTIONS.parse_map_typed("", ["-p", "http"].iter(), |_| panic!("unreachable"),
+      |_, error| assert!(matches!(error, ParseError::ArgumentError("-p", "http", crate::ParseErrorKind::InvalidInteger)))),
+      ParseMapResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_parse_collect() {
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::flag("flag", &["-f"]),
+      Opt::value("name", &["-name"], "pattern"),
+      Opt::positional("path", "path").required(),
+    ]);
+    let ParseCollectResult::List(list) = OPTIONS.parse_collect("", ["dir", "-name", "*.rs", "-f", "-name", "*.md"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(list, [
+      ("path", Some("dir".into())),
+      ("name", Some("*.rs".into())),
+      ("flag", None),
+      ("name", Some("*.md".into())),
+    ]);
+  }
+
+  #[test]
+  fn test_matches() {
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::help_flag("help", &["-h"]),
+      Opt::flag("verbose", &["-v"]),
+      Opt::flag("absent", &["-a"]),
+      Opt::value("jobs", &["-j"], "jobs"),
+      Opt::value("include", &["-I"], "dir"),
+    ]);
+    let ParseMatchesResult::Matches(matches) = OPTIONS.parse_matches("", ["-v", "-j", "2", "-I", "a", "-v", "-I", "b"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert!(matches.contains("verbose"));
+    assert!(!matches.contains("absent"));
+    assert_eq!(matches.count("verbose"), 2);
+    assert_eq!(matches.count("absent"), 0);
+    assert_eq!(matches.get_one::<u32>("jobs"), Ok(Some(2)));
+    assert_eq!(matches.get_one::<u32>("absent"), Ok(None));
+    assert!(matches.get_one::<u32>("include").is_err());
+    assert_eq!(matches.get_str("include"), Some("b"));
+    assert_eq!(matches.get_many::<String>("include"), Ok(["a", "b"].map(String::from).to_vec()));
+    assert_eq!(matches.get_many::<String>("absent"), Ok(Vec::new()));
+    assert_eq!(matches.as_slice().len(), 5);
+
+    assert!(matches!(OPTIONS.parse_matches("", ["-h"].iter(), |_| {}, |_, error| panic!("unreachable: {error:?}")),
+      ParseMatchesResult::ExitSuccess));
+  }
+
+  #[test]
+  fn test_from_optstring() {
+    let options = Opts::from_optstring("+ab:c", &[("all", 'a'), ("bee", 'b')]).unwrap();
+    assert_eq!(options.iter().map(|o| (o.id, o.names())).collect::<Vec<_>>(), [
+      ('a', &["-a", "--all"][..]),
+      ('b', &["-b", "--bee"][..]),
+      ('c', &["-c"][..]),
+    ]);
+    assert!(matches!(options.iter().map(|o| o.r#type).collect::<Vec<_>>()[..],
+      [OptType::Flag, OptType::Value, OptType::Flag]));
+
+    let ParseCollectResult::List(list) = options.parse_collect("", ["--all", "-b", "x", "-c", "--bee=y"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(list, [('a', None), ('b', Some("x".into())), ('c', None), ('b', Some("y".into()))]);
+  }
+
+  #[test]
+  fn test_from_optstring_invalid() {
+    for (optstring, long_options, expect) in [
+      ("a-", &[][..], "Invalid option character in optstring"),
+      ("aba", &[][..], "Option characters must be unique"),
+      ("ab::", &[][..], "Optional arguments are not supported"),
+      ("ab", &[("--all", 'a')][..], "Long option names must be non-empty and given without dashes"),
+      ("ab", &[("all", 'a'), ("all", 'b')][..], "Long option names must be unique"),
+      ("ab", &[("cee", 'c')][..], "Long option refers to a character missing from the optstring"),
+    ] {
+      assert_eq!(Opts::from_optstring(optstring, long_options).err(), Some(expect));
+    }
+  }
+
+  #[test]
+  fn test_from_usage() {
+    let options = Opts::from_usage("Example program.
+
+Usage: example [options] [-o <format>] <input> [<output>]
+       example --version
+
+Options:
+  -h, --help            Show this help.
+  -n, --number=<value>  Number value.
+  -o FILE               Output format.
+  --version
+").unwrap();
+    assert_eq!(options.description, Some("Example program."));
+    assert_eq!(options.iter().map(|o| (o.id, o.names(), o.value_name(), o.help_string, o.is_required()))
+      .collect::<Vec<_>>(), [
+      ("input", &["input"][..], None, None, true),
+      ("output", &["output"][..], None, None, false),
+      ("help", &["-h", "--help"][..], None, Some("Show this help."), false),
+      ("number", &["-n", "--number"][..], Some("value"), Some("Number value."), false),
+      ("o", &["-o"][..], Some("FILE"), Some("Output format."), false),
+      ("version", &["--version"][..], None, None, false),
+    ]);
+    assert!(options.iter().nth(2).unwrap().is_help());
+
+    let ParseCollectResult::List(list) = options.parse_collect("", ["in", "-n", "1"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(list, [("input", Some("in".into())), ("number", Some("1".into()))]);
+
+    // Lines shorter than "usage:" in bytes but not in characters
+    let options = Opts::from_usage("aéééé\nUsage: x <f>\n").unwrap();
+    assert_eq!(options.description, Some("aéééé"));
+    assert!(options.iter().map(|o| o.id).eq(["f"]));
+  }
+
+  #[test]
+  fn test_from_usage_invalid() {
+    for (text, expect) in [
+      ("Usage: x [<a>", "Unbalanced brackets in usage"),
+      ("Usage: x <a", "Unterminated placeholder in usage"),
+      ("  -a X Y  Help.", "Unexpected token in option specification"),
+      ("  -a  A.\n  -a  Again.", "Option names must be unique"),
+    ] {
+      assert_eq!(Opts::from_usage(text).err(), Some(expect));
+    }
+  }
+}
@@ -3,12 +3,15 @@
  * SPDX-License-Identifier: MIT OR Apache-2.0
  */
 
-use crate::{Opt, Opts};
+use crate::{Opt, Opts, ParseEvent};
 use crate::option::OptType;
 use crate::options::RequiredParamsBitSet;
+use crate::trace::trace;
+#[cfg(feature = "trace")]
+use crate::TraceEvent;
 
 /// Enum describing the result of parsing arguments, and how the program should behave.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParseResult {
   /// Parsing succeeded and program execution should continue.
   ContinueSuccess,
@@ -19,6 +22,7 @@ pub enum ParseResult {
 }
 
 /// Execution control for parser handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParseControl {
   /// Continue parsing arguments
   Continue,
@@ -29,43 +33,113 @@ pub enum ParseControl {
 }
 
 #[derive(Debug)]
-pub struct ParseHandlerContext<'a, ID: 'static> {
+pub struct ParseHandlerContext<'a, ID> {
   /// Name of the program, for printing statuses to the user.
   pub program_name: &'a str,
   /// The generic argument ID that was matched.
   pub id: &'a ID,
   /// The option that was matched by the parser.
-  pub option: &'a Opt<ID>,
+  pub option: &'a Opt<'a, ID>,
   /// The name of the argument parameter that was matched,
   /// for option parameters this is the token supplied by the user.
   pub name: &'a str,
-  /// The argument provided to positional arguments and value options, else "".
+  /// The argument provided to positional arguments and value options,
+  /// or the [constant](crate::OptExtras::constant) carried by a flag, else "".
   pub arg: &'a str,
+  /// Which value the argument is for options taking several [value names](crate::OptExtras::value_names),
+  /// counting from 0, else 0.
+  pub value_index: usize,
+}
+
+impl<ID> ParseHandlerContext<'_, ID> {
+  /// Reject the argument to this option, with the reason why.
+  /// The option name and argument are filled in by the parser when the error is returned from the handler.
+  /// ```
+  /// # use jaarg::{HandlerReturn, ParseControl, ParseHandlerContext};
+  /// fn handle(ctx: ParseHandlerContext<'_, u32>) -> HandlerReturn<'static> {
+  ///   match ctx.arg.parse::<u32>()? {
+  ///     1..=10 => Ok(ParseControl::Continue),
+  ///     _ => Err(ctx.invalid_value("must be between 1 and 10")),
+  ///   }
+  /// }
+  /// ```
+  pub fn invalid_value<'r>(&self, reason: &'r str) -> ParseError<'r> {
+    ParseError::InvalidValue("", "", reason)
+  }
+
+  /// Reject an option that was given without a usable argument, like an empty `--option=`.
+  /// The option name is filled in by the parser when the error is returned from the handler.
+  pub fn missing(&self) -> ParseError<'static> {
+    ParseError::ExpectArgument("")
+  }
+
+  /// Parse the argument as an integer, accepting `0x`, `0o`, and `0b` prefixes, see [parse_int](crate::parse_int).
+  /// The option name and argument are filled in by the parser when an error is returned from the handler.
+  pub fn parse_int<T: crate::Integer>(&self) -> Result<T, ParseError<'static>> {
+    crate::parse_int(self.arg)
+  }
+
+  /// Reject the argument to this option with one of the standard [ParseErrorKind]s.
+  /// The option name and argument are filled in by the parser when the error is returned from the handler.
+  pub fn argument_error(&self, kind: ParseErrorKind) -> ParseError<'static> {
+    ParseError::ArgumentError("", "", kind)
+  }
 }
 
 /// Result type used by the handler passed to the parser.
-pub(crate) type HandlerResult<'a, T> = core::result::Result<T, ParseError<'a>>;
+/// Errors returned from a handler are reported like any other [ParseError].
+pub type HandlerResult<'a, T> = core::result::Result<T, ParseError<'a>>;
 
-#[derive(Debug)]
+/// Return type of the handler passed to the parser, for naming it in helper functions.
+/// ```
+/// use jaarg::{HandlerReturn, ParseControl, ParseHandlerContext};
+/// fn handle(ctx: ParseHandlerContext<'_, u32>) -> HandlerReturn<'static> {
+///   Ok(if *ctx.id == 0 { ParseControl::Quit } else { ParseControl::Continue })
+/// }
+/// ```
+pub type HandlerReturn<'a> = HandlerResult<'a, ParseControl>;
+
+/// Shorthand for the handler closures accepted by the parser, implemented for every
+/// `FnMut(ParseHandlerContext<ID>) -> HandlerReturn<'a>` so helpers can take `impl ParseHandler<'a, ID>`.
+pub trait ParseHandler<'a, ID>: FnMut(ParseHandlerContext<ID>) -> HandlerReturn<'a> {}
+
+impl<'a, ID, F: FnMut(ParseHandlerContext<ID>) -> HandlerReturn<'a>> ParseHandler<'a, ID> for F {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ParseError<'a> {
   UnknownOption(&'a str),
   UnexpectedToken(&'a str),
   ExpectArgument(&'a str),
   UnexpectedArgument(&'a str),
-  ArgumentError(&'static str, &'a str, ParseErrorKind),
+  TooManyValues(&'a str),
+  UnpairedSurrogate(&'a str),
+  /// A token isn't valid UTF-8, holding the valid part of the token.
+  InvalidUtf8(&'a str),
+  /// A command line string couldn't be split into tokens.
+  InvalidCommandLine(crate::shlex::TokenizeError),
+  TokenTooLong(&'a str),
+  ArgumentError(&'a str, &'a str, ParseErrorKind),
+  /// A handler rejected the argument to an option, with the reason why.
+  InvalidValue(&'a str, &'a str, &'a str),
+  /// A handler panicked while handling an option, with the panic message, see [Opts::with_contained_panics].
+  HandlerPanic(&'a str, &'a str),
   //TODO
-  //Exclusive(&'static str, &'a str),
-  RequiredPositional(&'static str),
-  RequiredParameter(&'static str),
+  //Exclusive(&'a str, &'a str),
+  RequiredPositional(&'a str),
+  RequiredParameter(&'a str),
 }
 
 /// The type of parsing error
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ParseErrorKind {
   IntegerEmpty,
   IntegerRange,
   InvalidInteger,
   InvalidFloat,
+  InvalidToggle,
+  InvalidChoice,
 }
 
 impl core::fmt::Display for ParseError<'_> {
@@ -75,12 +149,23 @@ impl core::fmt::Display for ParseError<'_> {
       Self::UnexpectedToken(t) => write!(f, "Unexpected positional argument '{t}'"),
       Self::ExpectArgument(o) => write!(f, "Option '{o}' requires an argument"),
       Self::UnexpectedArgument(o) => write!(f, "Flag '{o}' doesn't take an argument"),
+      Self::TooManyValues(o) => write!(f, "Too many values for option '{o}'"),
+      Self::UnpairedSurrogate(t) => write!(f, "Argument '{t}' contains invalid Unicode"),
+      Self::InvalidUtf8(t) => write!(f, "Argument '{t}...' isn't valid UTF-8"),
+      Self::InvalidCommandLine(e) => write!(f, "Invalid command line: {e}"),
+      Self::TokenTooLong(t) => write!(f, "Argument '{t}...' is too long"),
       Self::ArgumentError(o, a, ParseErrorKind::IntegerRange)
         => write!(f, "Argument '{a}' out of range for option '{o}'"),
       Self::ArgumentError(o, a, ParseErrorKind::InvalidInteger | ParseErrorKind::InvalidFloat)
         => write!(f, "Invalid argument '{a}' for option '{o}'"),
       Self::ArgumentError(o, _, ParseErrorKind::IntegerEmpty)
         => write!(f, "Argument for option '{o}' cannot be empty"),
+      Self::ArgumentError(o, a, ParseErrorKind::InvalidToggle)
+        => write!(f, "Invalid argument '{a}' for option '{o}', expected on, off, or auto"),
+      Self::ArgumentError(o, a, ParseErrorKind::InvalidChoice)
+        => write!(f, "Invalid argument '{a}' for option '{o}', expected one of the possible values"),
+      Self::InvalidValue(o, a, reason) => write!(f, "Invalid argument '{a}' for option '{o}': {reason}"),
+      Self::HandlerPanic(o, message) => write!(f, "Internal error while handling option '{o}': {message}"),
       //Self::Exclusive(l, r) => write!(f, "Argument {l}: not allowed with argument {r}"),
       Self::RequiredPositional(o) => write!(f, "Missing required positional argument '{o}'"),
       Self::RequiredParameter(o) => write!(f, "Missing required option '{o}'"),
@@ -114,47 +199,124 @@ impl From<core::num::ParseFloatError> for ParseError<'_> {
 impl core::error::Error for ParseError<'_> {}
 
 /// Internal state tracked by the parser.
-struct ParserState<ID: 'static> {
+pub(crate) struct ParserState<'o, const R: usize> {
   positional_index: usize,
-  expects_arg: Option<(&'static str, &'static Opt<ID>)>,
-  required_param_presences: RequiredParamsBitSet,
+  expects_arg: Option<(&'o str, usize)>,
+  value_slot: usize,
+  /// Value options given by name, by position among those that can be given positionally
+  named_positionals: RequiredParamsBitSet<R>,
+  required_param_presences: RequiredParamsBitSet<R>,
+  help_seen: bool,
 }
 
-impl<ID> Default for ParserState<ID> {
+impl<const R: usize> Default for ParserState<'_, R> {
   fn default() -> Self {
     Self {
       positional_index: 0,
       expects_arg: None,
+      value_slot: 0,
+      named_positionals: Default::default(),
       required_param_presences: Default::default(),
+      help_seen: false,
     }
   }
 }
 
-impl<ID: 'static> Opts<ID> {
+/// Act on the result of [Opts::next] for a token inside a parse loop, shared by every parse variant:
+/// breaks out of the (optionally labelled) loop on [ParseControl::Stop], or returns on quit and errors.
+macro_rules! parse_step {
+  ($opts:expr, $result:expr, $program_name:expr, $error:expr $(, $label:lifetime)?) => {
+    match $result {
+      Ok(ParseControl::Continue) => {}
+      Ok(ParseControl::Stop) => { break $($label)?; }
+      Ok(ParseControl::Quit) => { return ParseResult::ExitSuccess; }
+      Err(err) => {
+        // Call the error handler
+        $opts.observe($crate::ParseEvent::Error(&err));
+        $error($program_name, err);
+        return ParseResult::ExitFailure;
+      }
+    }
+  };
+}
+pub(crate) use parse_step;
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
   /// Parses an iterator of strings as argument tokens.
   pub fn parse<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
-    mut handler: impl FnMut(ParseHandlerContext<ID>) -> HandlerResult<'a, ParseControl>,
+    mut handler: impl ParseHandler<'a, ID>,
     error: impl FnOnce(&str, ParseError),
   ) -> ParseResult {
     let mut state = ParserState::default();
     for arg in args {
       // Fetch the next token
-      match self.next(&mut state, arg.as_ref(), program_name, &mut handler) {
-        Ok(ParseControl::Continue) => {}
-        Ok(ParseControl::Stop) => { break; }
-        Ok(ParseControl::Quit) => { return ParseResult::ExitSuccess; }
-        Err(err) => {
-          // Call the error handler
-          error(program_name, err);
-          return ParseResult::ExitFailure;
-        }
+      parse_step!(self, self.next(&mut state, arg.as_ref(), program_name, &mut handler), program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+
+  /// Parses a slice of argument tokens, like [Opts::parse], except the strings passed to the handler
+  /// borrow from the slice so they can be kept after the handler returns without allocating.
+  ///
+  /// The only constraint is that the options outlive the tokens, so short-lived buffers can be parsed
+  /// against a `'static` table. A table that lives shorter than the tokens limits borrows to its own lifetime.
+  pub fn parse_slice<'t>(&self, program_name: &'t str, args: &[&'t str],
+    mut handler: impl FnMut(ParseHandlerContext<'t, ID>) -> HandlerResult<'t, ParseControl>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult where 'o: 't {
+    let mut state = ParserState::default();
+    for &arg in args {
+      parse_step!(self, self.next(&mut state, arg, program_name, &mut handler), program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+
+  /// Parses an iterator of argument tokens like [Opts::parse], without being generic over the iterator,
+  /// handler, or error callback. Every call site shares one copy of the parser, for keeping binary size down.
+  pub fn parse_dyn<'a>(&self, program_name: &str, args: &mut dyn Iterator<Item = &str>,
+    handler: &mut dyn FnMut(ParseHandlerContext<ID>) -> HandlerResult<'a, ParseControl>,
+    error: &mut dyn FnMut(&str, ParseError),
+  ) -> ParseResult {
+    let mut state = ParserState::default();
+    for arg in args {
+      parse_step!(self, self.next(&mut state, arg, program_name, handler), program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+
+  /// Validate the final parser state once all tokens have been consumed.
+  pub(crate) fn finish(&self, state: ParserState<'o, R>, program_name: &str, error: impl FnOnce(&str, ParseError<'o>)
+  ) -> ParseResult {
+    match self.check_finished(state) {
+      // All arguments parsed successfully
+      Ok(()) => ParseResult::ContinueSuccess,
+      Err(err) => {
+        self.observe(ParseEvent::Error(&err));
+        error(program_name, err);
+        ParseResult::ExitFailure
       }
     }
+  }
 
+  /// Checks that the argument stream didn't end early, and that all required arguments were provided.
+  fn check_finished(&self, mut state: ParserState<'o, R>) -> Result<(), ParseError<'o>> {
     // Ensure that value options are provided a value
     if let Some((name, _)) = state.expects_arg.take() {
-      error(program_name, ParseError::ExpectArgument(name));
-      return ParseResult::ExitFailure;
+      return Err(ParseError::ExpectArgument(name));
+    }
+
+    // Missing required arguments aren't an error when the user asked for help
+    if state.help_seen {
+      return Ok(());
+    }
+
+    // Ensure that grouped positionals weren't only partly provided
+    let last_positional = state.positional_index.checked_sub(1).and_then(|i| self.options.get(i));
+    if last_positional.is_some_and(Opt::is_grouped_with_next) {
+      let mut following = self.options.iter().skip(state.positional_index);
+      if let Some(missing) = following.find(|o| o.takes_positional()) {
+        return Err(ParseError::RequiredPositional(missing.display_name()));
+      }
     }
 
     // Ensure that all required arguments have been provided
@@ -162,33 +324,98 @@ impl<ID: 'static> Opts<ID> {
     for (i, option) in self.iter().enumerate() {
       match option.r#type {
         OptType::Positional => if i >= state.positional_index && option.is_required() {
-          error(program_name, ParseError::RequiredPositional(option.first_name()));
-          return ParseResult::ExitFailure;
+          return Err(ParseError::RequiredPositional(option.display_name()));
         }
         OptType::Flag | OptType::Value => if option.is_required() {
           if !state.required_param_presences.get(required_flag_idx) {
-            error(program_name, ParseError::RequiredParameter(option.first_name()));
-            return ParseResult::ExitFailure;
+            return Err(ParseError::RequiredParameter(option.first_name()));
           }
           required_flag_idx += 1;
         }
       }
     }
+    Ok(())
+  }
+
+  /// Returns the positional argument that the next token would be passed to,
+  /// or None if the token is an option or the argument to one.
+  #[cfg(feature = "wild")]
+  pub(crate) fn next_positional(&self, state: &ParserState<'o, R>, token: &str) -> Option<&'o Opt<'o, ID>> {
+    if state.expects_arg.is_some() || self.starts_with_flag(token) {
+      return None;
+    }
+    self.next_positional_turn(state).map(|(_, option)| option)
+  }
+
+  /// Returns the index and option of the positional argument the next positional token is assigned to,
+  /// skipping value options that were already given by name.
+  fn next_positional_turn(&self, state: &ParserState<'o, R>) -> Option<(usize, &'o Opt<'o, ID>)> {
+    let mut ordinal = 0;
+    self.options.iter().enumerate().find(|&(index, option)| match option.r#type {
+      OptType::Positional => index >= state.positional_index,
+      OptType::Value if option.takes_positional() => {
+        ordinal += 1;
+        index >= state.positional_index && !state.named_positionals.get(ordinal - 1)
+      }
+      OptType::Value | OptType::Flag => false,
+    })
+      // Surplus tokens go to the positional collecting extras, which is always the last one
+      .or_else(|| self.options.iter().enumerate().take(state.positional_index).rev()
+        .find(|(_, option)| option.takes_positional()).filter(|(_, option)| option.collects_extra()))
+  }
 
-    // All arguments parsed successfully
-    ParseResult::ContinueSuccess
+  /// Checks if the whole of the next token would be matched as an option name, rather than containing an argument.
+  pub(crate) fn is_option_name(&self, state: &ParserState<'o, R>, token: &str) -> bool {
+    state.expects_arg.is_none() && self.starts_with_flag(token) && !token.contains('=')
+      && self.find_option(token).is_some()
   }
 
-  /// Parse the next token in the argument stream
-  fn next<'a, 'b>(&self, state: &mut ParserState<ID>, token: &'b str, program_name: &str,
-    handler: &mut impl FnMut(ParseHandlerContext<ID>) -> HandlerResult<'a, ParseControl>
-  ) -> HandlerResult<'b, ParseControl> where 'a: 'b {
-    let mut call_handler = |option: &Opt<ID>, name, value| {
-      match handler(ParseHandlerContext{ program_name, id: &option.id, option, name, arg: value }) {
+  /// Parse the next token in the argument stream.
+  /// The handler is type-erased so the matching logic is only instantiated once per options type,
+  /// rather than once per handler closure.
+  pub(crate) fn next<'a, 'b>(&self, state: &mut ParserState<'o, R>, token: &'b str, program_name: &'b str,
+    handler: &mut dyn FnMut(ParseHandlerContext<'b, ID>) -> HandlerResult<'a, ParseControl>
+  ) -> HandlerResult<'b, ParseControl> where 'a: 'b, 'o: 'b {
+    let mut call_handler = |option: &'o Opt<'o, ID>, name, value: &'b str, value_index| {
+      if value.is_empty() && option.is_non_empty() {
+        return Err(ParseError::ExpectArgument(name));
+      }
+      // Arguments to options with choices are passed on as the canonical value
+      let value = match option.choices() {
+        [] => value,
+        choices => match choices.iter().find(|choice| choice.matches(value)) {
+          Some(choice) => choice.value,
+          None => return Err(ParseError::ArgumentError(name, value, ParseErrorKind::InvalidChoice)),
+        },
+      };
+      // The built-in completions option prints the script and exits in place of the handler
+      if option.generates_completions() {
+        if let Some(shell) = crate::Shell::from_name(value) {
+          self.print_output(|mut w| self.print_completions_to(&mut w, program_name, shell));
+        }
+        return Ok(ParseControl::Quit);
+      }
+      // Help flags write to the help handler in place of the handler when the table has one
+      if let (true, Some(mut sink)) = (option.is_help(), self.sinks.help) {
+        let _ = self.print_full_help_to(&mut sink, program_name);
+        return Ok(ParseControl::Quit);
+      }
+      let ctx = ParseHandlerContext{ program_name, id: &option.id, option, name, arg: value, value_index };
+      // Callback actions are run in place of the handler
+      let result = match option.callback() {
+        Some(callback) => callback(ctx),
+        None => handler(ctx),
+      };
+      match result {
         // HACK: Ensure the string fields are set properly, because coerced
         //       ParseIntError/ParseFloatError will have the string fields blanked.
         Err(ParseError::ArgumentError("", "", kind))
           => Err(ParseError::ArgumentError(name, value, kind)),
+        Err(ParseError::InvalidValue("", "", reason))
+          => Err(ParseError::InvalidValue(name, value, reason)),
+        Err(ParseError::HandlerPanic("", message)) => Err(ParseError::HandlerPanic(name, message)),
+        Err(ParseError::ExpectArgument("")) => Err(ParseError::ExpectArgument(name)),
+        Err(ParseError::TooManyValues("")) => Err(ParseError::TooManyValues(name)),
         Err(err) => Err(err),
         Ok(ctl) => Ok(ctl),
       }
@@ -197,61 +424,93 @@ impl<ID: 'static> Opts<ID> {
     // If the previous token is expecting an argument, ie: value a value option
     //  was matched and didn't have an equals sign separating a value,
     //  then call the handler here.
-    if let Some((name, option)) = state.expects_arg.take() {
-      call_handler(option, name, token)
+    trace!(self, TraceEvent::Token(token));
+    if let Some((name, index)) = state.expects_arg.take() {
+      trace!(self, TraceEvent::Value { index, name, value: token });
+      let Some(option) = self.options.get(index) else {
+        return Err(ParseError::UnknownOption(name));
+      };
+      self.observe(ParseEvent::Value { option, name, value: token });
+      // Options taking several values expect another token until every value is filled
+      let value_index = state.value_slot;
+      state.value_slot += 1;
+      if state.value_slot < option.value_names().len() {
+        state.expects_arg = Some((name, index));
+      } else {
+        state.value_slot = 0;
+      }
+      call_handler(option, name, token, value_index)
     } else {
       // Check if the next argument token starts with an option flag
-      if self.flag_chars.chars().any(|c| token.starts_with(c)) {
+      if self.starts_with_flag(token) {
         // Value options can have their value delineated by an equals sign or with whitespace.
         // In the latter case; the value will be in the next token.
         let (option_str, value_str) = token.split_once("=")
           .map_or((token, None), |(k, v)| (k, Some(v)));
 
-        // Keep track of how many required options we've seen
-        let mut required_idx = 0;
-
         // Match a suitable option by name (ignoring the first flag character & skipping positional arguments)
-        let (name, option) = self.iter()
-          .filter(|opt| matches!(opt.r#type, OptType::Flag | OptType::Value)).find_map(|opt| {
-            if let Some(name) = opt.match_name(option_str, 1) {
-              Some((name, opt))
-            } else {
-              if opt.is_required() {
-                required_idx += 1
-              }
-              None
-            }
-          }).ok_or(ParseError::UnknownOption(option_str))?;
+        let Some((index, name, required_idx, positional_idx)) = self.find_option(option_str) else {
+          trace!(self, TraceEvent::Unmatched(option_str));
+          return Err(ParseError::UnknownOption(option_str));
+        };
+        let Some(option) = self.options.get(index) else {
+          return Err(ParseError::UnknownOption(option_str));
+        };
+        trace!(self, TraceEvent::Option { index, name, value: value_str });
+        self.observe(ParseEvent::Option { option, name });
 
         // Mark required option as visited
         if option.is_required() {
           state.required_param_presences.insert(required_idx, true);
         }
+        state.help_seen |= option.is_help();
+        if option.takes_positional() {
+          state.named_positionals.insert(positional_idx, true);
+        }
 
         match (&option.r#type, value_str) {
-          // Call handler for flag-only options
-          (OptType::Flag, None) => call_handler(option, name, ""),
+          // Terminal flags print their output and exit in place of the handler
+          (OptType::Flag, None) => match option.terminal() {
+            Some(output) => {
+              self.print_output(|w| output.write_to(w, program_name));
+              Ok(ParseControl::Quit)
+            }
+            // Call handler for flag-only options, with the constant they carry if any
+            None => call_handler(option, name, option.constant().unwrap_or(""), 0),
+          }
           // Value was provided this token, so call the handler right now
-          (OptType::Value, Some(value)) => call_handler(option, name, value),
+          (OptType::Value, Some(value)) => {
+            self.observe(ParseEvent::Value { option, name, value });
+            if option.value_names().len() > 1 {
+              (state.expects_arg, state.value_slot) = (Some((name, index)), 1);
+            }
+            call_handler(option, name, value, 0)
+          }
           // No value available in this token, delay handling to next token
           (OptType::Value, None) => {
-            state.expects_arg = Some((name, option));
+            trace!(self, TraceEvent::AwaitValue { index, name });
+            state.expects_arg = Some((name, index));
             Ok(ParseControl::Continue)
           }
           // Flag-only options do not support arguments
           (OptType::Flag, Some(_)) => Err(ParseError::UnexpectedArgument(option_str)),
-          // Positional arguments are filtered out so this is impossible
-          (OptType::Positional, _) => unreachable!("Won't parse a positional argument as an option"),
+          // Positional arguments are never looked up by name
+          (OptType::Positional, _) => Err(ParseError::UnknownOption(option_str)),
         }
       } else {
         // Find the next positional argument
-        for (i, option) in self.options[state.positional_index..].iter().enumerate() {
-          if matches!(option.r#type, OptType::Positional) {
-            call_handler(option, option.first_name(), token)?;
-            state.positional_index += i + 1;
-            return Ok(ParseControl::Continue);
+        if let Some((index, option)) = self.next_positional_turn(state) {
+          trace!(self, TraceEvent::Positional { index, name: option.first_name(), value: token });
+          self.observe(ParseEvent::Positional { option, value: token });
+          // Value options given positionally are marked as visited like they are when given by name
+          if let Some(required_idx) = self.required_ordinal(index) {
+            state.required_param_presences.insert(required_idx, true);
           }
+          call_handler(option, option.first_name(), token, 0)?;
+          state.positional_index = index + 1;
+          return Ok(ParseControl::Continue);
         }
+        trace!(self, TraceEvent::NoPositional(token));
         Err(ParseError::UnexpectedToken(token))
       }
     }
@@ -263,6 +522,7 @@ mod tests {
   extern crate alloc;
   use alloc::string::String;
   use super::*;
+  use crate::OptExtras;
 
   #[test]
   fn test() {
@@ -301,4 +561,342 @@ mod tests {
     assert_eq!(four, Some("four".into()));
     assert_eq!(five, Some("".into()));
   }
+
+  #[test]
+  fn test_custom_required_capacity() {
+    const OPTIONS: Opts<u32, 1> = Opts::new_with_capacity(&[
+      Opt::flag(0, &["-a"]).required(),
+      Opt::flag(1, &["-b"]).required(),
+    ]);
+    assert!(matches!(OPTIONS.parse("", ["-b", "-a"].iter(), |_| Ok(ParseControl::Continue),
+      |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert!(matches!(OPTIONS.parse("", ["-a"].iter(), |_| Ok(ParseControl::Continue),
+      |_, error| assert!(matches!(error, ParseError::RequiredParameter("-b")))), ParseResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_borrowed_options() {
+    use alloc::vec::Vec;
+    let names: Vec<String> = ["-n", "--name"].iter().map(|&n| n.into()).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let positional = String::from("file");
+    let option_list = [Opt::value(0, &names, "value"), Opt::positional(1, &positional).required()];
+    let options = Opts::new(&option_list);
+
+    let mut name: Option<String> = None;
+    assert!(matches!(options.parse("", ["--name", "pizza"].iter(), |ctx| {
+      name = Some(ctx.arg.into());
+      Ok(ParseControl::Continue)
+    }, |_, error| {
+      assert!(matches!(error, ParseError::RequiredPositional("file")));
+    }), ParseResult::ExitFailure));
+    assert_eq!(name, Some("pizza".into()));
+  }
+
+  #[test]
+  fn test_parse_slice() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--name"], "value"),
+      Opt::flag(1, &["--stop"]),
+      Opt::positional(2, "file").required(),
+    ]);
+    // Arguments outlive the handler without being copied
+    let mut args: [&str; 2] = [""; 2];
+    assert!(matches!(OPTIONS.parse_slice("", &["--name=pizza", "file.txt", "--stop", "ignored"], |ctx| {
+      match ctx.id {
+        0 => args[0] = ctx.arg,
+        1 => return Ok(ParseControl::Stop),
+        _ => args[1] = ctx.arg,
+      }
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(args, ["pizza", "file.txt"]);
+
+    assert!(matches!(OPTIONS.parse_slice("", &["--stop"], |_| Ok(ParseControl::Stop),
+      |_, error| assert!(matches!(error, ParseError::RequiredPositional("file")))), ParseResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_multiple_values() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--copy"], "SRC").extras(&OptExtras::new().value_names(&["SRC", "DST"])),
+      Opt::positional(1, "file"),
+    ]);
+    for args in [&["--copy", "a", "b", "c"][..], &["--copy=a", "b", "c"]] {
+      let mut values = [("", 0); 3];
+      let mut count = 0;
+      assert!(matches!(OPTIONS.parse_slice("", args, |ctx| {
+        values[count] = (ctx.arg, ctx.value_index);
+        count += 1;
+        Ok(ParseControl::Continue)
+      }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+      assert_eq!(values, [("a", 0), ("b", 1), ("c", 0)]);
+    }
+    // Every value must be provided, even if it looks like an option
+    assert!(matches!(OPTIONS.parse_slice("", &["--copy", "a"], |_| Ok(ParseControl::Continue),
+      |_, error| assert!(matches!(error, ParseError::ExpectArgument("--copy")))), ParseResult::ExitFailure));
+    let mut values = [""; 2];
+    let mut count = 0;
+    assert!(matches!(OPTIONS.parse_slice("", &["--copy=a", "--copy"], |ctx| {
+      values[count] = ctx.arg;
+      count += 1;
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(values, ["a", "--copy"]);
+  }
+
+  #[test]
+  fn test_positional_group() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "file"),
+      Opt::positional(1, "host").group_with_next(),
+      Opt::positional(2, "port"),
+    ]);
+    for args in [&[][..], &["a"], &["a", "localhost", "80"]] {
+      assert!(matches!(OPTIONS.parse_slice("", args, |_| Ok(ParseControl::Continue),
+        |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    }
+    assert!(matches!(OPTIONS.parse_slice("", &["a", "localhost"], |_| Ok(ParseControl::Continue),
+      |_, error| assert_eq!(error, ParseError::RequiredPositional("port"))), ParseResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_or_positional() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "command").required(),
+      Opt::value(1, &["--target"], "target").or_positional().required(),
+      Opt::positional(2, "extra"),
+    ]);
+    for args in [&["build", "x86", "y"][..], &["build", "--target", "x86", "y"], &["--target=x86", "build", "y"]] {
+      let mut values = [""; 3];
+      assert!(matches!(OPTIONS.parse_slice("", args, |ctx| {
+        values[*ctx.id as usize] = ctx.arg;
+        Ok(ParseControl::Continue)
+      }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+      assert_eq!(values, ["build", "x86", "y"]);
+    }
+    // Required either way
+    assert!(matches!(OPTIONS.parse_slice("", &["build"], |_| Ok(ParseControl::Continue),
+      |_, error| assert_eq!(error, ParseError::RequiredParameter("--target"))), ParseResult::ExitFailure));
+    // Giving it both ways is a repeat, not a shift to the next positional
+    let mut targets = 0;
+    assert!(matches!(OPTIONS.parse_slice("", &["build", "x86", "--target=arm"], |ctx| {
+      targets += (*ctx.id == 1) as usize;
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(targets, 2);
+  }
+
+  #[test]
+  fn test_or_positional_late_entry() {
+    const NUM_OPTS: usize = 200;
+    const OPT_LIST: [Opt<usize>; NUM_OPTS] = {
+      let mut array = [const { Opt::flag(0, &[""]) }; NUM_OPTS];
+      array[NUM_OPTS - 2] = Opt::value(NUM_OPTS - 2, &["--target"], "target").or_positional();
+      array[NUM_OPTS - 1] = Opt::positional(NUM_OPTS - 1, "file");
+      array
+    };
+    const OPTIONS: Opts<usize> = Opts::new(&OPT_LIST);
+    const INDEX: crate::NameIndex<{ OPTIONS.name_count() }> = OPTIONS.name_index();
+    for options in [OPTIONS, OPTIONS.with_index(&INDEX)] {
+      let mut values = [""; 2];
+      assert!(matches!(options.parse_slice("", &["--target=x86", "a.out"], |ctx| {
+        values[*ctx.id + 2 - NUM_OPTS] = ctx.arg;
+        Ok(ParseControl::Continue)
+      }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+      assert_eq!(values, ["x86", "a.out"]);
+    }
+  }
+
+  #[test]
+  fn test_collect_extra() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "command").required(),
+      Opt::flag(1, &["-v"]),
+      Opt::positional(2, "args").collect_extra(),
+    ]);
+    for (args, expected) in [(&["run"][..], &[][..]), (&["run", "a"], &["a"]), (&["run", "a", "-v", "b", "c"], &["a", "b", "c"])] {
+      let mut extra = alloc::vec::Vec::new();
+      assert_eq!(OPTIONS.parse_slice("", args, |ctx| {
+        if *ctx.id == 2 {
+          extra.push(ctx.arg);
+        }
+        Ok(ParseControl::Continue)
+      }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess);
+      assert_eq!(extra, expected);
+    }
+
+    // Without a collecting positional surplus tokens are still rejected
+    const STRICT: Opts<u32> = Opts::new(&[Opt::positional(0, "command")]);
+    assert!(matches!(STRICT.parse_slice("", &["run", "a"], |_| Ok(ParseControl::Continue),
+      |_, error| assert_eq!(error, ParseError::UnexpectedToken("a"))), ParseResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_non_empty() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-n", "--name"], "name").non_empty(),
+      Opt::value(1, &["--tag"], "tag"),
+    ]);
+    for args in [&["--name="][..], &["--name", ""], &["-n", "", "--tag="]] {
+      assert!(matches!(OPTIONS.parse_slice("", args, |ctx| {
+        assert_ne!(*ctx.id, 0);
+        Ok(ParseControl::Continue)
+      }, |_, error| assert_eq!(error, ParseError::ExpectArgument(args[0].trim_end_matches('=')))),
+        ParseResult::ExitFailure));
+    }
+    // Options without the constraint still take empty arguments
+    assert!(matches!(OPTIONS.parse_slice("", &["--tag=", "--name=x"], |_| Ok(ParseControl::Continue),
+      |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+  }
+
+  #[test]
+  fn test_help_skips_required() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "file").required(),
+      Opt::value(1, &["-o"], "out").required(),
+      Opt::help_flag(2, &["--help"]),
+    ]);
+    // Handlers that defer showing help until after parsing don't see missing required arguments
+    for args in [["--help", "-o=a"], ["-o=a", "--help"]] {
+      let mut help = false;
+      assert!(matches!(OPTIONS.parse("", args.iter(), |ctx| {
+        help |= ctx.option.is_help();
+        Ok(ParseControl::Continue)
+      }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+      assert!(help);
+    }
+    assert!(matches!(OPTIONS.parse("", ["-o=a"].iter(), |_| Ok(ParseControl::Continue),
+      |_, error| assert!(matches!(error, ParseError::RequiredPositional("file")))), ParseResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_parse_slice_short_lived() {
+    const OPTIONS: Opts<u32> = Opts::new(&[Opt::positional(0, "word")]);
+    fn first_word(line: &str) -> Option<&str> {
+      let tokens = [line.split_whitespace().next()?];
+      let mut word = None;
+      OPTIONS.parse_slice("", &tokens, |ctx| {
+        word = Some(ctx.arg);
+        Ok(ParseControl::Continue)
+      }, |_, _| {});
+      word
+    }
+    // The token buffer lives on the stack of a function that has returned, the borrow is of the line
+    let line = String::from("hello world");
+    assert_eq!(first_word(&line), Some("hello"));
+  }
+
+  #[test]
+  fn test_parse_dyn() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::flag(0, &["-v"]),
+      Opt::positional(1, "file").required(),
+    ]);
+    let mut count = 0;
+    let mut handler = |_: ParseHandlerContext<u32>| { count += 1; Ok(ParseControl::Continue) };
+    assert!(matches!(OPTIONS.parse_dyn("", &mut ["-v", "a"].into_iter(), &mut handler,
+      &mut |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    let mut failed = false;
+    assert!(matches!(OPTIONS.parse_dyn("", &mut ["-v"].into_iter(), &mut handler,
+      &mut |_, error| failed = matches!(error, ParseError::RequiredPositional("file"))), ParseResult::ExitFailure));
+    assert!(failed);
+    assert_eq!(count, 3);
+  }
+
+  #[test]
+  fn test_parse_never_panics() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::flag(0, &["-v", "é", "évé"]),
+      Opt::value(1, &["-o", "éo"], "out"),
+      Opt::positional(2, "file"),
+    ]).with_flag_chars("-é");
+    const INDEX: crate::NameIndex<{ OPTIONS.name_count() }> = OPTIONS.name_index();
+    const TOKENS: &[&str] = &["", "-", "é", "=", "-=", "é=", "éé", "-é", "év", "évé=x", "éo=ü", "ü", "-ü=", "--", "-o"];
+    for options in [OPTIONS, OPTIONS.with_index(&INDEX)] {
+      for &token in TOKENS {
+        for args in [&[token][..], &["-v", token], &[token, token]] {
+          let _ = options.parse("", args.iter(), |_| Ok(ParseControl::Continue), |_, _| {});
+        }
+      }
+    }
+    let mut matched = [false; 2];
+    let _ = OPTIONS.with_index(&INDEX).parse("", ["évé", "éo=ü"].iter(), |ctx| {
+      matched[*ctx.id as usize] = *ctx.id == 0 || ctx.arg == "ü";
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}"));
+    assert_eq!(matched, [true, true]);
+  }
+
+  #[test]
+  fn test_handler_return() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-n"], "count"),
+    ]);
+    fn handle(ctx: ParseHandlerContext<u32>, total: &mut u32) -> HandlerReturn<'static> {
+      *total += ctx.arg.parse::<u32>()?;
+      Ok(ParseControl::Continue)
+    }
+    let mut total = 0;
+    assert!(matches!(OPTIONS.parse("", ["-n", "2", "-n=3"].iter(), |ctx| handle(ctx, &mut total),
+      |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(total, 5);
+  }
+
+  #[test]
+  fn test_parse_handler() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::flag(0, &["-v"]),
+    ]);
+    fn parse_quiet<'a>(args: &'a [&str], handler: impl ParseHandler<'a, u32>) -> ParseResult {
+      OPTIONS.parse("", args.iter(), handler, |_, _| {})
+    }
+    let mut count = 0;
+    assert!(matches!(parse_quiet(&["-v", "-v"], |_| { count += 1; Ok(ParseControl::Continue) }),
+      ParseResult::ContinueSuccess));
+    assert_eq!(count, 2);
+  }
+
+  #[test]
+  fn test_result_equality() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-n"], "count").required(),
+    ]);
+    let cases: [(&[&str], _); 2] = [
+      (&["-n", "x"], ParseError::ArgumentError("-n", "x", ParseErrorKind::InvalidInteger)),
+      (&[], ParseError::RequiredParameter("-n")),
+    ];
+    for (args, expected) in cases {
+      let mut error = None;
+      assert_eq!(OPTIONS.parse("", args.iter(), |ctx| Ok(ctx.arg.parse::<u32>().map(|_| ParseControl::Continue)?),
+        |_, e| error = Some(e == expected)), ParseResult::ExitFailure);
+      assert_eq!(error, Some(true));
+    }
+  }
+
+  #[test]
+  fn test_context_errors() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-n"], "count"),
+      Opt::value(1, &["-o"], "out"),
+      Opt::value(2, &["-j"], "jobs"),
+    ]);
+    let handler = |ctx: ParseHandlerContext<u32>| match ctx.id {
+      0 => Err(ctx.invalid_value("must be between 1 and 10")),
+      1 => Err(ctx.missing()),
+      _ => Err(ctx.argument_error(ParseErrorKind::IntegerRange)),
+    };
+    let cases: [(&[&str], _); 3] = [
+      (&["-n", "11"], ParseError::InvalidValue("-n", "11", "must be between 1 and 10")),
+      (&["-o="], ParseError::ExpectArgument("-o")),
+      (&["-j=99999"], ParseError::ArgumentError("-j", "99999", ParseErrorKind::IntegerRange)),
+    ];
+    for (args, expected) in cases {
+      let mut error = None;
+      assert_eq!(OPTIONS.parse("", args.iter(), handler, |_, e| error = Some(e == expected)), ParseResult::ExitFailure);
+      assert_eq!(error, Some(true));
+    }
+    assert_eq!(alloc::format!("{}", ParseError::InvalidValue("-n", "11", "must be between 1 and 10")),
+      "Invalid argument '11' for option '-n': must be between 1 and 10");
+  }
 }
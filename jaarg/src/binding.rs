@@ -0,0 +1,184 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult};
+use core::cell::Cell;
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::AtomicBool;
+#[cfg(target_has_atomic = "32")]
+use core::sync::atomic::AtomicU32;
+#[cfg(any(target_has_atomic = "8", target_has_atomic = "32"))]
+use core::sync::atomic::Ordering;
+
+/// Destination for the argument of an option, filled in by [Opts::parse_bind].
+#[derive(Debug)]
+pub enum Binding<'b, 't> {
+  /// Set to true when the option is matched.
+  Flag(&'b mut bool),
+  /// Incremented each time the option is matched.
+  Count(&'b mut u32),
+  /// Set to the argument, borrowed from the argument list.
+  Str(&'b mut Option<&'t str>),
+  /// Set to the argument parsed as a signed integer, see [parse_int](crate::parse_int).
+  Int(&'b mut i64),
+  /// Set to the argument parsed as a floating-point number.
+  Float(&'b mut f64),
+}
+
+impl<'t> Binding<'_, 't> {
+  fn store<ID>(&mut self, ctx: &ParseHandlerContext<'t, ID>) -> Result<(), ParseError<'t>> {
+    match self {
+      Self::Flag(dest) => **dest = true,
+      Self::Count(dest) => **dest = dest.saturating_add(1),
+      Self::Str(dest) => **dest = Some(ctx.arg),
+      Self::Int(dest) => **dest = ctx.parse_int()?,
+      Self::Float(dest) => **dest = ctx.arg.parse()?,
+    }
+    Ok(())
+  }
+}
+
+impl<'o, ID: PartialEq, const R: usize> Opts<'o, ID, R> {
+  /// Parse a slice of argument strings, storing the argument of each matched option into the
+  /// [Binding] paired with its ID. Options without a binding are ignored.
+  pub fn parse_bind<'t>(&self, program_name: &'t str, args: &[&'t str], bindings: &mut [(ID, Binding<'_, 't>)],
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult where 'o: 't {
+    self.parse_slice(program_name, args, |ctx| {
+      if ctx.option.is_help() {
+        help(program_name);
+        return Ok(ParseControl::Quit);
+      }
+      for (_, binding) in bindings.iter_mut().filter(|(id, _)| id == ctx.id) {
+        binding.store(&ctx)?;
+      }
+      Ok(ParseControl::Continue)
+    }, error)
+  }
+}
+
+/// Shared destination for the argument of an option, filled in by [Opts::parse_bind_cells].
+/// Unlike [Binding] the table only needs shared references, so it can be declared once and reused,
+/// and the atomic variants can point at configuration statics on bare-metal targets.
+#[derive(Debug, Clone, Copy)]
+pub enum CellBinding<'b, 't> {
+  /// Set to true when the option is matched.
+  Flag(&'b Cell<bool>),
+  /// Incremented each time the option is matched.
+  Count(&'b Cell<u32>),
+  /// Set to the argument, borrowed from the argument list.
+  Str(&'b Cell<Option<&'t str>>),
+  /// Set to the argument parsed as a signed integer.
+  Int(&'b Cell<i64>),
+  /// Set to the argument parsed as a floating-point number.
+  Float(&'b Cell<f64>),
+  /// Set to true when the option is matched.
+  #[cfg(target_has_atomic = "8")]
+  AtomicFlag(&'b AtomicBool),
+  /// Incremented each time the option is matched.
+  #[cfg(target_has_atomic = "32")]
+  AtomicCount(&'b AtomicU32),
+}
+
+impl<'t> CellBinding<'_, 't> {
+  fn store<ID>(&self, ctx: &ParseHandlerContext<'t, ID>) -> Result<(), ParseError<'t>> {
+    match self {
+      Self::Flag(dest) => dest.set(true),
+      Self::Count(dest) => dest.set(dest.get().saturating_add(1)),
+      Self::Str(dest) => dest.set(Some(ctx.arg)),
+      Self::Int(dest) => dest.set(ctx.parse_int()?),
+      Self::Float(dest) => dest.set(ctx.arg.parse()?),
+      #[cfg(target_has_atomic = "8")]
+      Self::AtomicFlag(dest) => dest.store(true, Ordering::Relaxed),
+      #[cfg(target_has_atomic = "32")]
+      Self::AtomicCount(dest) => {
+        let _ = dest.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| Some(n.saturating_add(1)));
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<'o, ID: PartialEq, const R: usize> Opts<'o, ID, R> {
+  /// Parse a slice of argument strings, storing the argument of each matched option into the
+  /// [CellBinding] paired with its ID. Options without a binding are ignored.
+  pub fn parse_bind_cells<'t>(&self, program_name: &'t str, args: &[&'t str],
+    bindings: &[(ID, CellBinding<'_, 't>)], help: impl Fn(&str), error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult where 'o: 't {
+    self.parse_slice(program_name, args, |ctx| {
+      if ctx.option.is_help() {
+        help(program_name);
+        return Ok(ParseControl::Quit);
+      }
+      for (_, binding) in bindings.iter().filter(|(id, _)| id == ctx.id) {
+        binding.store(&ctx)?;
+      }
+      Ok(ParseControl::Continue)
+    }, error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+
+  #[derive(PartialEq)]
+  enum Arg { Help, Verbose, Dry, Name, Jobs, Scale, File }
+  const OPTIONS: Opts<Arg> = Opts::new(&[
+    Opt::help_flag(Arg::Help, &["-h"]),
+    Opt::flag(Arg::Verbose, &["-v"]),
+    Opt::flag(Arg::Dry, &["-n"]),
+    Opt::value(Arg::Name, &["--name"], "name"),
+    Opt::value(Arg::Jobs, &["-j"], "jobs"),
+    Opt::value(Arg::Scale, &["--scale"], "scale"),
+    Opt::positional(Arg::File, "file"),
+  ]);
+
+  #[test]
+  fn test_parse_bind() {
+    let (mut verbose, mut dry, mut name, mut jobs, mut scale, mut file) = (0, false, None, 1, 1.0, None);
+    assert!(matches!(OPTIONS.parse_bind("", &["-v", "--name=pizza", "-v", "-j", "4", "--scale", "0.5", "in"], &mut [
+      (Arg::Verbose, Binding::Count(&mut verbose)),
+      (Arg::Dry, Binding::Flag(&mut dry)),
+      (Arg::Name, Binding::Str(&mut name)),
+      (Arg::Jobs, Binding::Int(&mut jobs)),
+      (Arg::Scale, Binding::Float(&mut scale)),
+      (Arg::File, Binding::Str(&mut file)),
+    ], |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!((verbose, dry, name, jobs, scale, file), (2, false, Some("pizza"), 4, 0.5, Some("in")));
+  }
+
+  #[test]
+  fn test_parse_bind_errors() {
+    let mut jobs = 0;
+    assert!(matches!(OPTIONS.parse_bind("", &["-j", "x"], &mut [(Arg::Jobs, Binding::Int(&mut jobs))],
+      |_| panic!("unreachable"), |_, error| assert!(matches!(error,
+        ParseError::ArgumentError("-j", "x", crate::ParseErrorKind::InvalidInteger)))),
+      ParseResult::ExitFailure));
+    let help = core::cell::Cell::new(false);
+    assert!(matches!(OPTIONS.parse_bind("", &["-h", "-j", "1"], &mut [(Arg::Jobs, Binding::Int(&mut jobs))],
+      |_| help.set(true), |_, error| panic!("unreachable: {error:?}")), ParseResult::ExitSuccess));
+    assert!(help.get());
+    assert_eq!(jobs, 0);
+  }
+
+  #[test]
+  fn test_parse_bind_cells() {
+    static DRY: AtomicBool = AtomicBool::new(false);
+    static VERBOSE: AtomicU32 = AtomicU32::new(0);
+    let (name, jobs) = (Cell::new(None), Cell::new(1));
+    let bindings = [
+      (Arg::Verbose, CellBinding::AtomicCount(&VERBOSE)),
+      (Arg::Dry, CellBinding::AtomicFlag(&DRY)),
+      (Arg::Name, CellBinding::Str(&name)),
+      (Arg::Jobs, CellBinding::Int(&jobs)),
+    ];
+    assert!(matches!(OPTIONS.parse_bind_cells("", &["-v", "-n", "--name", "pizza", "-v", "-j=3"], &bindings,
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!((VERBOSE.load(Ordering::Relaxed), DRY.load(Ordering::Relaxed)), (2, true));
+    assert_eq!((name.get(), jobs.get()), (Some("pizza"), 3));
+  }
+}
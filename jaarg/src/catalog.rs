@@ -0,0 +1,164 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Opt, Opts};
+
+/// A string written by the built-in help writers that a [HelpCatalog] can translate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HelpKey<'a, ID> {
+  /// The help text of the option with this ID.
+  Help(&'a ID),
+  /// The program description.
+  Description,
+  /// The `Usage:` keyword that starts the short usage.
+  Usage,
+  /// The `[OPTIONS]` placeholder in the clap short usage.
+  OptionsPlaceholder,
+  /// The header of the positional arguments section of the full help.
+  PositionalsHeader,
+  /// The header of the options section of the full help.
+  OptionsHeader,
+  /// The `Report bugs to:` label before the bug report address at the end of the full help.
+  ReportBugs,
+}
+
+/// Translations of help text and well-known strings, set with [Opts::with_catalog].
+/// Strings the catalog doesn't have are written as declared in the options table.
+/// ```
+/// use jaarg::{HelpKey, Opt, Opts};
+///
+/// #[derive(PartialEq)]
+/// enum Arg { Help, File }
+/// const OPTIONS: Opts<Arg> = Opts::new(&[
+///   Opt::help_flag(Arg::Help, &["-h", "--help"]).help_text("Show this help and exit."),
+///   Opt::positional(Arg::File, "file").help_text("Input file."),
+/// ]);
+///
+/// let french = |key: HelpKey<Arg>| match key {
+///   HelpKey::Usage => Some("Utilisation :"),
+///   HelpKey::Help(Arg::Help) => Some("Afficher cette aide et quitter."),
+///   HelpKey::Help(Arg::File) => Some("Fichier d'entrée."),
+///   _ => None,
+/// };
+/// let mut help = String::new();
+/// OPTIONS.with_catalog(&french).print_full_help_to(&mut help, "prog").unwrap();
+/// assert!(help.starts_with("Utilisation : prog"));
+/// ```
+pub trait HelpCatalog<ID> {
+  /// Look up the translation of a string, or [None] to write it as declared.
+  fn lookup(&self, key: HelpKey<'_, ID>) -> Option<&str>;
+}
+
+impl<ID, F: Fn(HelpKey<'_, ID>) -> Option<&'static str>> HelpCatalog<ID> for F {
+  fn lookup(&self, key: HelpKey<'_, ID>) -> Option<&str> {
+    self(key)
+  }
+}
+
+/// The catalog set with [Opts::with_catalog], compared by address so [Opts] can still derive [PartialEq] & [Debug].
+pub(crate) struct CatalogRef<'o, ID>(&'o dyn HelpCatalog<ID>);
+
+impl<ID> Clone for CatalogRef<'_, ID> {
+  fn clone(&self) -> Self { *self }
+}
+
+impl<ID> Copy for CatalogRef<'_, ID> {}
+
+impl<ID> PartialEq for CatalogRef<'_, ID> {
+  fn eq(&self, other: &Self) -> bool {
+    core::ptr::addr_eq(self.0, other.0)
+  }
+}
+
+impl<ID> core::fmt::Debug for CatalogRef<'_, ID> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("HelpCatalog")
+  }
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Translate the help text and well-known strings written by the built-in help writers through `catalog`,
+  /// so the same table can render help in the language chosen at runtime.
+  #[inline]
+  pub const fn with_catalog(mut self, catalog: &'o dyn HelpCatalog<ID>) -> Self {
+    self.sinks.catalog = Some(CatalogRef(catalog));
+    self
+  }
+
+  /// Look up a string in the catalog, or `default` if there's no catalog or translation.
+  pub(crate) fn localize<'a>(&'a self, key: HelpKey<'_, ID>, default: &'a str) -> &'a str {
+    self.sinks.catalog.and_then(|catalog| catalog.0.lookup(key)).unwrap_or(default)
+  }
+
+  /// The help text of an option, translated by the catalog.
+  pub(crate) fn localized_help(&self, option: &Opt<'o, ID>) -> Option<&str> {
+    option.help_string.map(|help_string| self.localize(HelpKey::Help(&option.id), help_string))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate alloc;
+  use alloc::string::String;
+  use crate::ClapFullHelpWriter;
+  use super::*;
+
+  #[derive(Debug, PartialEq)]
+  enum Arg { Verbose, File }
+
+  const OPTIONS: Opts<Arg> = Opts::new(&[
+    Opt::flag(Arg::Verbose, &["-v"]).help_text("Verbose output"),
+    Opt::positional(Arg::File, "file").help_text("Input file"),
+  ]).with_description("Does a thing.");
+
+  /// A catalog that owns its strings, as one loaded at runtime would.
+  struct Catalog([String; 6]);
+
+  impl HelpCatalog<Arg> for Catalog {
+    fn lookup(&self, key: HelpKey<'_, Arg>) -> Option<&str> {
+      let index = match key {
+        HelpKey::Help(Arg::Verbose) => 0,
+        HelpKey::Help(Arg::File) => 1,
+        HelpKey::Description => 2,
+        HelpKey::Usage => 3,
+        HelpKey::PositionalsHeader => 4,
+        HelpKey::OptionsHeader => 5,
+        HelpKey::OptionsPlaceholder | HelpKey::ReportBugs => return None,
+      };
+      Some(&self.0[index])
+    }
+  }
+
+  #[test]
+  fn test_catalog() {
+    let catalog = Catalog(["Sortie détaillée", "Fichier d'entrée", "Fait une chose.", "Utilisation :",
+      "Arguments positionnels :", "Options :"].map(String::from));
+    let mut out = String::new();
+    OPTIONS.with_catalog(&catalog).print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Utilisation : prog [-v] [file]
+
+Fait une chose.
+
+Arguments positionnels :
+  file .. Fichier d'entrée
+
+Options :
+  -v .... Sortie détaillée
+");
+    out.clear();
+    OPTIONS.with_catalog(&catalog).print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Fait une chose.
+
+Utilisation : prog [OPTIONS] [file]
+
+Arguments positionnels :
+  [file]  Fichier d'entrée
+
+Options :
+  -v      Sortie détaillée
+");
+  }
+}
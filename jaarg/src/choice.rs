@@ -0,0 +1,105 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+/// A value accepted by an option declared with [OptExtras::choices](crate::OptExtras::choices),
+/// with any number of synonyms that the handler receives as the canonical value.
+/// ```
+/// use jaarg::{Choice, Opt, OptExtras, Opts, ParseControl};
+///
+/// const OPTIONS: Opts<()> = Opts::new(&[
+///   Opt::value((), &["--confirm"], "answer").extras(&OptExtras::new().choices(&[
+///     Choice::new("yes").synonyms(&["y", "true"]),
+///     Choice::new("no").synonyms(&["n", "false"]),
+///   ])),
+/// ]);
+/// OPTIONS.parse("", ["--confirm=y"].iter(), |ctx| {
+///   assert_eq!(ctx.arg, "yes");
+///   Ok(ParseControl::Continue)
+/// }, |_, _| {});
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Choice<'o> {
+  pub(crate) value: &'o str,
+  pub(crate) synonyms: &'o [&'o str],
+}
+
+impl<'o> Choice<'o> {
+  /// A choice accepted as `value` only.
+  pub const fn new(value: &'o str) -> Self {
+    Self { value, synonyms: &[] }
+  }
+
+  /// Also accept each of `synonyms` as this choice, help only shows the canonical value.
+  pub const fn synonyms(mut self, synonyms: &'o [&'o str]) -> Self {
+    self.synonyms = synonyms;
+    self
+  }
+
+  /// The canonical value passed to the handler.
+  pub const fn value(&self) -> &'o str {
+    self.value
+  }
+
+  /// Returns true if `argument` is the canonical value or one of its synonyms.
+  pub fn matches(&self, argument: &str) -> bool {
+    self.value == argument || self.synonyms.contains(&argument)
+  }
+}
+
+/// Formatter for the canonical values of a list of choices, separated by commas.
+pub(crate) struct ChoiceList<'a>(pub(crate) &'a [Choice<'a>]);
+
+impl core::fmt::Display for ChoiceList<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for (i, choice) in self.0.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      f.write_str(choice.value)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Opt, OptExtras, Opts, ParseControl, ParseError, ParseErrorKind, ParseResult};
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::value(0, &["--color"], "when").extras(&OptExtras::new().choices(&[
+      Choice::new("always").synonyms(&["yes", "force"]),
+      Choice::new("never").synonyms(&["no", "none"]),
+      Choice::new("auto"),
+    ])),
+    Opt::positional(1, "shape").extras(&OptExtras::new().choices(&[Choice::new("circle"), Choice::new("square").synonyms(&["box"])])),
+  ]);
+
+  #[test]
+  fn test_choices() {
+    let mut values = [""; 2];
+    assert_eq!(OPTIONS.parse_slice("", &["--color", "force", "box"], |ctx| {
+      values[*ctx.id as usize] = ctx.arg;
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess);
+    assert_eq!(values, ["always", "square"]);
+
+    for (args, expected) in [
+      (&["--color=sometimes"][..], ParseError::ArgumentError("--color", "sometimes", ParseErrorKind::InvalidChoice)),
+      (&["triangle"], ParseError::ArgumentError("shape", "triangle", ParseErrorKind::InvalidChoice)),
+    ] {
+      let mut failed = None;
+      assert_eq!(OPTIONS.parse_slice("", args, |_| Ok(ParseControl::Continue), |_, error| failed = Some(error == expected)),
+        ParseResult::ExitFailure);
+      assert_eq!(failed, Some(true));
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "Flags don't take a value")]
+  fn test_flag_choices_disallowed() {
+    Opt::flag((), &["-f"]).extras(&OptExtras::new().choices(&[Choice::new("a")]));
+  }
+}
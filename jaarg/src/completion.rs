@@ -0,0 +1,399 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Choice, HelpWriter, HelpWriterContext, Opt, Opts};
+use crate::option::OptType;
+
+/// Shells that completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shell {
+  Bash,
+  Zsh,
+  Fish,
+}
+
+impl Shell {
+  /// Every supported shell, in the order they're listed by [Opt::generate_completions].
+  pub const ALL: [Shell; 3] = [Self::Bash, Self::Zsh, Self::Fish];
+  /// The shells as the choices of [Opt::generate_completions].
+  pub(crate) const CHOICES: [Choice<'static>; 3] = [Choice::new("bash"), Choice::new("zsh"), Choice::new("fish")];
+
+  /// The name of the shell, as accepted by [Opt::generate_completions].
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      Self::Bash => "bash",
+      Self::Zsh => "zsh",
+      Self::Fish => "fish",
+    }
+  }
+
+  /// Look up a shell by name.
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|shell| shell.as_str() == name)
+  }
+}
+
+impl<'o, ID> Opt<'o, ID> {
+  /// A hidden `--generate-completions <shell>` option that prints a completion script for the shell,
+  /// and ends parsing with [ParseResult::ExitSuccess](crate::ParseResult::ExitSuccess) without calling the handler.
+  /// The script goes to the sink set with [Opts::with_output], or to stdout with `features = ["std"]`.
+  /// ```
+  /// # use jaarg::{Opt, Opts, ParseResult};
+  /// const OPTIONS: Opts<&str> = Opts::new(&[
+  ///   Opt::flag("verbose", &["-v", "--verbose"]).help_text("Verbose output"),
+  ///   Opt::generate_completions("completions"),
+  /// ]).with_output(|script| print!("{script}"));
+  /// let result = OPTIONS.parse("tool", ["--generate-completions", "fish"].iter(), |_| unreachable!(), |_, _| {});
+  /// assert_eq!(result, ParseResult::ExitSuccess);
+  /// ```
+  #[inline]
+  pub const fn generate_completions(id: ID) -> Self {
+    Self::value(id, &["--generate-completions"], "shell")
+      .hide_usage(crate::OptHide::All)
+      .hide_completion()
+      .with_completions_flag()
+  }
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Write a completion script for `shell` into a formatter sink.
+  pub fn print_completions_to(&self, out: &mut impl core::fmt::Write, program_name: &str, shell: Shell
+  ) -> core::fmt::Result {
+    match shell {
+      Shell::Bash => self.print_help_to::<BashCompletionWriter<'_, ID, R>>(out, program_name),
+      Shell::Zsh => self.print_help_to::<ZshCompletionWriter<'_, ID, R>>(out, program_name),
+      Shell::Fish => self.print_help_to::<FishCompletionWriter<'_, ID, R>>(out, program_name),
+    }
+  }
+
+  /// Options offered by completions, see [Opt::hide_completion].
+  fn completable(&self) -> impl Iterator<Item = &'o Opt<'o, ID>> {
+    self.iter().filter(|o| o.is_completion_visible())
+  }
+}
+
+/// Writer that escapes single quotes for writing inside a single quoted shell string.
+struct QuoteEscaper<'a, 'b>(&'a mut core::fmt::Formatter<'b>);
+
+impl core::fmt::Write for QuoteEscaper<'_, '_> {
+  fn write_str(&mut self, s: &str) -> core::fmt::Result {
+    for (i, part) in s.split('\'').enumerate() {
+      if i > 0 {
+        self.0.write_str("'\\''")?;
+      }
+      self.0.write_str(part)?;
+    }
+    Ok(())
+  }
+}
+
+/// Formatter for a value single quoted for POSIX-like shells.
+struct Quoted<T>(T);
+
+impl<T: core::fmt::Display> core::fmt::Display for Quoted<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use core::fmt::Write;
+    f.write_str("'")?;
+    write!(QuoteEscaper(f), "{}", self.0)?;
+    f.write_str("'")
+  }
+}
+
+/// Formatter for a program name as part of a shell function name.
+struct FunctionName<'a>(&'a str);
+
+impl core::fmt::Display for FunctionName<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use core::fmt::Write;
+    self.0.chars().try_for_each(|c| f.write_char(if c.is_ascii_alphanumeric() { c } else { '_' }))
+  }
+}
+
+/// Formatter for the canonical values of choices separated by spaces.
+struct Words<'a>(&'a [Choice<'a>]);
+
+impl core::fmt::Display for Words<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for (i, choice) in self.0.iter().enumerate() {
+      if i > 0 {
+        f.write_str(" ")?;
+      }
+      f.write_str(choice.value())?;
+    }
+    Ok(())
+  }
+}
+
+/// Formatter for every name of the options offered by completions, separated by spaces.
+struct OptionNames<'a, ID, const R: usize>(&'a Opts<'a, ID, R>);
+
+impl<ID, const R: usize> core::fmt::Display for OptionNames<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut first = true;
+    for option in self.0.completable().filter(|o| !matches!(o.r#type, OptType::Positional)) {
+      for name in option.names() {
+        write!(f, "{}{name}", if first { "" } else { " " })?;
+        first = false;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// The first line of the help text of an option, for completion descriptions.
+fn summary<'a, ID, const R: usize>(options: &'a Opts<'a, ID, R>, option: &Opt<'a, ID>) -> Option<&'a str> {
+  options.localized_help(option).and_then(|help| help.lines().next())
+}
+
+/// Completion script for bash, completing option names, choices, and otherwise files.
+pub struct BashCompletionWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for BashCompletionWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for BashCompletionWriter<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let (options, function) = (self.0.options, FunctionName(self.0.program_name));
+    writeln!(f, "_{function}() {{")?;
+    writeln!(f, "  local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+
+    // Complete the value of the previous option
+    writeln!(f, "  case \"$prev\" in")?;
+    for option in options.completable().filter(|o| matches!(o.r#type, OptType::Value)) {
+      f.write_str("    ")?;
+      for (i, name) in option.names().iter().enumerate() {
+        write!(f, "{}{}", if i > 0 { "|" } else { "" }, Quoted(name))?;
+      }
+      match option.choices() {
+        [] => writeln!(f, ") COMPREPLY=($(compgen -f -- \"$cur\")); return ;;")?,
+        choices => writeln!(f, ") COMPREPLY=($(compgen -W {} -- \"$cur\")); return ;;", Quoted(Words(choices)))?,
+      }
+    }
+    writeln!(f, "  esac")?;
+
+    // Complete option names when the word starts with a flag character, otherwise fall back to files
+    writeln!(f, "  case \"$cur\" in")?;
+    f.write_str("    [")?;
+    options.flag_chars.chars().try_for_each(|c| write!(f, "\\{c}"))?;
+    writeln!(f, "]*) COMPREPLY=($(compgen -W {} -- \"$cur\")) ;;", Quoted(OptionNames(options)))?;
+    writeln!(f, "  esac")?;
+    writeln!(f, "}}")?;
+    writeln!(f, "complete -o default -F _{function} {}", Quoted(self.0.program_name))
+  }
+}
+
+/// Completion script for zsh, using `_arguments` with option descriptions.
+pub struct ZshCompletionWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for ZshCompletionWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for ZshCompletionWriter<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use core::fmt::Write;
+
+    /// Completes a value with the choices if there are any, or else files.
+    fn action(f: &mut QuoteEscaper, value_name: &str, choices: &[Choice]) -> core::fmt::Result {
+      match choices {
+        [] => write!(f, ":{value_name}:_files"),
+        choices => write!(f, ":{value_name}:({})", Words(choices)),
+      }
+    }
+
+    let (options, program_name, function) = (self.0.options, self.0.program_name, FunctionName(self.0.program_name));
+    writeln!(f, "#compdef {program_name}")?;
+    writeln!(f)?;
+    writeln!(f, "_{function}() {{")?;
+    f.write_str("  _arguments -s")?;
+    for option in options.completable() {
+      let names = match option.r#type {
+        OptType::Positional => &[""][..],
+        OptType::Flag | OptType::Value => option.names(),
+      };
+      for &name in names {
+        f.write_str(" \\\n    '")?;
+        let mut spec = QuoteEscaper(f);
+        if matches!(option.r#type, OptType::Positional) {
+          if !option.is_required() {
+            spec.write_char(':')?;
+          }
+          action(&mut spec, option.display_name(), option.choices())?;
+          f.write_char('\'')?;
+          continue;
+        }
+        // Long names take a value after an equals sign or in the next word, short names in the same or next word
+        spec.write_str(name)?;
+        spec.write_str(match (option.r#type, option.first_long_name() == Some(name)) {
+          (OptType::Flag, _) => "",
+          (_, true) => "=",
+          (_, false) => "+",
+        })?;
+        if let Some(help) = summary(options, option) {
+          spec.write_char('[')?;
+          for c in help.chars() {
+            if matches!(c, '[' | ']' | '\\') {
+              spec.write_char('\\')?;
+            }
+            spec.write_char(c)?;
+          }
+          spec.write_char(']')?;
+        }
+        for &value_name in option.value_names() {
+          action(&mut spec, value_name, option.choices())?;
+        }
+        f.write_char('\'')?;
+      }
+    }
+    writeln!(f)?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+    writeln!(f, "if [ \"$funcstack[1]\" = \"_{function}\" ]; then")?;
+    writeln!(f, "  _{function} \"$@\"")?;
+    writeln!(f, "else")?;
+    writeln!(f, "  compdef _{function} {}", Quoted(program_name))?;
+    writeln!(f, "fi")
+  }
+}
+
+/// Completion script for fish, with option descriptions.
+/// Only names starting with `-` can be completed by fish, options with none are left out.
+pub struct FishCompletionWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for FishCompletionWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for FishCompletionWriter<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let (options, program_name) = (self.0.options, Quoted(self.0.program_name));
+    for option in options.completable().filter(|o| !matches!(o.r#type, OptType::Positional)) {
+      let mut named = false;
+      for name in option.names() {
+        let (switch, name) = match (name.strip_prefix("--"), name.strip_prefix('-')) {
+          (Some(long), _) if !long.is_empty() => ("-l", long),
+          (_, Some(short)) if short.chars().count() == 1 => ("-s", short),
+          (_, Some(old)) if !old.is_empty() => ("-o", old),
+          _ => continue,
+        };
+        if !named {
+          write!(f, "complete -c {program_name}")?;
+          named = true;
+        }
+        write!(f, " {switch} {}", Quoted(name))?;
+      }
+      if !named {
+        continue;
+      }
+      match (option.r#type, option.choices()) {
+        (OptType::Flag, _) => {}
+        (_, []) => f.write_str(" -r")?,
+        (_, choices) => write!(f, " -x -a {}", Quoted(Words(choices)))?,
+      }
+      if let Some(help) = summary(options, option) {
+        write!(f, " -d {}", Quoted(help))?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate std;
+  use std::cell::RefCell;
+  use std::string::String;
+  use crate::{OptExtras, ParseControl, ParseResult};
+  use super::*;
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::help_flag(0, &["-h", "--help"]).help_text("Show help"),
+    Opt::value(1, &["-o", "--out"], "path").help_text("Output path [default: stdout]"),
+    Opt::value(2, &["--color"], "when").help_text("Don't colour\nunless asked")
+      .extras(&OptExtras::new().choices(&[Choice::new("always"), Choice::new("never")])),
+    Opt::flag(3, &["--debug"]).hide_completion(),
+    Opt::positional(4, "file").required(),
+    Opt::positional(5, "shape").extras(&OptExtras::new().choices(&[Choice::new("circle"), Choice::new("square")])),
+    Opt::generate_completions(6),
+  ]);
+
+  #[test]
+  fn test_bash() {
+    let mut out = String::new();
+    OPTIONS.print_completions_to(&mut out, "my-tool", Shell::Bash).unwrap();
+    assert_eq!(out, r#"_my_tool() {
+  local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+  case "$prev" in
+    '-o'|'--out') COMPREPLY=($(compgen -f -- "$cur")); return ;;
+    '--color') COMPREPLY=($(compgen -W 'always never' -- "$cur")); return ;;
+  esac
+  case "$cur" in
+    [\-]*) COMPREPLY=($(compgen -W '-h --help -o --out --color' -- "$cur")) ;;
+  esac
+}
+complete -o default -F _my_tool 'my-tool'
+"#);
+  }
+
+  #[test]
+  fn test_zsh() {
+    let mut out = String::new();
+    OPTIONS.print_completions_to(&mut out, "my-tool", Shell::Zsh).unwrap();
+    assert_eq!(out, r#"#compdef my-tool
+
+_my_tool() {
+  _arguments -s \
+    '-h[Show help]' \
+    '--help[Show help]' \
+    '-o+[Output path \[default: stdout\]]:path:_files' \
+    '--out=[Output path \[default: stdout\]]:path:_files' \
+    '--color=[Don'\''t colour]:when:(always never)' \
+    ':file:_files' \
+    '::shape:(circle square)'
+}
+
+if [ "$funcstack[1]" = "_my_tool" ]; then
+  _my_tool "$@"
+else
+  compdef _my_tool 'my-tool'
+fi
+"#);
+  }
+
+  #[test]
+  fn test_fish() {
+    let mut out = String::new();
+    OPTIONS.with_flag_chars("-/").print_completions_to(&mut out, "my-tool", Shell::Fish).unwrap();
+    assert_eq!(out, r#"complete -c 'my-tool' -s 'h' -l 'help' -d 'Show help'
+complete -c 'my-tool' -s 'o' -l 'out' -r -d 'Output path [default: stdout]'
+complete -c 'my-tool' -l 'color' -x -a 'always never' -d 'Don'\''t colour'
+"#);
+  }
+
+  #[test]
+  fn test_generate_completions() {
+    std::thread_local! {
+      static OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
+    }
+
+    let options = OPTIONS.with_output(|text| OUTPUT.with_borrow_mut(|output| output.push_str(text)));
+    for shell in Shell::ALL {
+      // Required arguments aren't checked, and the handler isn't called
+      assert_eq!(options.parse("my-tool", ["--generate-completions", shell.as_str()].iter(),
+        |ctx| panic!("unreachable: {}", ctx.id), |_, error| panic!("unreachable: {error:?}")), ParseResult::ExitSuccess);
+      let mut expected = String::new();
+      OPTIONS.print_completions_to(&mut expected, "my-tool", shell).unwrap();
+      assert_eq!(OUTPUT.take(), expected);
+      assert_eq!(Shell::from_name(shell.as_str()), Some(shell));
+    }
+    let mut failed = None;
+    assert_eq!(options.parse("my-tool", ["--generate-completions=tcsh"].iter(), |_| Ok(ParseControl::Continue),
+      |_, error| failed = Some(error == crate::ParseError::ArgumentError("--generate-completions", "tcsh",
+        crate::ParseErrorKind::InvalidChoice))), ParseResult::ExitFailure);
+    assert_eq!(failed, Some(true));
+  }
+}
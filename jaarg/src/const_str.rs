@@ -3,9 +3,25 @@
  * SPDX-License-Identifier: MIT OR Apache-2.0
  */
 
+//! Const fn string utilities, for validating option tables at compile time and writing custom help writers,
+//! until the equivalent `str` methods can be used in const contexts.
+//!
+//! ```
+//! use jaarg::const_str;
+//!
+//! const NAME: &str = "--pizza";
+//! const _: () = assert!(const_str::starts_with(NAME, "--") && !const_str::eq(NAME, "--pasta"));
+//! const _: () = assert!(const_str::CharIterator::from("比薩").count() == 2);
+//! const _: () = assert!(const_str::display_width("比薩") == 4);
+//! ```
+
+// Const fns can't use `get`, every index here is bounded by a preceding length check
+#![allow(clippy::indexing_slicing)]
+
 /// Fully const fn nostd UTF-8 character iterator.
 /// Assumes a well-formed UTF-8 input string. Doesn't take into account graphemes.
-pub(crate) struct CharIterator<'a> {
+#[derive(Debug, Clone)]
+pub struct CharIterator<'a> {
   bytes: &'a [u8],
   index: usize
 }
@@ -13,7 +29,7 @@ pub(crate) struct CharIterator<'a> {
 impl<'a> CharIterator<'a> {
   /// Create a char iterator from an immutable string slice.
   #[inline]
-  pub(crate) const fn from(value: &'a str) -> Self {
+  pub const fn from(value: &'a str) -> Self {
     Self {
       bytes: value.as_bytes(),
       index: 0,
@@ -24,7 +40,7 @@ impl<'a> CharIterator<'a> {
 
 impl CharIterator<'_> {
   /// Gets a count of the number of Unicode characters (not graphemes) in the string.
-  pub(crate) const fn count(&self) -> usize {
+  pub const fn count(&self) -> usize {
     let len = self.bytes.len();
     let mut count = 0;
     let mut i = 0;
@@ -39,7 +55,9 @@ impl CharIterator<'_> {
   }
 
   /// Gets the next character in a well-formed UTF-8 string, or None for end of string or errors.
-  pub(crate) const fn next(&mut self) -> Option<char> {
+  // Not an Iterator, whose by-value methods would shadow the const ones
+  #[allow(clippy::should_implement_trait)]
+  pub const fn next(&mut self) -> Option<char> {
     /// UTF-8 2-byte flag bits
     const MULTIBYTE_2: u8 = 0b1100_0000;
     /// UTF-8 3-byte flag bits
@@ -124,6 +142,54 @@ impl CharIterator<'_> {
   }
 }
 
+/// Const fn byte-wise string equality, until `PartialEq` for `str` can be used in const contexts.
+pub const fn eq(lhs: &str, rhs: &str) -> bool {
+  lhs.len() == rhs.len() && starts_with(lhs, rhs)
+}
+
+/// Const fn equivalent of [str::starts_with] for a string prefix.
+pub const fn starts_with(string: &str, prefix: &str) -> bool {
+  let (string, prefix) = (string.as_bytes(), prefix.as_bytes());
+  if prefix.len() > string.len() {
+    return false;
+  }
+  let mut i = 0;
+  while i < prefix.len() {
+    if string[i] != prefix[i] {
+      return false;
+    }
+    i += 1;
+  }
+  true
+}
+
+/// Approximate number of terminal columns a string occupies, counting East Asian wide characters
+/// and emoji as two columns, and control characters, combining marks, and joiners as none.
+/// Doesn't take into account graphemes, so emoji sequences are wider than most terminals display them.
+pub const fn display_width(string: &str) -> usize {
+  let mut chars = CharIterator::from(string);
+  let mut width = 0;
+  while let Some(c) = chars.next() {
+    width += char_width(c);
+  }
+  width
+}
+
+/// Terminal columns occupied by a single character, see [display_width].
+pub const fn char_width(c: char) -> usize {
+  match c as u32 {
+    // Control characters
+    ..0x20 | 0x7F..0xA0 => 0,
+    // Combining marks, zero width spaces & joiners, and variation selectors
+    0x0300..0x0370 | 0x1AB0..0x1B00 | 0x1DC0..0x1E00 | 0x200B..0x2010 | 0x20D0..0x2100
+      | 0xFE00..0xFE10 | 0xFE20..0xFE30 | 0xE0100..0xE01F0 => 0,
+    // Hangul Jamo, CJK, Hangul syllables, fullwidth forms, and emoji
+    0x1100..0x1160 | 0x2E80..0x303F | 0x3041..0x4DC0 | 0x4E00..0xA4D0
+      | 0xAC00..0xD7A4 | 0xF900..0xFB00 | 0xFE30..0xFE50 | 0xFF00..0xFF61 | 0xFFE0..0xFFE7
+      | 0x1F300..0x1F650 | 0x1F900..0x1FA00 | 0x20000..0x2FFFE | 0x30000..0x3FFFE => 2,
+    _ => 1,
+  }
+}
 
 #[cfg(test)]
 mod tests {
@@ -138,4 +204,29 @@ mod tests {
       assert_eq!(it.next(), None);
     }
   }
+
+  #[test]
+  fn test_eq() {
+    assert!(eq("", ""));
+    assert!(eq("--pizza", "--pizza"));
+    assert!(!eq("--pizza", "--pizzas"));
+    assert!(!eq("-p", "-q"));
+    assert!(!eq("🍕", ""));
+  }
+
+  #[test]
+  fn test_starts_with() {
+    for (string, prefix) in [("--pizza", "--"), ("--pizza", ""), ("", ""), ("🍕", "🍕"), ("-p", "--"), ("-", "--")] {
+      assert_eq!(starts_with(string, prefix), string.starts_with(prefix), "'{string}' starts with '{prefix}'");
+    }
+  }
+
+  #[test]
+  fn test_display_width() {
+    for (s, width) in [
+      ("pizza", 5), ("/ˈpitt͡sə/", 9), ("🍕", 2), ("比薩", 4), ("ピザ", 4), ("ｐｉｚｚａ", 10), ("e\u{301}\t", 1),
+    ] {
+      assert_eq!(display_width(s), width, "width of '{s}'");
+    }
+  }
 }
@@ -0,0 +1,226 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+//! Loader for `.env` files of `KEY=VALUE` pairs, so tools that take defaults from the environment
+//! during development don't need a dotenv crate. Variables set in the process environment take
+//! precedence over the file, as with other dotenv loaders.
+//!
+//! Requires `features = ["std"]`.
+
+extern crate std;
+
+use std::string::String;
+use std::vec::Vec;
+use std::path::Path;
+use std::{env, fs, io};
+
+/// Errors from loading a `.env` file.
+#[derive(Debug)]
+pub enum Error {
+  /// The file couldn't be read.
+  Io(io::Error),
+  /// The line with this 1-based number isn't a comment or a `KEY=VALUE` pair.
+  InvalidLine(usize),
+  /// The quoted value starting on this 1-based line number is missing its closing quote.
+  UnterminatedQuote(usize),
+}
+
+impl core::fmt::Display for Error {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Io(e) => write!(f, "{e}"),
+      Self::InvalidLine(line) => write!(f, "Expected KEY=VALUE on line {line}"),
+      Self::UnterminatedQuote(line) => write!(f, "Unterminated quoted value on line {line}"),
+    }
+  }
+}
+
+impl core::error::Error for Error {}
+
+impl From<io::Error> for Error {
+  fn from(e: io::Error) -> Self { Self::Io(e) }
+}
+
+/// Variables read from a `.env` file.
+/// ```
+/// use jaarg::dotenv::DotEnv;
+///
+/// let env = DotEnv::parse("# Local overrides\nexport JOBS=4\nGREETING=\"hello\\nworld\"\n").unwrap();
+/// assert_eq!(env.get("JOBS"), Some("4"));
+/// assert_eq!(env.get("GREETING"), Some("hello\nworld"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DotEnv {
+  vars: Vec<(String, String)>,
+}
+
+impl DotEnv {
+  /// Reads variables from the file at `path`.
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+    Self::parse(&fs::read_to_string(path)?)
+  }
+
+  /// Reads variables from `.env` in the current directory, or none if there isn't one.
+  pub fn local() -> Result<Self, Error> {
+    match fs::read_to_string(".env") {
+      Ok(text) => Self::parse(&text),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Parses the contents of a `.env` file.
+  ///
+  /// Blank lines and lines starting with `#` are skipped, and keys may be prefixed with `export`.
+  /// Unquoted values are trimmed, values may also be single quoted, taken literally, or double quoted, where `\n`,
+  /// `\t`, `\"` and `\\` are unescaped; both kinds of quoted value can span lines. A `#` preceded by whitespace starts
+  /// a comment after an unquoted value. Later definitions of a key replace earlier ones.
+  pub fn parse(text: &str) -> Result<Self, Error> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    while let Some((number, line)) = lines.next() {
+      // Only the start is trimmed here, whitespace may be part of a quoted value spanning lines
+      let line = line.trim_start();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+      let (key, value) = line.split_once('=').ok_or(Error::InvalidLine(number))?;
+      let key = key.trim_end();
+      if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+        return Err(Error::InvalidLine(number));
+      }
+
+      let value = value.trim_start();
+      let value = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+          let mut quoted = String::new();
+          let mut rest = value.strip_prefix(quote).unwrap_or(value);
+          loop {
+            if let Some(end) = closing_quote(rest, quote) {
+              let (inner, trailing) = rest.split_at(end);
+              unquote(&mut quoted, inner, quote);
+              let trailing = trailing.strip_prefix(quote).unwrap_or(trailing).trim_start();
+              if !trailing.is_empty() && !trailing.starts_with('#') {
+                return Err(Error::InvalidLine(number));
+              }
+              break;
+            }
+            unquote(&mut quoted, rest, quote);
+            quoted.push('\n');
+            rest = lines.next().ok_or(Error::UnterminatedQuote(number))?.1;
+          }
+          quoted
+        }
+        _ => {
+          let end = value.find(" #").or_else(|| value.find("\t#")).unwrap_or(value.len());
+          String::from(value.split_at(end).0.trim_end())
+        }
+      };
+
+      match vars.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value,
+        None => vars.push((String::from(key), value)),
+      }
+    }
+    Ok(Self { vars })
+  }
+
+  /// The value of `key` in the file.
+  pub fn get(&self, key: &str) -> Option<&str> {
+    self.vars.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+  }
+
+  /// The value of `key` in the process environment, falling back to the file.
+  /// Environment variables that aren't valid unicode are skipped.
+  pub fn var(&self, key: &str) -> Option<String> {
+    env::var(key).ok().or_else(|| self.get(key).map(String::from))
+  }
+
+  /// Iterates over the variables in the file, in the order they were first defined.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+    self.vars.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+  }
+}
+
+/// Byte offset of the quote closing a value, skipping escaped quotes in double quoted values.
+fn closing_quote(s: &str, quote: char) -> Option<usize> {
+  let mut escaped = false;
+  for (i, c) in s.char_indices() {
+    match c {
+      _ if escaped => escaped = false,
+      '\\' if quote == '"' => escaped = true,
+      c if c == quote => return Some(i),
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Append the inside of a quoted value, unescaping double quoted values.
+fn unquote(out: &mut String, s: &str, quote: char) {
+  if quote == '\'' {
+    out.push_str(s);
+    return;
+  }
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    match (c, chars.clone().next()) {
+      ('\\', Some(e @ ('n' | 't' | '"' | '\\'))) => {
+        out.push(match e { 'n' => '\n', 't' => '\t', e => e });
+        chars.next();
+      }
+      (c, _) => out.push(c),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::string::ToString;
+
+  #[test]
+  fn test_parse() {
+    let env = DotEnv::parse(r#"
+# Development defaults
+export JOBS = 4
+OUTPUT=out/dir  # trailing comment
+EMPTY=
+HASH=a#b
+LITERAL='C:\path\n' # comment
+QUOTED="say \"hi\"\tthere"
+MULTI="first
+second"
+JOBS=8
+"#).unwrap();
+    assert_eq!(env.iter().collect::<Vec<_>>(), [
+      ("JOBS", "8"), ("OUTPUT", "out/dir"), ("EMPTY", ""), ("HASH", "a#b"), ("LITERAL", "C:\\path\\n"),
+      ("QUOTED", "say \"hi\"\tthere"), ("MULTI", "first\nsecond"),
+    ]);
+    assert_eq!(env.get("MISSING"), None);
+    // Whitespace inside quoted values spanning lines is kept
+    let env = DotEnv::parse("A=\"x  \n  y  \"  \nB='  z  '").unwrap();
+    assert_eq!(env.iter().collect::<Vec<_>>(), [("A", "x  \n  y  "), ("B", "  z  ")]);
+
+    for (text, expected) in [
+      ("JOBS", "Expected KEY=VALUE on line 1"),
+      ("\n=4", "Expected KEY=VALUE on line 2"),
+      ("MY KEY=4", "Expected KEY=VALUE on line 1"),
+      ("A='b' c", "Expected KEY=VALUE on line 1"),
+      ("A=1\nB=\"open\nstill open", "Unterminated quoted value on line 2"),
+    ] {
+      assert_eq!(DotEnv::parse(text).unwrap_err().to_string(), expected);
+    }
+  }
+
+  #[test]
+  fn test_var() {
+    let env = DotEnv::parse("PATH=from-file\nJAARG_DOTENV_TEST_UNSET=from-file").unwrap();
+    // The process environment takes precedence
+    assert_eq!(env.var("PATH"), env::var("PATH").ok());
+    assert_eq!(env.var("JAARG_DOTENV_TEST_UNSET").as_deref(), Some("from-file"));
+  }
+}
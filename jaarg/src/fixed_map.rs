@@ -0,0 +1,210 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{HandlerResult, Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult};
+
+/// Fixed-capacity map of option IDs to arguments borrowed from the argument list,
+/// for targets without an allocator. Produced by [Opts::parse_fixed_map].
+#[derive(Debug, Clone)]
+pub struct FixedMap<'s, ID, const N: usize> {
+  entries: [Option<(ID, &'s str)>; N],
+  len: usize,
+}
+
+impl<'s, ID: PartialEq + Copy, const N: usize> FixedMap<'s, ID, N> {
+  /// Create an empty map.
+  pub const fn new() -> Self {
+    Self { entries: [None; N], len: 0 }
+  }
+
+  /// Gets the argument for an option, flags have an empty argument.
+  pub fn get(&self, id: ID) -> Option<&'s str> {
+    self.iter().find_map(|(i, arg)| (i == id).then_some(arg))
+  }
+
+  /// Returns true if the option was matched.
+  pub fn contains(&self, id: ID) -> bool {
+    self.get(id).is_some()
+  }
+
+  /// Number of distinct options matched.
+  #[inline(always)]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns true if no options were matched.
+  #[inline(always)]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Iterate the matched options and their arguments, in the order they were first matched.
+  pub fn iter(&self) -> impl Iterator<Item = (ID, &'s str)> + '_ {
+    self.entries.iter().flatten().copied()
+  }
+
+  /// Insert or replace the argument for an option, returning false if the map is full.
+  pub fn insert(&mut self, id: ID, arg: &'s str) -> bool {
+    if let Some(entry) = self.entries.iter_mut().flatten().find(|(i, _)| *i == id) {
+      entry.1 = arg;
+    } else if let Some(slot) = self.entries.get_mut(self.len) {
+      *slot = Some((id, arg));
+      self.len += 1;
+    } else {
+      return false;
+    }
+    true
+  }
+}
+
+impl<ID: PartialEq + Copy, const N: usize> Default for FixedMap<'_, ID, N> {
+  fn default() -> Self { Self::new() }
+}
+
+/// The result of parsing commands with [Opts::parse_fixed_map].
+pub enum ParseFixedMapResult<'s, ID, const N: usize> {
+  Map(FixedMap<'s, ID, N>),
+  ExitSuccess, ExitFailure
+}
+
+impl<'o, ID: PartialEq + Copy, const R: usize> Opts<'o, ID, R> {
+  /// Parse a list of argument strings and return the results in a [FixedMap] borrowing from them,
+  /// the alloc-free equivalent of `parse_map`.
+  ///
+  /// Fails with [ParseError::TooManyValues] once more than `N` different options are given,
+  /// so `N` should be at least the number of options.
+  pub fn parse_fixed_map<'s, const N: usize>(&self, program_name: &'s str, args: &[&'s str],
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseFixedMapResult<'s, ID, N> where 'o: 's {
+    let mut out = FixedMap::new();
+    match self.parse_slice(program_name, args, |ctx| {
+      if ctx.option.is_help() {
+        help(program_name);
+        return Ok(ParseControl::Quit);
+      }
+      match out.insert(*ctx.id, ctx.arg) {
+        true => Ok(ParseControl::Continue),
+        false => Err(ParseError::TooManyValues(ctx.name)),
+      }
+    }, error) {
+      ParseResult::ContinueSuccess => ParseFixedMapResult::Map(out),
+      ParseResult::ExitSuccess => ParseFixedMapResult::ExitSuccess,
+      ParseResult::ExitFailure => ParseFixedMapResult::ExitFailure,
+    }
+  }
+}
+
+/// Caller-provided storage for collecting the values of a repeated option without allocating,
+/// for use with [Opts::parse_slice].
+pub struct ValueSlots<'b, 's> {
+  slots: &'b mut [Option<&'s str>],
+  len: usize,
+}
+
+impl<'b, 's> ValueSlots<'b, 's> {
+  /// Wrap a buffer of empty slots, its length is the maximum number of values that can be collected.
+  pub fn new(slots: &'b mut [Option<&'s str>]) -> Self {
+    slots.fill(None);
+    Self { slots, len: 0 }
+  }
+
+  /// Store the argument of the matched option,
+  /// failing with [ParseError::TooManyValues] if the buffer is full.
+  pub fn push<ID>(&mut self, ctx: &ParseHandlerContext<'s, ID>) -> HandlerResult<'s, ()> {
+    let slot = self.slots.get_mut(self.len).ok_or(ParseError::TooManyValues(ctx.name))?;
+    *slot = Some(ctx.arg);
+    self.len += 1;
+    Ok(())
+  }
+
+  /// Number of values collected.
+  #[inline(always)]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns true if no values were collected.
+  #[inline(always)]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Iterate the collected values in the order they were given.
+  pub fn iter(&self) -> impl Iterator<Item = &'s str> + '_ {
+    self.slots.iter().flatten().copied()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+
+  const OPTIONS: Opts<&'static str> = Opts::new(&[
+    Opt::help_flag("help", &["-h"]),
+    Opt::flag("flag", &["-f"]),
+    Opt::value("value", &["-v"], "value"),
+    Opt::positional("positional", "positional"),
+  ]);
+
+  #[test]
+  fn test_parse_fixed_map() {
+    let ParseFixedMapResult::Map(map) = OPTIONS.parse_fixed_map::<4>("", &["-v", "a", "-f", "b", "-v=c"],
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get("value"), Some("c"));
+    assert_eq!(map.get("flag"), Some(""));
+    assert_eq!(map.get("positional"), Some("b"));
+    assert!(!map.contains("help"));
+  }
+
+  #[test]
+  fn test_parse_fixed_map_capacity() {
+    assert!(matches!(OPTIONS.parse_fixed_map::<3>("", &["-v", "a", "b"], |_| {}, |_, _| {}),
+      ParseFixedMapResult::Map(map) if map.len() == 2));
+    let mut failed = None;
+    assert!(matches!(OPTIONS.parse_fixed_map::<2>("", &["-v", "a", "b", "-f"], |_| {},
+      |_, error| failed = Some(error == ParseError::TooManyValues("-f"))), ParseFixedMapResult::ExitFailure));
+    assert_eq!(failed, Some(true));
+  }
+
+  #[test]
+  fn test_fixed_map_full() {
+    let mut map = FixedMap::<u32, 1>::new();
+    assert!(map.insert(0, "a"));
+    assert!(map.insert(0, "b"));
+    assert!(!map.insert(1, "c"));
+    let mut iter = map.iter();
+    assert_eq!(iter.next(), Some((0, "b")));
+    assert_eq!(iter.next(), None);
+  }
+
+  #[test]
+  fn test_value_slots() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-I"], "dir"),
+      Opt::flag(1, &["-f"]),
+    ]);
+    let mut buffer = [None; 2];
+    let mut includes = ValueSlots::new(&mut buffer);
+    assert!(matches!(OPTIONS.parse_slice("", &["-I", "a", "-f", "-I=b"], |ctx| {
+      if *ctx.id == 0 {
+        includes.push(&ctx)?;
+      }
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(includes.len(), 2);
+    let mut iter = includes.iter();
+    assert_eq!((iter.next(), iter.next(), iter.next()), (Some("a"), Some("b"), None));
+
+    let mut buffer = [None; 2];
+    let mut includes = ValueSlots::new(&mut buffer);
+    assert!(matches!(OPTIONS.parse_slice("", &["-I", "a", "-I", "b", "-I", "c"], |ctx| {
+      includes.push(&ctx)?;
+      Ok(ParseControl::Continue)
+    }, |_, error| assert!(matches!(error, ParseError::TooManyValues("-I")))), ParseResult::ExitFailure));
+  }
+}
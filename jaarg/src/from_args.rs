@@ -0,0 +1,115 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult};
+
+/// Types that can be filled in from command line arguments using an associated options table.
+///
+/// Implementors provide [FromArgs::OPTIONS] and [FromArgs::handle], which is called for each match
+/// except for help flags, which are handled by the provided parse methods.
+pub trait FromArgs: Default {
+  /// Identifier type of the options table.
+  type Id: 'static;
+
+  /// Options table used to parse into this type.
+  const OPTIONS: Opts<'static, Self::Id>;
+
+  /// Fill in the matched argument.
+  fn handle<'a>(&mut self, ctx: ParseHandlerContext<Self::Id>) -> Result<ParseControl, ParseError<'a>>;
+
+  /// Parse an iterator of strings as arguments into a new instance of this type.
+  fn parse_from<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(program_name: &str, args: I,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError),
+  ) -> FromArgsResult<Self> {
+    let mut out = Self::default();
+    match Self::OPTIONS.parse(program_name, args, |ctx| {
+      if ctx.option.is_help() {
+        help(program_name);
+        Ok(ParseControl::Quit)
+      } else {
+        out.handle(ctx)
+      }
+    }, error) {
+      ParseResult::ContinueSuccess => FromArgsResult::Parsed(out),
+      ParseResult::ExitSuccess => FromArgsResult::ExitSuccess,
+      ParseResult::ExitFailure => FromArgsResult::ExitFailure,
+    }
+  }
+
+  /// Parse arguments from the command line into a new instance of this type.
+  /// Help and errors are formatted in a standard user-friendly format.
+  ///
+  /// Requires `features = ["std"]`.
+  #[cfg(feature = "std")]
+  fn parse_easy() -> FromArgsResult<Self> {
+    let mut out = Self::default();
+    match Self::OPTIONS.parse_easy(|ctx| {
+      if ctx.option.is_help() {
+        Self::OPTIONS.print_full_help(ctx.program_name);
+        Ok(ParseControl::Quit)
+      } else {
+        out.handle(ctx)
+      }
+    }) {
+      ParseResult::ContinueSuccess => FromArgsResult::Parsed(out),
+      ParseResult::ExitSuccess => FromArgsResult::ExitSuccess,
+      ParseResult::ExitFailure => FromArgsResult::ExitFailure,
+    }
+  }
+}
+
+/// The result of parsing commands with [FromArgs::parse_from] or [FromArgs::parse_easy].
+pub enum FromArgsResult<T> {
+  Parsed(T),
+  ExitSuccess, ExitFailure
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+
+  #[derive(Default)]
+  struct Config { verbose: bool, jobs: u32, files: usize }
+
+  enum Arg { Help, Verbose, Jobs, File }
+
+  impl FromArgs for Config {
+    type Id = Arg;
+    const OPTIONS: Opts<'static, Arg> = Opts::new(&[
+      Opt::help_flag(Arg::Help, &["-h"]),
+      Opt::flag(Arg::Verbose, &["-v"]),
+      Opt::value(Arg::Jobs, &["-j"], "jobs"),
+      Opt::positional(Arg::File, "file"),
+      Opt::positional(Arg::File, "file"),
+    ]);
+
+    fn handle<'a>(&mut self, ctx: ParseHandlerContext<Arg>) -> Result<ParseControl, ParseError<'a>> {
+      match ctx.id {
+        Arg::Help => unreachable!(),
+        Arg::Verbose => self.verbose = true,
+        Arg::Jobs => self.jobs = ctx.arg.parse()?,
+        Arg::File => self.files += 1,
+      }
+      Ok(ParseControl::Continue)
+    }
+  }
+
+  #[test]
+  fn test_parse_from() {
+    let FromArgsResult::Parsed(config) = Config::parse_from("", ["a", "-v", "-j", "4", "b"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert!(config.verbose);
+    assert_eq!(config.jobs, 4);
+    assert_eq!(config.files, 2);
+
+    let help = core::cell::Cell::new(false);
+    assert!(matches!(Config::parse_from("", ["-h"].iter(), |_| help.set(true), |_, error| panic!("unreachable: {error:?}")),
+      FromArgsResult::ExitSuccess));
+    assert!(help.get());
+    assert!(matches!(Config::parse_from("", ["-j", "x"].iter(), |_| panic!("unreachable"), |_, _| {}),
+      FromArgsResult::ExitFailure));
+  }
+}
@@ -0,0 +1,369 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+//! Facade mimicking the `getopts` crate's [Options] and [Matches] types on top of jaarg,
+//! to ease migrating existing code.
+//!
+//! Differences from `getopts`: optional arguments (`optflagopt`) aren't supported,
+//! short options can't be grouped (`-abc`) or take attached values (`-ofile`, use `-o=file`),
+//! and querying an option that wasn't defined returns nothing instead of panicking.
+//!
+//! Requires `features = ["alloc"]`.
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use core::str::FromStr;
+use crate::{Opt, Opts, ParseControl, ParseError, StandardShortUsageWriter};
+
+/// Whether an option takes an argument.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HasArg {
+  Yes,
+  No,
+}
+
+/// How many times an option may occur.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Occur {
+  /// The option must occur exactly once.
+  Req,
+  /// The option may occur at most once.
+  Optional,
+  /// The option may occur any number of times.
+  Multi,
+}
+
+/// Failure while parsing arguments with [Options::parse].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fail {
+  ArgumentMissing(String),
+  UnrecognizedOption(String),
+  OptionMissing(String),
+  OptionDuplicated(String),
+  UnexpectedArgument(String),
+  /// An option name was defined by more than one option group.
+  OptionDefinedTwice(String),
+}
+
+impl core::fmt::Display for Fail {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::ArgumentMissing(nm) => write!(f, "Argument to option '{nm}' missing"),
+      Self::UnrecognizedOption(nm) => write!(f, "Unrecognized option: '{nm}'"),
+      Self::OptionMissing(nm) => write!(f, "Required option '{nm}' missing"),
+      Self::OptionDuplicated(nm) => write!(f, "Option '{nm}' given more than once"),
+      Self::UnexpectedArgument(nm) => write!(f, "Option '{nm}' does not take an argument"),
+      Self::OptionDefinedTwice(nm) => write!(f, "Option '{nm}' is defined more than once"),
+    }
+  }
+}
+
+impl core::error::Error for Fail {}
+
+struct OptGroup {
+  short_name: String,
+  long_name: String,
+  hint: String,
+  desc: String,
+  hasarg: HasArg,
+  occur: Occur,
+}
+
+impl OptGroup {
+  fn names(&self) -> Vec<String> {
+    let short = (!self.short_name.is_empty()).then(|| format!("-{}", self.short_name));
+    let long = (!self.long_name.is_empty()).then(|| format!("--{}", self.long_name));
+    short.into_iter().chain(long).collect()
+  }
+}
+
+/// A description of the options that a program can handle.
+#[derive(Default)]
+pub struct Options {
+  grps: Vec<OptGroup>,
+}
+
+impl Options {
+  /// Create a blank set of options.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a generic option group, stating all parameters explicitly.
+  pub fn opt(&mut self, short_name: &str, long_name: &str, desc: &str, hint: &str, hasarg: HasArg, occur: Occur
+  ) -> &mut Self {
+    assert!(!short_name.is_empty() || !long_name.is_empty(), "Option must have a short or long name");
+    self.grps.push(OptGroup {
+      short_name: short_name.into(),
+      long_name: long_name.into(),
+      hint: hint.into(),
+      desc: desc.into(),
+      hasarg,
+      occur,
+    });
+    self
+  }
+
+  /// Create an option that is optional and does not take an argument.
+  pub fn optflag(&mut self, short_name: &str, long_name: &str, desc: &str) -> &mut Self {
+    self.opt(short_name, long_name, desc, "", HasArg::No, Occur::Optional)
+  }
+
+  /// Create an option that is optional, does not take an argument, and may occur multiple times.
+  pub fn optflagmulti(&mut self, short_name: &str, long_name: &str, desc: &str) -> &mut Self {
+    self.opt(short_name, long_name, desc, "", HasArg::No, Occur::Multi)
+  }
+
+  /// Create an option that is optional and takes an argument.
+  pub fn optopt(&mut self, short_name: &str, long_name: &str, desc: &str, hint: &str) -> &mut Self {
+    self.opt(short_name, long_name, desc, hint, HasArg::Yes, Occur::Optional)
+  }
+
+  /// Create an option that is optional, takes an argument, and may occur multiple times.
+  pub fn optmulti(&mut self, short_name: &str, long_name: &str, desc: &str, hint: &str) -> &mut Self {
+    self.opt(short_name, long_name, desc, hint, HasArg::Yes, Occur::Multi)
+  }
+
+  /// Create an option that is required and takes an argument.
+  pub fn reqopt(&mut self, short_name: &str, long_name: &str, desc: &str, hint: &str) -> &mut Self {
+    self.opt(short_name, long_name, desc, hint, HasArg::Yes, Occur::Req)
+  }
+
+  /// Each option group's names, leaving out names defined by an earlier group and returning the first of those.
+  fn names(&self) -> (Vec<Vec<String>>, Option<String>) {
+    let mut duplicate = None;
+    let mut names: Vec<Vec<String>> = Vec::with_capacity(self.grps.len());
+    for grp in &self.grps {
+      let (unique, repeated): (Vec<String>, Vec<String>) = grp.names().into_iter()
+        .partition(|name| !names.iter().flatten().any(|n| n == name));
+      duplicate = duplicate.or_else(|| repeated.into_iter().next());
+      names.push(unique);
+    }
+    (names, duplicate)
+  }
+
+  /// Parse command line arguments according to the provided options.
+  /// Arguments following `--` are always treated as free arguments.
+  pub fn parse<S: AsRef<str>>(&self, args: impl IntoIterator<Item = S>) -> Result<Matches, Fail> {
+    const FREE: usize = usize::MAX;
+
+    let args: Vec<S> = args.into_iter().collect();
+    let (args, trailing) = match args.iter().position(|a| a.as_ref() == "--") {
+      Some(i) => args.split_at(i),
+      None => (args.as_slice(), &[][..]),
+    };
+    let trailing = trailing.get(1..).unwrap_or_default();
+
+    // Build an options table borrowing from the option groups, jaarg requires unique names
+    let (names, duplicate) = self.names();
+    if let Some(name) = duplicate {
+      return Err(Fail::OptionDefinedTwice(name.trim_start_matches('-').into()));
+    }
+    let names: Vec<Vec<&str>> = names.iter().map(|n| n.iter().map(String::as_str).collect()).collect();
+    let mut option_list: Vec<Opt<usize>> = self.grps.iter().zip(&names).enumerate().map(|(i, (grp, names))| {
+      let option = match grp.hasarg {
+        HasArg::Yes => Opt::value(i, names, &grp.hint),
+        HasArg::No => Opt::flag(i, names),
+      };
+      if grp.occur == Occur::Req { option.required() } else { option }
+    }).collect();
+    // jaarg has no catch-all positional, so declare enough for every argument to be a free argument
+    option_list.extend((0..args.len()).map(|_| Opt::positional(FREE, "free")));
+    let options = Opts::new(&option_list);
+
+    let mut vals: Vec<Vec<Option<String>>> = self.grps.iter().map(|_| Vec::new()).collect();
+    let mut free: Vec<String> = Vec::new();
+    let mut duplicated = None;
+    let mut fail = None;
+    let _ = options.parse("", args.iter(), |ctx| {
+      if *ctx.id == FREE {
+        free.push(ctx.arg.into());
+        return Ok(ParseControl::Continue);
+      }
+      let (Some(grp), Some(vals)) = (self.grps.get(*ctx.id), vals.get_mut(*ctx.id)) else {
+        return Ok(ParseControl::Continue);
+      };
+      if grp.occur != Occur::Multi && !vals.is_empty() {
+        duplicated = Some(Fail::OptionDuplicated(ctx.name.trim_start_matches('-').into()));
+        return Ok(ParseControl::Quit);
+      }
+      vals.push((grp.hasarg == HasArg::Yes).then(|| ctx.arg.into()));
+      Ok(ParseControl::Continue)
+    }, |_, error| {
+      fail = Some(match error {
+        ParseError::UnknownOption(o) => Fail::UnrecognizedOption(o.trim_start_matches('-').into()),
+        ParseError::ExpectArgument(o) => Fail::ArgumentMissing(o.trim_start_matches('-').into()),
+        ParseError::UnexpectedArgument(o) => Fail::UnexpectedArgument(o.trim_start_matches('-').into()),
+        ParseError::RequiredParameter(o) => Fail::OptionMissing(o.trim_start_matches('-').into()),
+        other => Fail::UnrecognizedOption(other.to_string()),
+      });
+    });
+    if let Some(fail) = duplicated.or(fail) {
+      return Err(fail);
+    }
+    free.extend(trailing.iter().map(|a| a.as_ref().into()));
+    let names = self.grps.iter().map(|g| (g.short_name.clone(), g.long_name.clone())).collect();
+    Ok(Matches { names, vals, free })
+  }
+
+  /// Derive a short one-line usage summary from a set of options.
+  /// Names defined more than once are only shown for the first option group that defines them,
+  /// groups left without a name are omitted.
+  pub fn short_usage(&self, program_name: &str) -> String {
+    let (names, _) = self.names();
+    let names: Vec<Vec<&str>> = names.iter().map(|n| n.iter().map(String::as_str).collect()).collect();
+    let groups = self.grps.iter().zip(&names).filter(|(_, names)| !names.is_empty());
+    let option_list: Vec<Opt<()>> = groups.map(|(grp, names)| {
+      let option = match grp.hasarg {
+        HasArg::Yes => Opt::value((), names, &grp.hint),
+        HasArg::No => Opt::flag((), names),
+      };
+      if grp.occur == Occur::Req { option.required() } else { option }
+    }).collect();
+    let mut out = String::new();
+    // Writing to a String can't fail
+    let _ = Opts::new(&option_list).print_help_to::<StandardShortUsageWriter<'_, ()>>(&mut out, program_name);
+    out
+  }
+
+  /// Derive a usage message from a set of options, prefixed by `brief`.
+  pub fn usage(&self, brief: &str) -> String {
+    let rows: Vec<String> = self.grps.iter().map(|grp| {
+      let mut row = match (grp.short_name.is_empty(), grp.long_name.is_empty()) {
+        (false, false) => format!("-{}, --{}", grp.short_name, grp.long_name),
+        (false, true) => format!("-{}", grp.short_name),
+        (true, _) => format!("    --{}", grp.long_name),
+      };
+      if grp.hasarg == HasArg::Yes {
+        row.push(' ');
+        row.push_str(&grp.hint);
+      }
+      row
+    }).collect();
+    let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+
+    let mut out = format!("{brief}\n\nOptions:\n");
+    for (row, grp) in rows.iter().zip(&self.grps) {
+      out.push_str(&format!("    {row:width$}  {}\n", grp.desc));
+    }
+    out
+  }
+}
+
+/// The result of parsing a command line with a set of [Options].
+#[derive(Debug, Clone)]
+pub struct Matches {
+  names: Vec<(String, String)>,
+  vals: Vec<Vec<Option<String>>>,
+  /// Free arguments, in the order they were given.
+  pub free: Vec<String>,
+}
+
+impl Matches {
+  fn values(&self, nm: &str) -> &[Option<String>] {
+    self.names.iter().position(|(short, long)| !nm.is_empty() && (short == nm || long == nm))
+      .and_then(|i| self.vals.get(i)).map_or(&[], Vec::as_slice)
+  }
+
+  /// Returns true if the option was matched.
+  pub fn opt_present(&self, nm: &str) -> bool {
+    !self.values(nm).is_empty()
+  }
+
+  /// Returns the number of times the option was matched.
+  pub fn opt_count(&self, nm: &str) -> usize {
+    self.values(nm).len()
+  }
+
+  /// Returns true if any of the options were matched.
+  pub fn opts_present(&self, names: &[impl AsRef<str>]) -> bool {
+    names.iter().any(|nm| self.opt_present(nm.as_ref()))
+  }
+
+  /// Returns the argument of the first occurrence of the option, if any.
+  pub fn opt_str(&self, nm: &str) -> Option<String> {
+    self.opt_strs(nm).into_iter().next()
+  }
+
+  /// Returns the arguments of every occurrence of the option, in order.
+  pub fn opt_strs(&self, nm: &str) -> Vec<String> {
+    self.values(nm).iter().flatten().cloned().collect()
+  }
+
+  /// Returns the argument of the first occurrence of the option converted to `T`, if any.
+  pub fn opt_get<T: FromStr>(&self, nm: &str) -> Result<Option<T>, T::Err> {
+    self.values(nm).iter().flatten().next().map(|s| s.parse()).transpose()
+  }
+
+  /// Returns the argument of the first occurrence of the option converted to `T`, or `def` if it wasn't given.
+  pub fn opt_get_default<T: FromStr>(&self, nm: &str, def: T) -> Result<T, T::Err> {
+    self.opt_get(nm).map(|v| v.unwrap_or(def))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn options() -> Options {
+    let mut opts = Options::new();
+    opts.optopt("o", "output", "set output file name", "NAME")
+      .optflag("h", "help", "print this help menu")
+      .optflagmulti("v", "", "increase verbosity")
+      .optmulti("I", "include", "add an include directory", "DIR");
+    opts
+  }
+
+  #[test]
+  fn test_parse() {
+    let matches = options().parse(["in", "-o", "out", "-v", "--include=a", "-v", "-I", "b", "--", "-h"]).unwrap();
+    assert!(!matches.opt_present("h"));
+    assert!(matches.opt_present("output"));
+    assert_eq!(matches.opt_str("o"), Some("out".into()));
+    assert_eq!(matches.opt_count("v"), 2);
+    assert_eq!(matches.opt_strs("I"), ["a", "b"]);
+    assert!(matches.opts_present(&["x", "v"]));
+    assert!(!matches.opt_present("x"));
+    assert_eq!(matches.opt_get_default("o", String::new()), Ok(String::from("out")));
+    assert_eq!(matches.free, ["in", "-h"]);
+  }
+
+  #[test]
+  fn test_parse_fail() {
+    let mut required = options();
+    required.reqopt("n", "number", "a number", "N");
+    for (args, expect) in [
+      (&["-n", "1", "-x"][..], Fail::UnrecognizedOption("x".into())),
+      (&["-n"][..], Fail::ArgumentMissing("n".into())),
+      (&["-n", "1", "--help=yes"][..], Fail::UnexpectedArgument("help".into())),
+      (&["-n", "1", "-o", "a", "-o", "b"][..], Fail::OptionDuplicated("o".into())),
+      (&[][..], Fail::OptionMissing("n".into())),
+    ] {
+      assert_eq!(required.parse(args).err(), Some(expect));
+    }
+    assert!(required.parse(["-n", "x"]).unwrap().opt_get::<u32>("n").is_err());
+  }
+
+  #[test]
+  fn test_defined_twice() {
+    let mut opts = options();
+    opts.optflag("q", "output", "quiet").optflag("v", "", "verbose");
+    assert_eq!(opts.parse(["-q"]).err(), Some(Fail::OptionDefinedTwice("output".into())));
+    assert_eq!(opts.short_usage("test"), "Usage: test [-o|--output NAME] [-h|--help] [-v] [-I|--include DIR] [-q]");
+  }
+
+  #[test]
+  fn test_usage() {
+    assert_eq!(options().usage("Usage: test [options]"), "Usage: test [options]\n\nOptions:
+    -o, --output NAME  set output file name
+    -h, --help         print this help menu
+    -v                 increase verbosity
+    -I, --include DIR  add an include directory\n");
+    assert_eq!(options().short_usage("test"), "Usage: test [-o|--output NAME] [-h|--help] [-v] [-I|--include DIR]");
+  }
+}
@@ -3,34 +3,70 @@
  * SPDX-License-Identifier: MIT OR Apache-2.0
  */
 
-use crate::{Opt, Opts, ParseError};
-use crate::option::{OptIdentifier, OptType};
+use crate::{const_str, Choice, ChoiceList, HelpKey, Opt, Opts, ParseError};
+use crate::option::OptType;
 
 /// Enough context to show full help text.
-pub struct HelpWriterContext<'a, ID: 'static> {
-  pub options: &'a Opts<ID>,
+pub struct HelpWriterContext<'a, ID, const R: usize = 4> {
+  pub options: &'a Opts<'a, ID, R>,
   pub program_name: &'a str,
 }
 
-impl<ID: 'static> Clone for HelpWriterContext<'_, ID> {
+impl<ID, const R: usize> Clone for HelpWriterContext<'_, ID, R> {
   fn clone(&self) -> Self {
     Self { options: self.options, program_name: self.program_name }
   }
 }
 
-pub trait HelpWriter<'a, ID: 'static>: core::fmt::Display {
-  fn new(ctx: HelpWriterContext<'a, ID>) -> Self;
+pub trait HelpWriter<'a, ID, const R: usize = 4>: core::fmt::Display {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self;
 }
 
-pub struct StandardShortUsageWriter<'a, ID: 'static>(HelpWriterContext<'a, ID>);
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Write help text into a formatter sink using the provided help writer.
+  pub fn print_help_to<'a, W: HelpWriter<'a, ID, R>>(&'a self, out: &mut impl core::fmt::Write, program_name: &'a str
+  ) -> core::fmt::Result {
+    let ctx = HelpWriterContext { options: self, program_name };
+    write!(out, "{}", W::new(ctx))
+  }
+
+  /// Write full help text into a formatter sink using the standard full help writer.
+  pub fn print_full_help_to(&self, out: &mut impl core::fmt::Write, program_name: &str) -> core::fmt::Result {
+    self.print_help_to::<StandardFullHelpWriter<'_, ID, R>>(out, program_name)
+  }
+
+  /// Show help when a help flag is matched by writing the full help to `sink` in place of the handler,
+  /// then end parsing with [ParseResult::ExitSuccess](crate::ParseResult::ExitSuccess),
+  /// so handlers don't need to refer back to the options table.
+  /// ```
+  /// # use jaarg::{Opt, Opts, ParseResult};
+  /// const OPTIONS: Opts<()> = Opts::new(&[Opt::help_flag((), &["--help"])]).with_help_handler(|text| print!("{text}"));
+  /// let result = OPTIONS.parse("prog", ["--help"].iter(), |_| unreachable!(), |_, _| {});
+  /// assert_eq!(result, ParseResult::ExitSuccess);
+  /// ```
+  pub const fn with_help_handler(mut self, sink: fn(&str)) -> Self {
+    self.sinks.help = Some(crate::terminal::OutputSink(sink));
+    self
+  }
 
-impl<'a, ID: 'static> HelpWriter<'a, ID> for StandardShortUsageWriter<'a, ID> {
-  fn new(ctx: HelpWriterContext<'a, ID>) -> Self { Self(ctx) }
+  /// Write error & usage text into a formatter sink using the provided error & usage writer.
+  pub fn print_usage_to<'a, W: ErrorUsageWriter<'a, ID, R>>(&'a self, out: &mut impl core::fmt::Write,
+    program_name: &'a str, error: ParseError<'a>
+  ) -> core::fmt::Result {
+    let ctx = ErrorUsageWriterContext { options: self, program_name, error };
+    write!(out, "{}", W::new(ctx))
+  }
 }
 
-impl<ID: 'static> core::fmt::Display for StandardShortUsageWriter<'_, ID> {
+pub struct StandardShortUsageWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for StandardShortUsageWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for StandardShortUsageWriter<'_, ID, R> {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-    write!(f, "Usage: {}", self.0.program_name)?;
+    write!(f, "{} {}", self.0.options.localize(HelpKey::Usage, "Usage:"), self.0.program_name)?;
 
     // Write option parameter arguments
     for option in self.0.options.iter()
@@ -40,178 +76,397 @@ impl<ID: 'static> core::fmt::Display for StandardShortUsageWriter<'_, ID> {
         (Some(short_name), Some(long_name)) => write!(f, "{short_name}|{long_name}")?,
         (Some(short_name), None) => f.write_str(short_name)?,
         (None, Some(long_name))  => f.write_str(long_name)?,
-        _ => unreachable!(),
+        (None, None) => f.write_str(option.first_name())?,
       }
-      if let Some(value_name) = option.value_name {
-         write!(f, " {value_name}")?;
+      for &value_name in option.value_names() {
+        write!(f, " {}", ValueName::new(self.0.options, value_name, false, ValueStyle::PLAIN))?;
       }
       write!(f, "{}", if option.is_required() { '>' } else { ']' })?;
     }
 
     // Write positional arguments
-    for option in self.0.options.iter()
-        .filter(|o| matches!((o.r#type, o.is_short_visible()), (OptType::Positional, true))) {
-      let name = option.first_name();
-      match option.is_required() {
-        true  => write!(f, " <{name}>")?,
-        false => write!(f, " [{name}]")?,
-      }
-    }
+    write!(f, "{}", PositionalsUsage(self.0.options))?;
     Ok(())
   }
 }
 
-pub struct StandardFullHelpWriter<'a, ID: 'static>(HelpWriterContext<'a, ID>);
+pub struct StandardFullHelpWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
 
-impl<'a, ID: 'static> HelpWriter<'a, ID> for StandardFullHelpWriter<'a, ID> {
-  fn new(ctx: HelpWriterContext<'a, ID>) -> Self { Self(ctx) }
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for StandardFullHelpWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
 }
 
-impl<ID> core::fmt::Display for StandardFullHelpWriter<'_, ID> {
+impl<ID, const R: usize> core::fmt::Display for StandardFullHelpWriter<'_, ID, R> {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     // Base short usage
     writeln!(f, "{}", StandardShortUsageWriter::new(self.0.clone()))?;
 
-    if let Some(description) = self.0.options.description {
+    if let Some(description) = self.0.options.description.map(|d| self.0.options.localize(HelpKey::Description, d)) {
       writeln!(f)?;
-      writeln!(f, "{description}")?;
+      writeln!(f, "{}", Paragraphs(description, self.0.options.help_layout.width))?;
     }
 
-	  // Determine the alignment width from the longest option parameter
-    fn calculate_option_line_length<ID: 'static>(option: &Opt<ID>) -> usize {
-      (match option.names {
-        OptIdentifier::Single(name) => name.chars().count(),
-        OptIdentifier::Multi(names) => (names.len() - 1) * 3 + names.iter()
-          .fold(0, |accum, name| accum + name.chars().count()),
-      }) + option.value_name.map_or(0, |v| v.len() + 3)
-    }
-    let align_width = 3 + self.0.options.iter()
-      .map(|o| calculate_option_line_length(o)).max().unwrap_or(0);
-
-    // Write positional argument descriptions
-    let mut first = true;
-    for option in self.0.options.iter()
-        .filter(|o| matches!((o.r#type, o.is_full_visible()), (OptType::Positional, true))) {
-      if first {
-        // Write separator and positional section header
-        writeln!(f)?;
-        writeln!(f, "Positional arguments:")?;
-        first = false;
-      }
+    // The width of the longest entry is computed when the table is built
+    let layout = &self.0.options.help_layout;
+    let left_width = self.0.options.help_align.min(layout.max_left_width);
 
-      // Write positional argument line (name + optional aligned help text)
-      let name = option.first_name();
-      write!(f, "  {name}")?;
-      if let Some(help_text) = option.help_string {
-        write!(f, " {:.<width$} {help_text}", "",
-          width = align_width - name.chars().count() - 1)?;
+    /// Write an entry, followed by its help text aligned to the help column.
+    fn write_entry(f: &mut core::fmt::Formatter<'_>, layout: &HelpLayout, left_width: usize,
+      entry: impl core::fmt::Display, help_text: Option<&str>, choices: &[Choice],
+    ) -> core::fmt::Result {
+      use core::fmt::Write;
+      write!(f, "{:indent$}{entry}", "", indent = layout.indent)?;
+      if help_text.is_some() || !choices.is_empty() {
+        let length = display_len(&entry);
+        if length > left_width {
+          // Entries wider than the left column have their help text on the next line
+          writeln!(f)?;
+          write!(f, "{:width$}", "", width = layout.indent + left_width + layout.min_gap)?;
+        } else {
+          // The fill is always surrounded by a space on either side
+          let gap = left_width + layout.min_gap - length;
+          for i in 0..gap {
+            f.write_char(if i == 0 || i == gap - 1 { ' ' } else { layout.fill })?;
+          }
+        }
+        write_help_text(f, help_text, choices, layout.indent + left_width + layout.min_gap)?;
       }
-      writeln!(f)?;
+      writeln!(f)
     }
 
     /// Formatter for option usage lines.
-    struct OptionUsageLine<'a, ID>(&'a Opt<ID>);
-    impl<ID> core::fmt::Display for OptionUsageLine<'_, ID> {
+    struct OptionUsageLine<'a, ID, const R: usize>(&'a Opts<'a, ID, R>, &'a Opt<'a, ID>);
+    impl<ID, const R: usize> core::fmt::Display for OptionUsageLine<'_, ID, R> {
       fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        use core::fmt::Write;
-        let mut length = 0;
-
         // Write option flag name(s)
-        match self.0.names {
-          OptIdentifier::Single(name) => {
-            write!(f, "{name}")?;
-            length = name.chars().count();
-          }
-          OptIdentifier::Multi(names) => {
-            for (i, name) in names.iter().enumerate() {
-              if i == 0 {
-                write!(f, "{name}")?;
-                length += name.chars().count();
-              } else {
-                write!(f, " | {name}")?;
-                length += 3 + name.chars().count();
-              }
-            }
+        for (i, name) in self.1.names().iter().enumerate() {
+          if i > 0 {
+            f.write_str(" | ")?;
           }
+          f.write_str(name)?;
         }
 
         // Write value argument for value options parameters
-        if let Some(value_name) = self.0.value_name {
-          write!(f, " <{value_name}>")?;
-          length += 2 + value_name.chars().count() + 1;
+        for &value_name in self.1.value_names() {
+          write!(f, " {}", ValueName::new(self.0, value_name, false, ValueStyle::BRACKETED))?;
         }
+        Ok(())
+      }
+    }
 
-        // Write padding if requested
-        match (f.align(), f.width().unwrap_or(0).checked_sub(length)) {
-          (Some(core::fmt::Alignment::Left), Some(width)) if width > 0 => {
-            let fill = f.fill();
-            // First padding char is *always* a space
-            f.write_char(' ')?;
-            for _ in 1..width {
-              f.write_char(fill)?;
-            }
-            Ok(())
+    let sections = &self.0.options.help_sections;
+    let write_section = |f: &mut core::fmt::Formatter<'_>, section: &HelpSection| {
+      let positional = matches!(section, HelpSection::Positionals);
+      let mut first = true;
+      for option in self.0.options.iter()
+          .filter(|o| matches!(o.r#type, OptType::Positional) == positional && o.is_full_visible()) {
+        if first {
+          // Write separator and section header
+          writeln!(f)?;
+          match positional {
+            true  => writeln!(f, "{}", self.0.options.localize(HelpKey::PositionalsHeader,
+              sections.positionals.unwrap_or("Positional arguments:")))?,
+            false => writeln!(f, "{}", self.0.options.localize(HelpKey::OptionsHeader,
+              sections.options.unwrap_or("Options:")))?,
           }
-          _ => Ok(()),
+          first = false;
+        }
+
+        // Write line for the positional name or option usage, with aligned help text if needed
+        match positional {
+          true => write_entry(f, layout, left_width,
+            ValueName::new(self.0.options, option.display_name(), !option.is_required(), ValueStyle::PLAIN),
+            self.0.options.localized_help(option), option.choices())?,
+          false => write_entry(f, layout, left_width, OptionUsageLine(self.0.options, option),
+            self.0.options.localized_help(option), option.choices())?,
         }
       }
-    }
+      Ok(())
+    };
+    sections.order.iter().try_for_each(|section| write_section(f, section))?;
+    write_bug_report(f, self.0.options)
+  }
+}
 
-    // Write option parameter argument descriptions
-    first = true;
-    for option in self.0.options.iter()
-        .filter(|o| matches!((o.r#type, o.is_full_visible()), (OptType::Flag | OptType::Value, true))) {
-      if first {
-        // Write separator and options section header
-        writeln!(f)?;
-        writeln!(f, "Options:")?;
-        first = false;
+
+// Enough context to show usage and error information.
+pub struct ErrorUsageWriterContext<'a, ID, const R: usize = 4> {
+  pub options: &'a Opts<'a, ID, R>,
+  pub program_name: &'a str,
+  pub error: ParseError<'a>
+}
+
+pub trait ErrorUsageWriter<'a, ID, const R: usize = 4>: core::fmt::Display {
+  fn new(ctx: ErrorUsageWriterContext<'a, ID, R>) -> Self;
+}
+
+/// What [StandardErrorUsageWriter] writes after the error message, set with [Opts::with_error_usage].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum ErrorUsage {
+  /// Only the error message.
+  None,
+  /// The short usage and how to view all options, after missing required argument errors (the default).
+  #[default]
+  Short,
+  /// The short usage and how to view all options, after every error.
+  ShortAlways,
+  /// The full help text, after every error.
+  Full,
+}
+
+/// A section of the full help text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HelpSection {
+  /// Positional arguments and their help text.
+  Positionals,
+  /// Flag and value options and their help text.
+  Options,
+}
+
+/// Section headers and order of the full help text written by the built-in writers, set with [Opts::with_help_sections].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HelpSections<'o> {
+  /// Header line of the positional arguments section, or [None] for the writer's own, eg; `Positional arguments:`.
+  pub positionals: Option<&'o str>,
+  /// Header line of the options section, or [None] for the writer's own, eg; `Options:`.
+  pub options: Option<&'o str>,
+  /// Order the sections are written in, empty sections are skipped.
+  pub order: [HelpSection; 2],
+}
+
+impl HelpSections<'_> {
+  /// The writer's own headers, with positional arguments before options.
+  pub const DEFAULT: Self = Self { positionals: None, options: None, order: [HelpSection::Positionals, HelpSection::Options] };
+}
+
+impl Default for HelpSections<'_> {
+  fn default() -> Self { Self::DEFAULT }
+}
+
+/// Column layout of the option and help text columns of [StandardFullHelpWriter], set with [Opts::with_help_layout].
+/// The description width also applies to [ClapFullHelpWriter].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HelpLayout {
+  /// Character filling the gap between an option and its help text, `'.'` by default,
+  /// the fill is always surrounded by a space on either side.
+  pub fill: char,
+  /// Columns between the longest option and its help text, 4 by default.
+  pub min_gap: usize,
+  /// Columns before each option, 2 by default.
+  pub indent: usize,
+  /// Widest an option can be before its help text moves to the next line, unlimited by default.
+  pub max_left_width: usize,
+  /// Columns the description is wrapped to, unlimited by default.
+  pub width: usize,
+}
+
+impl HelpLayout {
+  /// The default layout, eg; `  -n | --name <name> .. Help text`.
+  pub const DEFAULT: Self = Self { fill: '.', min_gap: 4, indent: 2, max_left_width: usize::MAX, width: usize::MAX };
+}
+
+impl Default for HelpLayout {
+  fn default() -> Self { Self::DEFAULT }
+}
+
+/// Formatter for a description, with lines wider than `width` columns wrapped between words, paragraphs separated by
+/// a single blank line, and leading or trailing blank lines dropped.
+struct Paragraphs<'a>(&'a str, usize);
+
+impl core::fmt::Display for Paragraphs<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let (mut written, mut blank) = (false, false);
+    for line in self.0.lines().map(str::trim_end) {
+      if line.is_empty() {
+        blank = written;
+        continue;
+      }
+      if written {
+        f.write_str(if blank { "\n\n" } else { "\n" })?;
       }
+      (written, blank) = (true, false);
 
-      // Write line for option, with aligned help text if needed
-      let line = OptionUsageLine(option);
-      if let Some(help_text) = option.help_string {
-        write!(f, "  {line:.<align_width$} {help_text}")?;
-      } else {
-        write!(f, "  {line}")?;
+      // Lines that already fit are written as given, preserving indentation
+      if const_str::display_width(line) <= self.1 {
+        f.write_str(line)?;
+        continue;
       }
-      writeln!(f)?;
+      let mut column = 0;
+      for word in line.split_whitespace() {
+        let length = const_str::display_width(word);
+        match column {
+          0 => {}
+          _ if column + 1 + length > self.1 => { f.write_str("\n")?; column = 0; }
+          _ => { f.write_str(" ")?; column += 1; }
+        }
+        f.write_str(word)?;
+        column += length;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Write the trailing `Report bugs to:` line of the full help, if the table has a bug report address.
+fn write_bug_report<ID, const R: usize>(f: &mut core::fmt::Formatter<'_>, options: &Opts<ID, R>) -> core::fmt::Result {
+  match options.bug_report {
+    Some(bug_report) => write!(f, "\n{} {bug_report}\n", options.localize(HelpKey::ReportBugs, "Report bugs to:")),
+    None => Ok(()),
+  }
+}
+
+/// Choices of the option or positional argument named in an error.
+fn choices_of<'o, ID, const R: usize>(options: &Opts<'o, ID, R>, name: &str) -> &'o [Choice<'o>] {
+  options.find_by_name(name).map_or(&[], |o| o.choices())
+}
+
+/// Write help text with each line after the first indented to `column`, leaving blank lines empty,
+/// followed by the canonical values of any choices.
+fn write_help_text(f: &mut core::fmt::Formatter<'_>, help_text: Option<&str>, choices: &[Choice], column: usize
+) -> core::fmt::Result {
+  for (i, line) in help_text.unwrap_or_default().lines().enumerate() {
+    match (i, line.is_empty()) {
+      (0, _) => f.write_str(line)?,
+      (_, true) => writeln!(f)?,
+      (_, false) => write!(f, "\n{:column$}{line}", "")?,
+    }
+  }
+  if !choices.is_empty() {
+    let separator = if help_text.is_some_and(|h| !h.is_empty()) { " " } else { "" };
+    write!(f, "{separator}[possible values: {}]", ChoiceList(choices))?;
+  }
+  Ok(())
+}
+
+/// Number of characters a value displays as.
+fn display_len(value: &impl core::fmt::Display) -> usize {
+  use core::fmt::Write;
+  struct Counter(usize);
+  impl Write for Counter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+      self.0 += s.chars().count();
+      Ok(())
     }
+  }
+  let mut counter = Counter(0);
+  let _ = write!(counter, "{value}");
+  counter.0
+}
+
+/// How value names and positional names are rendered by every help writer, set with [Opts::with_value_style].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ValueStyle {
+  /// Write names in uppercase, eg; `NAME`.
+  pub uppercase: bool,
+  /// Surround value names and positional names with angle brackets, eg; `<name>`.
+  pub angle_brackets: bool,
+  /// Surround optional positional names with square brackets, eg; `[name]`.
+  pub square_optional: bool,
+}
+
+impl ValueStyle {
+  /// GNU-ish convention, eg; `--name NAME`, `FILE`, and `[FILE]`.
+  pub const GNU: Self = Self { uppercase: true, angle_brackets: false, square_optional: true };
+  /// clap-ish convention, eg; `--name <NAME>`, `<FILE>`, and `[FILE]`.
+  pub const CLAP: Self = Self { uppercase: true, angle_brackets: true, square_optional: true };
+  /// Names written as declared without brackets.
+  const PLAIN: Self = Self { uppercase: false, angle_brackets: false, square_optional: false };
+  /// Names written as declared in brackets.
+  const BRACKETED: Self = Self { uppercase: false, angle_brackets: true, square_optional: true };
 
+  /// The brackets surrounding a name, if any.
+  pub(crate) const fn brackets(&self, optional: bool) -> Option<(char, char)> {
+    match (optional && self.square_optional, self.angle_brackets) {
+      (true, _) => Some(('[', ']')),
+      (false, true) => Some(('<', '>')),
+      (false, false) => None,
+    }
+  }
+}
+
+/// Formatter for the positional arguments in a usage line,
+/// with each group of positionals provided together in one pair of brackets, eg; `[host port]`.
+struct PositionalsUsage<'a, ID, const R: usize>(&'a Opts<'a, ID, R>);
+
+impl<ID, const R: usize> core::fmt::Display for PositionalsUsage<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut in_group = false;
+    for option in self.0.positionals().filter(|o| o.is_short_visible()) {
+      let mut name = ValueName::new(self.0, option.display_name(), !option.is_required(), ValueStyle::BRACKETED);
+      if in_group || option.is_grouped_with_next() {
+        // Members of a group are written bare inside the group's brackets
+        name.optional = false;
+        name.style.angle_brackets = false;
+        write!(f, " {}{name}", if in_group { "" } else { "[" })?;
+        in_group = option.is_grouped_with_next();
+        if !in_group {
+          f.write_str("]")?;
+        }
+      } else {
+        write!(f, " {name}{}", if option.collects_extra() { "..." } else { "" })?;
+      }
+    }
     Ok(())
   }
 }
 
+/// Formatter for a value name or positional name in the value style set on the options,
+/// or `default` for the writer's own convention.
+struct ValueName<'a> {
+  name: &'a str,
+  optional: bool,
+  style: ValueStyle,
+}
 
-// Enough context to show usage and error information.
-pub struct ErrorUsageWriterContext<'a, ID: 'static> {
-  pub options: &'a Opts<ID>,
-  pub program_name: &'a str,
-  pub error: ParseError<'a>
+impl<'a> ValueName<'a> {
+  fn new<ID, const R: usize>(options: &Opts<ID, R>, name: &'a str, optional: bool, default: ValueStyle) -> Self {
+    Self { name, optional, style: options.value_style.unwrap_or(default) }
+  }
 }
 
-pub trait ErrorUsageWriter<'a, ID: 'static>: core::fmt::Display {
-  fn new(ctx: ErrorUsageWriterContext<'a, ID>) -> Self;
+impl core::fmt::Display for ValueName<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use core::fmt::Write;
+    let brackets = self.style.brackets(self.optional);
+    if let Some((open, _)) = brackets {
+      f.write_char(open)?;
+    }
+    match self.style.uppercase {
+      true  => self.name.chars().flat_map(char::to_uppercase).try_for_each(|c| f.write_char(c))?,
+      false => f.write_str(self.name)?,
+    }
+    if let Some((_, close)) = brackets {
+      f.write_char(close)?;
+    }
+    Ok(())
+  }
 }
 
-pub struct StandardErrorUsageWriter<'a, ID: 'static>(ErrorUsageWriterContext<'a, ID>);
+pub struct StandardErrorUsageWriter<'a, ID, const R: usize = 4>(ErrorUsageWriterContext<'a, ID, R>);
 
-impl<'a, ID: 'static> ErrorUsageWriter<'a, ID> for StandardErrorUsageWriter<'a, ID> {
-  fn new(ctx: ErrorUsageWriterContext<'a, ID>) -> Self { Self(ctx) }
+impl<'a, ID, const R: usize> ErrorUsageWriter<'a, ID, R> for StandardErrorUsageWriter<'a, ID, R> {
+  fn new(ctx: ErrorUsageWriterContext<'a, ID, R>) -> Self { Self(ctx) }
 }
 
-impl<ID> core::fmt::Display for StandardErrorUsageWriter<'_, ID> {
+impl<ID, const R: usize> core::fmt::Display for StandardErrorUsageWriter<'_, ID, R> {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     // Write error
     writeln!(f, "{name}: {error}", name=self.0.program_name, error = self.0.error)?;
+    if let ParseError::ArgumentError(o, _, crate::ParseErrorKind::InvalidChoice) = self.0.error {
+      writeln!(f, "Possible values: {}", ChoiceList(choices_of(self.0.options, o)))?;
+    }
 
-    // Provide usage hint for missing required arguments
-    if matches!(self.0.error, ParseError::RequiredPositional(_) | ParseError::RequiredParameter(_)) {
+    let ctx = HelpWriterContext { options: self.0.options, program_name: self.0.program_name };
+    let show_usage = match self.0.options.error_usage {
+      ErrorUsage::None => false,
+      // Provide usage hint for missing required arguments
+      ErrorUsage::Short => matches!(self.0.error, ParseError::RequiredPositional(_) | ParseError::RequiredParameter(_)),
+      ErrorUsage::ShortAlways => true,
+      ErrorUsage::Full => return write!(f, "{}", StandardFullHelpWriter::new(ctx)),
+    };
+    if show_usage {
       // Write short usage
-      writeln!(f, "{}", StandardShortUsageWriter::new(HelpWriterContext {
-        options: self.0.options,
-        program_name: self.0.program_name
-      }))?;
+      writeln!(f, "{}", StandardShortUsageWriter::new(ctx))?;
 
       // Write full help instruction if available
       if let Some(help_option) = self.0.options.help_option() {
@@ -224,3 +479,595 @@ impl<ID> core::fmt::Display for StandardErrorUsageWriter<'_, ID> {
     Ok(())
   }
 }
+
+/// Short usage line in the layout used by clap, eg; `Usage: prog [OPTIONS] --name <NAME> <INPUT>`.
+pub struct ClapShortUsageWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for ClapShortUsageWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for ClapShortUsageWriter<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{} {}", self.0.options.localize(HelpKey::Usage, "Usage:"), self.0.program_name)?;
+
+    // Optional options are collapsed into a single placeholder, required ones are written out
+    let parameters = || self.0.options.iter()
+      .filter(|o| matches!((o.r#type, o.is_short_visible()), (OptType::Value | OptType::Flag, true)));
+    if parameters().any(|o| !o.is_required()) {
+      write!(f, " {}", self.0.options.localize(HelpKey::OptionsPlaceholder, "[OPTIONS]"))?;
+    }
+    for option in parameters().filter(|o| o.is_required()) {
+      write!(f, " {}", option.first_long_name().or(option.first_short_name()).unwrap_or(option.first_name()))?;
+      for &value_name in option.value_names() {
+        write!(f, " {}", ValueName::new(self.0.options, value_name, false, ValueStyle::BRACKETED))?;
+      }
+    }
+
+    // Write positional arguments
+    write!(f, "{}", PositionalsUsage(self.0.options))?;
+    Ok(())
+  }
+}
+
+/// Full help text in the layout used by clap, with `Arguments:` and `Options:` sections.
+pub struct ClapFullHelpWriter<'a, ID, const R: usize = 4>(HelpWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> HelpWriter<'a, ID, R> for ClapFullHelpWriter<'a, ID, R> {
+  fn new(ctx: HelpWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for ClapFullHelpWriter<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    if let Some(description) = self.0.options.description.map(|d| self.0.options.localize(HelpKey::Description, d)) {
+      writeln!(f, "{}", Paragraphs(description, self.0.options.help_layout.width))?;
+      writeln!(f)?;
+    }
+    writeln!(f, "{}", ClapShortUsageWriter::new(self.0.clone()))?;
+
+    /// Formatter for an argument or option specification, eg; `-n, --name <NAME>`.
+    struct Spec<'a, ID, const R: usize>(&'a Opts<'a, ID, R>, &'a Opt<'a, ID>);
+    impl<ID, const R: usize> core::fmt::Display for Spec<'_, ID, R> {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let option = self.1;
+        if matches!(option.r#type, OptType::Positional) {
+          return write!(f, "{}", ValueName::new(self.0, option.display_name(), !option.is_required(), ValueStyle::BRACKETED));
+        }
+        match (option.first_short_name(), option.first_long_name()) {
+          (Some(short_name), Some(long_name)) => write!(f, "{short_name}, {long_name}")?,
+          (Some(short_name), None) => f.write_str(short_name)?,
+          (None, Some(long_name)) => write!(f, "    {long_name}")?,
+          (None, None) => f.write_str(option.first_name())?,
+        }
+        for &value_name in option.value_names() {
+          write!(f, " {}", ValueName::new(self.0, value_name, false, ValueStyle::BRACKETED))?;
+        }
+        Ok(())
+      }
+    }
+
+    // Help text is aligned across both sections, two spaces after the longest specification
+    let visible = || self.0.options.iter().filter(|o| o.is_full_visible());
+    let align_width = visible().map(|o| display_len(&Spec(self.0.options, o))).max().unwrap_or(0) + 2;
+    let sections = &self.0.options.help_sections;
+    let write_section = |f: &mut core::fmt::Formatter<'_>, section: &HelpSection| {
+      let (header, positional) = match section {
+        HelpSection::Positionals => (self.0.options.localize(HelpKey::PositionalsHeader,
+          sections.positionals.unwrap_or("Arguments:")), true),
+        HelpSection::Options => (self.0.options.localize(HelpKey::OptionsHeader,
+          sections.options.unwrap_or("Options:")), false),
+      };
+      let mut first = true;
+      for option in visible().filter(|o| matches!(o.r#type, OptType::Positional) == positional) {
+        if first {
+          writeln!(f)?;
+          writeln!(f, "{header}")?;
+          first = false;
+        }
+        let spec = Spec(self.0.options, option);
+        match (self.0.options.localized_help(option), option.choices()) {
+          (None, []) => writeln!(f, "  {spec}")?,
+          (help_text, choices) => {
+            write!(f, "  {spec}{:width$}", "", width = align_width - display_len(&spec))?;
+            write_help_text(f, help_text, choices, 2 + align_width)?;
+            writeln!(f)?;
+          }
+        }
+      }
+      Ok(())
+    };
+    sections.order.iter().try_for_each(|section| write_section(f, section))?;
+    write_bug_report(f, self.0.options)
+  }
+}
+
+/// Error & usage text in the layout used by clap.
+pub struct ClapErrorUsageWriter<'a, ID, const R: usize = 4>(ErrorUsageWriterContext<'a, ID, R>);
+
+impl<'a, ID, const R: usize> ErrorUsageWriter<'a, ID, R> for ClapErrorUsageWriter<'a, ID, R> {
+  fn new(ctx: ErrorUsageWriterContext<'a, ID, R>) -> Self { Self(ctx) }
+}
+
+impl<ID, const R: usize> core::fmt::Display for ClapErrorUsageWriter<'_, ID, R> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use crate::ParseErrorKind;
+    f.write_str("error: ")?;
+    match self.0.error {
+      ParseError::UnknownOption(a) | ParseError::UnexpectedToken(a) => writeln!(f, "unexpected argument '{a}' found")?,
+      ParseError::ExpectArgument(o) => writeln!(f, "a value is required for '{o}' but none was supplied")?,
+      ParseError::UnexpectedArgument(o) => writeln!(f, "unexpected value for '{o}' found; no more were expected")?,
+      ParseError::TooManyValues(o) => writeln!(f, "the argument '{o}' cannot be used that many times")?,
+      ParseError::UnpairedSurrogate(a) | ParseError::InvalidUtf8(a) => writeln!(f, "invalid UTF-8 was detected in argument '{a}'")?,
+      ParseError::TokenTooLong(a) => writeln!(f, "argument '{a}...' is too long")?,
+      ParseError::InvalidCommandLine(e) => writeln!(f, "invalid command line: {e}")?,
+      ParseError::ArgumentError(o, a, ref kind) => {
+        writeln!(f, "invalid value '{a}' for '{o}': {}", match kind {
+          ParseErrorKind::IntegerEmpty => "cannot parse integer from empty string",
+          ParseErrorKind::IntegerRange => "number too large or too small",
+          ParseErrorKind::InvalidInteger => "invalid digit found in string",
+          ParseErrorKind::InvalidFloat => "invalid float literal",
+          ParseErrorKind::InvalidToggle => "possible values are on, off, auto",
+          ParseErrorKind::InvalidChoice => "not one of the possible values",
+        })?;
+        if let ParseErrorKind::InvalidChoice = kind {
+          writeln!(f, "  [possible values: {}]", ChoiceList(choices_of(self.0.options, o)))?;
+        }
+      }
+      ParseError::InvalidValue(o, a, reason) => writeln!(f, "invalid value '{a}' for '{o}': {reason}")?,
+      ParseError::HandlerPanic(o, message) => writeln!(f, "internal error while handling '{o}': {message}")?,
+      ParseError::RequiredPositional(o) => writeln!(f, "the following required arguments were not provided:\n  {}",
+        ValueName::new(self.0.options, o, false, ValueStyle::BRACKETED))?,
+      ParseError::RequiredParameter(o) => {
+        writeln!(f, "the following required arguments were not provided:")?;
+        write!(f, "  {o}")?;
+        for &value_name in self.0.options.find_by_name(o).map_or(&[][..], |o| o.value_names()) {
+          write!(f, " {}", ValueName::new(self.0.options, value_name, false, ValueStyle::BRACKETED))?;
+        }
+        writeln!(f)?;
+      }
+    }
+    writeln!(f)?;
+    writeln!(f, "{}", ClapShortUsageWriter::new(HelpWriterContext {
+      options: self.0.options,
+      program_name: self.0.program_name,
+    }))?;
+    if let Some(help_option) = self.0.options.help_option() {
+      writeln!(f)?;
+      writeln!(f, "For more information, try '{}'.", help_option.first_long_name().unwrap_or(help_option.first_name()))?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate alloc;
+  use alloc::string::String;
+  use super::*;
+  use crate::OptExtras;
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::positional(0, "INPUT").help_text("Input file").required(),
+    Opt::positional(1, "OUTPUT").help_text("Output file"),
+    Opt::value(2, &["-n", "--name"], "NAME").help_text("Name to use").required(),
+    Opt::flag(3, &["--dry-run"]).help_text("Don't write anything"),
+    Opt::flag(4, &["-v"]),
+    Opt::help_flag(5, &["-h", "--help"]).help_text("Print help"),
+  ]).with_description("Does a thing.");
+
+  #[test]
+  fn test_print_to() {
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog <-n|--name NAME> [--dry-run] [-v] [-h|--help] <INPUT> [OUTPUT]
+
+Does a thing.
+
+Positional arguments:
+  INPUT ............... Input file
+  OUTPUT .............. Output file
+
+Options:
+  -n | --name <NAME> .. Name to use
+  --dry-run ........... Don't write anything
+  -v
+  -h | --help ......... Print help
+");
+    let mut full = String::new();
+    OPTIONS.print_help_to::<StandardFullHelpWriter<'_, _>>(&mut full, "prog").unwrap();
+    assert_eq!(full, out);
+
+    out.clear();
+    OPTIONS.print_usage_to::<StandardErrorUsageWriter<'_, _>>(&mut out, "prog", ParseError::UnknownOption("-x")).unwrap();
+    assert_eq!(out, "prog: Unrecognised option '-x'\n");
+
+    // Errors from the sink are passed back to the caller
+    struct Full;
+    impl core::fmt::Write for Full {
+      fn write_str(&mut self, _: &str) -> core::fmt::Result { Err(core::fmt::Error) }
+    }
+    assert!(OPTIONS.print_full_help_to(&mut Full, "prog").is_err());
+    assert!(OPTIONS.print_usage_to::<StandardErrorUsageWriter<'_, _>>(&mut Full, "prog", ParseError::UnknownOption("-x"))
+      .is_err());
+  }
+
+  #[test]
+  fn test_clap_help() {
+    let mut out = String::new();
+    OPTIONS.print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Does a thing.
+
+Usage: prog [OPTIONS] --name <NAME> <INPUT> [OUTPUT]
+
+Arguments:
+  <INPUT>            Input file
+  [OUTPUT]           Output file
+
+Options:
+  -n, --name <NAME>  Name to use
+      --dry-run      Don't write anything
+  -v
+  -h, --help         Print help
+");
+  }
+
+  #[test]
+  fn test_clap_error_usage() {
+    let mut out = String::new();
+    OPTIONS.print_usage_to::<ClapErrorUsageWriter<'_, _>>(&mut out, "prog", ParseError::RequiredParameter("-n"))
+      .unwrap();
+    assert_eq!(out, "error: the following required arguments were not provided:
+  -n <NAME>
+
+Usage: prog [OPTIONS] --name <NAME> <INPUT> [OUTPUT]
+
+For more information, try '--help'.
+");
+  }
+
+  #[test]
+  fn test_error_usage() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "file").required(),
+      Opt::help_flag(1, &["--help"]),
+    ]);
+    let write = |options: Opts<u32>, error| {
+      let mut out = String::new();
+      options.print_usage_to::<StandardErrorUsageWriter<'_, _>>(&mut out, "prog", error).unwrap();
+      out
+    };
+    assert_eq!(write(OPTIONS, ParseError::RequiredPositional("file")), "prog: Missing required positional argument 'file'
+Usage: prog [--help] <file>
+Run 'prog --help' to view all available options.
+");
+    assert_eq!(write(OPTIONS, ParseError::UnknownOption("-x")), "prog: Unrecognised option '-x'\n");
+    assert_eq!(write(OPTIONS.with_error_usage(ErrorUsage::ShortAlways), ParseError::UnknownOption("-x")),
+      "prog: Unrecognised option '-x'
+Usage: prog [--help] <file>
+Run 'prog --help' to view all available options.
+");
+    assert_eq!(write(OPTIONS.with_error_usage(ErrorUsage::None), ParseError::RequiredPositional("file")),
+      "prog: Missing required positional argument 'file'\n");
+    assert_eq!(write(OPTIONS.with_error_usage(ErrorUsage::Full), ParseError::UnknownOption("-x")),
+      "prog: Unrecognised option '-x'
+Usage: prog [--help] <file>
+
+Positional arguments:
+  file
+
+Options:
+  --help
+");
+  }
+  #[test]
+  fn test_metavar() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "input").extras(&OptExtras::new().metavar("FILE")).help_text("Input file").required(),
+      Opt::flag(1, &["-v"]).help_text("Verbose"),
+    ]);
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-v] <FILE>
+
+Positional arguments:
+  FILE .. Input file
+
+Options:
+  -v .... Verbose
+");
+    out.clear();
+    OPTIONS.print_help_to::<ClapShortUsageWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [OPTIONS] <FILE>");
+
+    // The handler still receives the positional's name, errors show the metavar
+    assert!(matches!(OPTIONS.parse("prog", ["in"].iter(), |ctx| {
+      assert_eq!(ctx.name, "input");
+      Ok(crate::ParseControl::Continue)
+    }, |_, _| unreachable!()), crate::ParseResult::ContinueSuccess));
+    assert!(matches!(OPTIONS.parse("prog", [""; 0].iter(), |_| Ok(crate::ParseControl::Continue),
+      |_, error| assert_eq!(error, ParseError::RequiredPositional("FILE"))), crate::ParseResult::ExitFailure));
+  }
+
+  #[test]
+  fn test_value_names() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-c", "--copy"], "src").extras(&OptExtras::new().value_names(&["src", "dst"])).help_text("Copy a file"),
+      Opt::value(1, &["--move"], "src").extras(&OptExtras::new().value_names(&["src", "dst"])).required(),
+    ]);
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-c|--copy src dst] <--move src dst>
+
+Options:
+  -c | --copy <src> <dst> .. Copy a file
+  --move <src> <dst>
+");
+    out.clear();
+    OPTIONS.print_help_to::<ClapShortUsageWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [OPTIONS] --move <src> <dst>");
+    out.clear();
+    OPTIONS.print_usage_to::<ClapErrorUsageWriter<'_, _>>(&mut out, "prog", ParseError::RequiredParameter("--move")).unwrap();
+    assert!(out.contains("  --move <src> <dst>\n"), "{out}");
+  }
+
+  #[test]
+  fn test_positional_group() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "file").required(),
+      Opt::positional(1, "host").group_with_next(),
+      Opt::positional(2, "port"),
+      Opt::positional(3, "user"),
+    ]);
+    let mut out = String::new();
+    OPTIONS.print_help_to::<StandardShortUsageWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog <file> [host port] [user]");
+    out.clear();
+    OPTIONS.with_value_style(ValueStyle::CLAP).print_help_to::<ClapShortUsageWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog <FILE> [HOST PORT] [USER]");
+  }
+
+  #[test]
+  fn test_collect_extra() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "command").required(),
+      Opt::positional(1, "args").collect_extra(),
+    ]);
+    let mut out = String::new();
+    OPTIONS.print_help_to::<StandardShortUsageWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog <command> [args]...");
+    out.clear();
+    OPTIONS.with_value_style(ValueStyle::CLAP).print_help_to::<ClapShortUsageWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog <COMMAND> [ARGS]...");
+  }
+
+  #[test]
+  fn test_multiline_help() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-o"], "path").help_text("Output path.\n\nDefaults to standard output."),
+      Opt::flag(1, &["-v"]).help_text("Verbose"),
+    ]);
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-o path] [-v]
+
+Options:
+  -o <path> .. Output path.
+
+               Defaults to standard output.
+  -v ......... Verbose
+");
+    out.clear();
+    OPTIONS.print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [OPTIONS]
+
+Options:
+  -o <path>  Output path.
+
+             Defaults to standard output.
+  -v         Verbose
+");
+  }
+
+  #[test]
+  fn test_choices() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--color"], "when").help_text("Colorize output")
+        .extras(&OptExtras::new().choices(&[Choice::new("always").synonyms(&["yes"]), Choice::new("never"), Choice::new("auto")])),
+      Opt::positional(1, "shape").extras(&OptExtras::new().choices(&[Choice::new("circle"), Choice::new("square")])),
+    ]);
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [--color when] [shape]
+
+Positional arguments:
+  shape ........... [possible values: circle, square]
+
+Options:
+  --color <when> .. Colorize output [possible values: always, never, auto]
+");
+    out.clear();
+    let error = ParseError::ArgumentError("shape", "oval", crate::ParseErrorKind::InvalidChoice);
+    OPTIONS.print_usage_to::<StandardErrorUsageWriter<'_, _>>(&mut out, "prog", error).unwrap();
+    assert!(out.contains("Possible values: circle, square\n"), "{out}");
+    out.clear();
+    let error = ParseError::ArgumentError("--color", "sometimes", crate::ParseErrorKind::InvalidChoice);
+    OPTIONS.print_usage_to::<ClapErrorUsageWriter<'_, _>>(&mut out, "prog", error).unwrap();
+    assert!(out.contains("'--color': not one of the possible values\n  [possible values: always, never, auto]\n"), "{out}");
+  }
+
+  #[test]
+  fn test_value_style() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "input").required(),
+      Opt::positional(1, "output").help_text("Output file"),
+      Opt::value(2, &["-n", "--name"], "name").help_text("Name to use"),
+    ]);
+    let write = |options: Opts<u32>, clap: bool| {
+      let mut out = String::new();
+      match clap {
+        true  => options.print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap(),
+        false => options.print_full_help_to(&mut out, "prog").unwrap(),
+      }
+      out
+    };
+    assert_eq!(write(OPTIONS.with_value_style(ValueStyle::GNU), false), "Usage: prog [-n|--name NAME] INPUT [OUTPUT]
+
+Positional arguments:
+  INPUT
+  [OUTPUT] .......... Output file
+
+Options:
+  -n | --name NAME .. Name to use
+");
+    assert_eq!(write(OPTIONS.with_value_style(ValueStyle::CLAP), false), "Usage: prog [-n|--name <NAME>] <INPUT> [OUTPUT]
+
+Positional arguments:
+  <INPUT>
+  [OUTPUT] ............ Output file
+
+Options:
+  -n | --name <NAME> .. Name to use
+");
+    assert_eq!(write(OPTIONS.with_value_style(ValueStyle::GNU), true), "Usage: prog [OPTIONS] INPUT [OUTPUT]
+
+Arguments:
+  INPUT
+  [OUTPUT]         Output file
+
+Options:
+  -n, --name NAME  Name to use
+");
+  }
+
+  #[test]
+  fn test_help_layout() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "file").help_text("Input file"),
+      Opt::value(1, &["-o", "--output"], "path").help_text("Output path"),
+      Opt::flag(2, &["-v"]).help_text("Verbose"),
+    ]);
+    let mut out = String::new();
+    OPTIONS.with_help_layout(HelpLayout { fill: ' ', min_gap: 2, indent: 4, max_left_width: 10, ..HelpLayout::DEFAULT })
+      .print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-o|--output path] [-v] [file]
+
+Positional arguments:
+    file        Input file
+
+Options:
+    -o | --output <path>
+                Output path
+    -v          Verbose
+");
+  }
+
+  #[test]
+  fn test_description_paragraphs() {
+    const OPTIONS: Opts<u32> = Opts::new(&[Opt::flag(0, &["-v"])]).with_description("
+Converts images between formats, keeping metadata where the target format supports it.
+
+
+Formats are detected from file extensions.
+  Indented lines that fit are kept.
+");
+    let mut out = String::new();
+    OPTIONS.with_help_layout(HelpLayout { width: 40, ..HelpLayout::DEFAULT }).print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-v]
+
+Converts images between formats, keeping
+metadata where the target format
+supports it.
+
+Formats are detected from file
+extensions.
+  Indented lines that fit are kept.
+
+Options:
+  -v
+");
+    out.clear();
+    OPTIONS.print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert!(out.starts_with("Converts images between formats, keeping metadata where the target format supports it.
+
+Formats are detected from file extensions.
+  Indented lines that fit are kept.
+
+Usage: prog [OPTIONS]
+"), "{out}");
+  }
+
+  #[test]
+  fn test_description_wide_characters() {
+    // Wide characters take two columns each
+    const OPTIONS: Opts<u32> = Opts::new(&[Opt::flag(0, &["-v"])]).with_description("比薩 比薩 比薩 比薩 pizza");
+    let mut out = String::new();
+    OPTIONS.with_help_layout(HelpLayout { width: 16, ..HelpLayout::DEFAULT }).print_full_help_to(&mut out, "prog").unwrap();
+    assert!(out.contains("\n比薩 比薩 比薩\n比薩 pizza\n"), "{out}");
+  }
+
+  #[test]
+  fn test_bug_report() {
+    const OPTIONS: Opts<u32> = Opts::new(&[Opt::flag(0, &["-v"]).help_text("Verbose")])
+      .with_bug_report("https://example.com/issues");
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-v]
+
+Options:
+  -v .. Verbose
+
+Report bugs to: https://example.com/issues
+");
+    out.clear();
+    OPTIONS.print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert!(out.ends_with("  -v  Verbose\n\nReport bugs to: https://example.com/issues\n"), "{out}");
+  }
+
+  #[test]
+  fn test_help_handler() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static WRITTEN: AtomicUsize = AtomicUsize::new(0);
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::help_flag(0, &["-h", "--help"]).help_text("Show help"),
+      Opt::positional(1, "file").required(),
+    ]).with_help_handler(|text| { WRITTEN.fetch_add(text.len(), Ordering::Relaxed); });
+    // The handler isn't called and missing required positionals aren't reported
+    assert_eq!(OPTIONS.parse("prog", ["-h"].iter(), |_| panic!("unreachable"),
+      |_, error| panic!("unreachable: {error:?}")), crate::ParseResult::ExitSuccess);
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(WRITTEN.load(Ordering::Relaxed), out.len());
+  }
+
+  #[test]
+  fn test_help_sections() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::positional(0, "file").help_text("Input file"),
+      Opt::flag(1, &["-v"]).help_text("Verbose"),
+    ]).with_help_sections(HelpSections {
+      positionals: Some("Inputs:"),
+      options: None,
+      order: [HelpSection::Options, HelpSection::Positionals],
+    });
+    let mut out = String::new();
+    OPTIONS.print_full_help_to(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [-v] [file]
+
+Options:
+  -v .... Verbose
+
+Inputs:
+  file .. Input file
+");
+    out.clear();
+    OPTIONS.print_help_to::<ClapFullHelpWriter<'_, _>>(&mut out, "prog").unwrap();
+    assert_eq!(out, "Usage: prog [OPTIONS] [file]
+
+Options:
+  -v      Verbose
+
+Inputs:
+  [file]  Input file
+");
+  }
+}
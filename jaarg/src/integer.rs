@@ -0,0 +1,111 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{ParseError, ParseErrorKind};
+
+/// Primitive integer types that can be parsed by [parse_int].
+pub trait Integer: Sized {
+  /// Converts a parsed magnitude and sign to this type, or [None] if it's out of range.
+  #[doc(hidden)]
+  fn from_magnitude(negative: bool, magnitude: u128) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+  (signed: $($t:ty),*) => {$(
+    impl Integer for $t {
+      fn from_magnitude(negative: bool, magnitude: u128) -> Option<Self> {
+        let value = match negative {
+          true => 0i128.checked_sub_unsigned(magnitude)?,
+          false => i128::try_from(magnitude).ok()?,
+        };
+        Self::try_from(value).ok()
+      }
+    }
+  )*};
+  (unsigned: $($t:ty),*) => {$(
+    impl Integer for $t {
+      fn from_magnitude(negative: bool, magnitude: u128) -> Option<Self> {
+        if negative && magnitude != 0 {
+          return None;
+        }
+        Self::try_from(magnitude).ok()
+      }
+    }
+  )*};
+}
+
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+
+/// Parses an integer in decimal, or in hexadecimal, octal, or binary with a `0x`, `0o`, or `0b` prefix,
+/// eg; for address and mask options. A sign may come before the prefix, like `-0x10`.
+/// Errors are [ParseError::ArgumentError]s with the option & argument fields filled in by the parser,
+/// [ParseErrorKind::IntegerRange] if the value doesn't fit in `T`.
+/// ```
+/// use jaarg::{parse_int, Opt, Opts, ParseControl};
+///
+/// assert_eq!(parse_int::<u32>("0xFFFF0000"), Ok(0xFFFF0000));
+/// assert_eq!(parse_int::<i8>("-0b10000000"), Ok(-128));
+///
+/// const OPTIONS: Opts<()> = Opts::new(&[Opt::value((), &["--mask"], "mask")]);
+/// let mut mask = 0u16;
+/// OPTIONS.parse("", ["--mask=0o777"].iter(), |ctx| {
+///   mask = ctx.parse_int()?;
+///   Ok(ParseControl::Continue)
+/// }, |_, _| {});
+/// assert_eq!(mask, 0o777);
+/// ```
+pub fn parse_int<T: Integer>(s: &str) -> Result<T, ParseError<'static>> {
+  if s.is_empty() {
+    return Err(ParseError::ArgumentError("", "", ParseErrorKind::IntegerEmpty));
+  }
+  let (negative, unsigned) = match s.strip_prefix('-') {
+    Some(unsigned) => (true, unsigned),
+    None => (false, s.strip_prefix('+').unwrap_or(s)),
+  };
+  let (radix, digits) = match unsigned.split_at_checked(2) {
+    Some(("0x" | "0X", digits)) => (16, digits),
+    Some(("0o" | "0O", digits)) => (8, digits),
+    Some(("0b" | "0B", digits)) => (2, digits),
+    _ => (10, unsigned),
+  };
+  // Signs are only accepted before the prefix, and a sign or prefix alone isn't a number
+  if digits.is_empty() || digits.starts_with(['+', '-']) {
+    return Err(ParseError::ArgumentError("", "", ParseErrorKind::InvalidInteger));
+  }
+  let magnitude = u128::from_str_radix(digits, radix)?;
+  T::from_magnitude(negative, magnitude).ok_or(ParseError::ArgumentError("", "", ParseErrorKind::IntegerRange))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_int() {
+    assert_eq!(parse_int::<u32>("42"), Ok(42));
+    assert_eq!(parse_int::<u32>("+0x2A"), Ok(42));
+    assert_eq!(parse_int::<u32>("0XdeadBEEF"), Ok(0xDEADBEEF));
+    assert_eq!(parse_int::<u8>("0o17"), Ok(0o17));
+    assert_eq!(parse_int::<u8>("0b1010"), Ok(0b1010));
+    assert_eq!(parse_int::<i8>("-0x80"), Ok(i8::MIN));
+    assert_eq!(parse_int::<i128>("-0x80000000000000000000000000000000"), Ok(i128::MIN));
+    assert_eq!(parse_int::<u128>("0xffffffffffffffffffffffffffffffff"), Ok(u128::MAX));
+    assert_eq!(parse_int::<u16>("-0"), Ok(0));
+    assert_eq!(parse_int::<i64>("007"), Ok(7));
+
+    let error = |kind| Some(ParseError::ArgumentError("", "", kind));
+    assert_eq!(parse_int::<u32>("").err(), error(ParseErrorKind::IntegerEmpty));
+    for s in ["-", "0x", "0b", "0x-1", "--1", "0b102", "0o8", "0xg", "1.0", "x10", " 1"] {
+      assert_eq!(parse_int::<i32>(s).err(), error(ParseErrorKind::InvalidInteger), "'{s}'");
+    }
+    for s in ["0x100", "256", "-1", "-0b1"] {
+      assert_eq!(parse_int::<u8>(s).err(), error(ParseErrorKind::IntegerRange), "'{s}'");
+    }
+    assert_eq!(parse_int::<i8>("-0x81").err(), error(ParseErrorKind::IntegerRange));
+    assert_eq!(parse_int::<i8>("0x80").err(), error(ParseErrorKind::IntegerRange));
+    assert_eq!(parse_int::<u128>("0x100000000000000000000000000000000").err(), error(ParseErrorKind::IntegerRange));
+  }
+}
@@ -4,21 +4,80 @@
  */
 
 #![no_std]
+// Parsing must never panic on user input, so panicking shortcuts and unchecked indexing are denied outside of tests.
+// Const fns, which can't use `get`, allow indexing that's bounded by their loop conditions.
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used, clippy::unreachable, clippy::panic,
+  clippy::indexing_slicing, clippy::string_slice))]
 
-mod const_utf8;
 mod ordered_bitset;
 
 mod option;
 mod options;
 mod argparse;
 mod help;
+mod verbosity;
+mod from_args;
+mod macros;
+mod fixed_map;
+mod seen_set;
+mod binding;
+mod utf8;
+mod utf16;
+mod multicall;
+mod name_index;
+mod trace;
+mod catalog;
+mod toggle;
+mod terminal;
+mod observer;
+mod choice;
+mod integer;
+mod completion;
+mod action;
 
 pub use option::*;
 pub use options::*;
 pub use argparse::*;
 pub use help::*;
+pub use verbosity::*;
+pub use from_args::*;
+pub use fixed_map::*;
+pub use seen_set::*;
+pub use binding::*;
+pub use utf8::*;
+pub use utf16::*;
+pub use multicall::*;
+pub use name_index::*;
+pub use catalog::*;
+pub use toggle::*;
+pub use terminal::*;
+pub use observer::*;
+pub use choice::Choice;
+pub use integer::*;
+pub use completion::*;
+pub use action::MapAction;
+pub(crate) use choice::ChoiceList;
+#[cfg(feature = "trace")]
+pub use trace::TraceEvent;
+
+pub mod const_str;
+pub mod shlex;
 
 #[cfg(feature = "alloc")]
 pub mod alloc;
+#[cfg(feature = "alloc")]
+pub mod getopts;
+#[cfg(feature = "alloc")]
+pub mod query;
+#[cfg(feature = "alloc")]
+pub mod windows;
 #[cfg(feature = "std")]
 pub mod std;
+#[cfg(feature = "std")]
+pub mod dotenv;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "wild")]
+mod wild;
+#[cfg(feature = "serde")]
+mod serialize;
@@ -0,0 +1,110 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+/// Declares an ID enum together with a const [Opts](crate::Opts) table using it.
+///
+/// Each entry names an enum variant followed by an [Opt](crate::Opt) constructor call without the
+/// ID parameter, and any builder methods. Methods following the table are applied to the [Opts](crate::Opts).
+///
+/// ```
+/// jaarg::options! {
+///   #[derive(Clone, Copy, PartialEq)]
+///   enum Arg;
+///   const OPTIONS {
+///     /// Shows the help text
+///     Help => help_flag(&["-h", "--help"]).help_text("Show this help"),
+///     Number => value(&["-n", "--number"], "value").help_text("Optional number value"),
+///     File => positional("file").help_text("Input file").required(),
+///   }.with_description("Example program.")
+/// }
+/// assert!(matches!(OPTIONS.find_by_id(&Arg::Number).map(|o| o.first_name()), Some("-n")));
+/// ```
+#[macro_export]
+macro_rules! options {
+  (
+    $(#[$meta:meta])*
+    $vis:vis enum $name:ident;
+    $cvis:vis const $opts:ident {
+      $(
+        $(#[$vmeta:meta])*
+        $variant:ident => $ctor:ident($($args:expr),* $(,)?) $(.$method:ident($($margs:expr),* $(,)?))*
+      ),* $(,)?
+    } $(.$omethod:ident($($oargs:expr),* $(,)?))*
+  ) => {
+    $(#[$meta])*
+    $vis enum $name {
+      $($(#[$vmeta])* $variant,)*
+    }
+
+    $cvis const $opts: $crate::Opts<'static, $name> = $crate::Opts::new(&[
+      $($crate::Opt::$ctor($name::$variant, $($args),*)$(.$method($($margs),*))*,)*
+    ])$(.$omethod($($oargs),*))*;
+  };
+}
+
+/// Validates an [Opts](crate::Opts) table at compile time with [Opts::assert_valid](crate::Opts::assert_valid),
+/// so mistakes in the interface fail the build instead of surfacing at runtime.
+///
+/// ```
+/// use jaarg::{Opt, Opts};
+/// const OPTIONS: Opts<u32> = Opts::new(&[
+///   Opt::help_flag(0, &["-h", "--help"]),
+///   Opt::positional(1, "file").required(),
+///   Opt::positional(2, "out"),
+/// ]);
+/// jaarg::assert_opts!(OPTIONS);
+/// ```
+///
+/// ```compile_fail
+/// use jaarg::{Opt, Opts};
+/// const OPTIONS: Opts<u32> = Opts::new(&[
+///   Opt::positional(1, "out"),
+///   Opt::positional(2, "file").required(),
+/// ]);
+/// jaarg::assert_opts!(OPTIONS);
+/// ```
+#[macro_export]
+macro_rules! assert_opts {
+  ($opts:expr $(,)?) => {
+    const _: () = $opts.assert_valid();
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{OptType, ParseControl, ParseResult};
+
+  crate::options! {
+    #[derive(Debug, PartialEq)]
+    enum Arg;
+    const OPTIONS {
+      Help => help_flag(&["-h", "--help"]).help_text("Show this help"),
+      Verbose => flag(&["-v"]),
+      Number => value(&["-n", "--number"], "value").help_text("Number value").required(),
+      File => positional("file",),
+    }.with_flag_chars("-")
+  }
+
+  #[test]
+  fn test_options_macro() {
+    assert_eq!(OPTIONS.options.len(), 4);
+    assert_eq!(OPTIONS.flag_chars, "-");
+    let number = OPTIONS.find_by_id(&Arg::Number).unwrap();
+    assert!(matches!(number.r#type, OptType::Value));
+    assert!(number.is_required());
+    assert_eq!(number.help_string, Some("Number value"));
+
+    let mut matched = [false; 2];
+    assert!(matches!(OPTIONS.parse("", ["-n", "1", "a"].iter(), |ctx| {
+      match ctx.id {
+        Arg::Number => matched[0] = ctx.arg == "1",
+        Arg::File => matched[1] = ctx.arg == "a",
+        _ => panic!("unreachable"),
+      }
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(matched, [true, true]);
+  }
+}
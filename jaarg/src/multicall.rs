@@ -0,0 +1,57 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+/// Table mapping invoked program names to entry points for busybox-style multicall binaries,
+/// where each applet has its own [Opts](crate::Opts) and handler.
+#[derive(Debug, Clone, Copy)]
+pub struct Multicall<'a, F> {
+  applets: &'a [(&'a str, F)],
+}
+
+impl<'a, F> Multicall<'a, F> {
+  /// Create a dispatch table from pairs of program names and entry points.
+  pub const fn new(applets: &'a [(&'a str, F)]) -> Self {
+    Self { applets }
+  }
+
+  /// Find the entry point for a program name, which may be a path as given in `argv[0]`.
+  /// Returns the applet name along with its entry point.
+  pub fn find(&self, program: &str) -> Option<(&'a str, &'a F)> {
+    let name = Self::basename(program);
+    self.applets.iter().find(|(n, _)| *n == name).map(|(n, f)| (*n, f))
+  }
+
+  /// Iterate the names of every applet.
+  pub fn names(&self) -> impl Iterator<Item = &'a str> {
+    self.applets.iter().map(|(name, _)| *name)
+  }
+
+  /// Strip leading directories and an executable extension, in any case, from a program path.
+  pub fn basename(program: &str) -> &str {
+    let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    match name.len().checked_sub(4).and_then(|i| name.split_at_checked(i)) {
+      Some((stem, extension)) if extension.eq_ignore_ascii_case(".exe") => stem,
+      _ => name,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_multicall() {
+    const APPLETS: Multicall<u32> = Multicall::new(&[("true", 0), ("false", 1)]);
+    assert_eq!(APPLETS.find("/usr/bin/false"), Some(("false", &1)));
+    assert_eq!(APPLETS.find(r"C:\bin\true.exe"), Some(("true", &0)));
+    assert_eq!(APPLETS.find(r"C:\BIN\true.EXE"), Some(("true", &0)));
+    assert_eq!(Multicall::<u32>::basename("tool.é.exe"), "tool.é");
+    assert_eq!(Multicall::<u32>::basename("é"), "é");
+    assert_eq!(APPLETS.find("true/"), None);
+    assert_eq!(APPLETS.find("yes"), None);
+    assert!(APPLETS.names().eq(["true", "false"]));
+  }
+}
@@ -0,0 +1,243 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::Opts;
+use crate::option::OptType;
+
+/// Option names sorted at compile time for binary search lookup,
+/// built by [Opts::name_index] and attached with [Opts::with_index].
+///
+/// `N` must be the number of option names in the table, as given by [Opts::name_count]:
+/// ```
+/// # use jaarg::{NameIndex, Opt, Opts};
+/// const OPTIONS: Opts<u32> = Opts::new(&[
+///   Opt::flag(0, &["-v", "--verbose"]),
+///   Opt::value(1, &["-o", "--output"], "path"),
+/// ]);
+/// const INDEX: NameIndex<{ OPTIONS.name_count() }> = OPTIONS.name_index();
+/// const INDEXED: Opts<u32> = OPTIONS.with_index(&INDEX);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NameIndex<const N: usize> {
+  /// Entries for every option name, sorted by name ignoring the flag character
+  entries: [IndexEntry; N],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct IndexEntry {
+  /// Index of the option in the table
+  option: usize,
+  /// Index of the name in the option's names
+  name: usize,
+  /// Position of the option among the required flag and value options
+  required: usize,
+  /// Position of the option among the value options that can be given positionally
+  positional: usize,
+}
+
+/// The bytes of an option name after its first (flag) character, which may be multibyte.
+const fn name_suffix(name: &str) -> &[u8] {
+  let bytes = name.as_bytes();
+  let len = match bytes.first() {
+    None => 0,
+    Some(&b) if b < 0x80 => 1,
+    Some(&b) if b < 0xE0 => 2,
+    Some(&b) if b < 0xF0 => 3,
+    Some(_) => 4,
+  };
+  bytes.split_at(len).1
+}
+
+/// Compare option names lexicographically, ignoring the first (flag) character.
+#[allow(clippy::indexing_slicing)]
+const fn name_less(lhs: &str, rhs: &str) -> bool {
+  let (lhs, rhs) = (name_suffix(lhs), name_suffix(rhs));
+  let mut i = 0;
+  while i < lhs.len() && i < rhs.len() {
+    if lhs[i] != rhs[i] {
+      return lhs[i] < rhs[i];
+    }
+    i += 1;
+  }
+  lhs.len() < rhs.len()
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Count the names of every flag and value option, the size of the [NameIndex] for this table.
+  #[allow(clippy::indexing_slicing)]
+  pub const fn name_count(&self) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < self.options.len() {
+      if matches!(self.options[i].r#type, OptType::Flag | OptType::Value) {
+        let names = self.options[i].names();
+        let mut j = 0;
+        while j < names.len() {
+          if !names[j].is_empty() {
+            count += 1;
+          }
+          j += 1;
+        }
+      }
+      i += 1;
+    }
+    count
+  }
+
+  /// Build a sorted index of the option names in this table for [Opts::with_index].
+  #[allow(clippy::indexing_slicing)]
+  pub const fn name_index<const N: usize>(&self) -> NameIndex<N> {
+    assert!(N == self.name_count(), "Index size must match the number of option names");
+    let mut entries = [IndexEntry { option: 0, name: 0, required: 0, positional: 0 }; N];
+    let mut len = 0;
+    let (mut required, mut positional) = (0, 0);
+    let mut i = 0;
+    while i < self.options.len() {
+      if matches!(self.options[i].r#type, OptType::Flag | OptType::Value) {
+        let names = self.options[i].names();
+        let mut j = 0;
+        while j < names.len() {
+          if !names[j].is_empty() {
+            // Insertion sort, keeping declaration order for names that compare equal
+            let mut k = len;
+            while k > 0 && name_less(names[j], self.name_at(entries[k - 1])) {
+              entries[k] = entries[k - 1];
+              k -= 1;
+            }
+            entries[k] = IndexEntry { option: i, name: j, required, positional };
+            len += 1;
+          }
+          j += 1;
+        }
+        if self.options[i].is_required() {
+          required += 1;
+        }
+        if self.options[i].takes_positional() {
+          positional += 1;
+        }
+      }
+      i += 1;
+    }
+    NameIndex { entries }
+  }
+
+  /// Use a [NameIndex] to look up options by name in O(log n) instead of scanning every option.
+  /// Panics if the index wasn't built from this same table.
+  #[allow(clippy::indexing_slicing)]
+  pub const fn with_index<const N: usize>(mut self, index: &'o NameIndex<N>) -> Self {
+    assert!(N == self.name_count(), "Index was built from a different options table");
+    // Compare against a fresh index so lookups can't go out of bounds or miss names
+    let expected = self.name_index::<N>();
+    let mut i = 0;
+    while i < N {
+      let (entry, expected) = (index.entries[i], expected.entries[i]);
+      assert!(entry.option == expected.option && entry.name == expected.name && entry.required == expected.required
+        && entry.positional == expected.positional, "Index was built from a different options table");
+      i += 1;
+    }
+    self.index = Some(&index.entries);
+    self
+  }
+
+  #[inline(always)]
+  #[allow(clippy::indexing_slicing)]
+  const fn name_at(&self, entry: IndexEntry) -> &'o str {
+    self.options[entry.option].names()[entry.name]
+  }
+
+  /// Find the flag or value option matching a token, ignoring the first flag character.
+  /// Returns the option's index in the table, the name that matched, its position among the required flag and
+  /// value options (meaningful only if it's required), and its position among the value options that can be given
+  /// positionally (meaningful only if it can be).
+  pub(crate) fn find_option(&self, token: &str) -> Option<(usize, &'o str, usize, usize)> {
+    match self.index {
+      Some(index) => {
+        let key = name_suffix(token);
+        if key.is_empty() {
+          return None;
+        }
+        let name = |entry: IndexEntry| self.options.get(entry.option)
+          .and_then(|option| option.names().get(entry.name)).copied().unwrap_or_default();
+        let first = index.partition_point(|&entry| name_suffix(name(entry)) < key);
+        index.get(first).filter(|&&entry| name_suffix(name(entry)) == key)
+          .map(|&entry| (entry.option, name(entry), entry.required, entry.positional))
+      }
+      None => {
+        // Count the required and positional options passed over while scanning
+        let (mut required, mut positional) = (0, 0);
+        self.options.iter().enumerate()
+          .filter(|(_, opt)| matches!(opt.r#type, OptType::Flag | OptType::Value))
+          .find_map(|(i, opt)| match opt.match_name(token, 1) {
+            Some(name) => Some((i, name, required, positional)),
+            None => {
+              required += opt.is_required() as usize;
+              positional += opt.takes_positional() as usize;
+              None
+            }
+          })
+      }
+    }
+  }
+
+  /// Gets the position of the required flag or value option at `index` in the table among every
+  /// required flag and value option, or [None] if the option isn't one.
+  pub fn required_ordinal(&self, index: usize) -> Option<usize> {
+    let option = self.options.get(index)?;
+    (matches!(option.r#type, OptType::Flag | OptType::Value) && option.is_required()).then(|| {
+      self.options.iter().take(index)
+        .filter(|opt| matches!(opt.r#type, OptType::Flag | OptType::Value) && opt.is_required())
+        .count()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::positional(0, "file"),
+    Opt::flag(1, &["-v", "--verbose"]),
+    Opt::value(2, &["--output", "-o"], "path").required(),
+    Opt::flag(3, &["/a"]),
+    Opt::flag(4, &["-a", "--all"]).required(),
+    Opt::flag(5, &["-é"]),
+  ]).with_flag_chars("-/");
+  const INDEX: NameIndex<{ OPTIONS.name_count() }> = OPTIONS.name_index();
+  const INDEXED: Opts<u32> = OPTIONS.with_index(&INDEX);
+
+  #[test]
+  fn test_name_index() {
+    assert_eq!(OPTIONS.name_count(), 8);
+    for token in ["-v", "/verbose", "--output", "-o", "-a", "/a", "--all", "-é", "-x", "--", "-", "-fil"] {
+      assert_eq!(INDEXED.find_option(token), OPTIONS.find_option(token), "{token}");
+    }
+    assert_eq!(INDEXED.find_option("-a"), Some((3, "/a", 1, 0)));
+    assert_eq!(INDEXED.find_option("/-all"), Some((4, "--all", 1, 0)));
+    assert_eq!(INDEXED.find_option("-file"), None);
+    assert_eq!([0, 1, 2, 4, 6].map(|i| OPTIONS.required_ordinal(i)), [None, None, Some(0), Some(1), None]);
+
+    use crate::{ParseControl, ParseError, ParseResult};
+    assert!(matches!(INDEXED.parse("", ["--all", "-o", "x", "in"].iter(), |_| Ok(ParseControl::Continue),
+      |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert!(matches!(INDEXED.parse("", ["--output=x", "/a"].iter(), |_| Ok(ParseControl::Continue),
+      |_, error| assert!(matches!(error, ParseError::RequiredParameter("-a")))), ParseResult::ExitFailure));
+  }
+
+  #[test]
+  #[should_panic(expected = "Index size must match the number of option names")]
+  fn test_name_index_size() {
+    let _ = OPTIONS.name_index::<7>();
+  }
+
+  #[test]
+  #[should_panic(expected = "Index was built from a different options table")]
+  fn test_name_index_mismatch() {
+    const OTHER: Opts<u32> = Opts::new(&[Opt::flag(0, &["-a", "-b", "-c", "-d", "-e", "-f", "-g", "-h"])]);
+    const OTHER_INDEX: NameIndex<8> = OTHER.name_index();
+    let _ = OPTIONS.with_index(&OTHER_INDEX);
+  }
+}
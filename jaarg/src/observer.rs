@@ -0,0 +1,116 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Opt, Opts, ParseError};
+
+/// Something that happened while parsing, passed to the observer set with [Opts::with_observer]
+/// before the handler sees it.
+#[derive(Debug)]
+pub enum ParseEvent<'a, ID> {
+  /// A flag or value option was matched by `name`.
+  Option { option: &'a Opt<'a, ID>, name: &'a str },
+  /// A value option matched by `name` consumed `value`, from the same token or the next.
+  Value { option: &'a Opt<'a, ID>, name: &'a str, value: &'a str },
+  /// The token was assigned to a positional argument.
+  Positional { option: &'a Opt<'a, ID>, value: &'a str },
+  /// Parsing failed, the error is passed to the error handler next.
+  Error(&'a ParseError<'a>),
+}
+
+/// The observer set with [Opts::with_observer], compared by address so [Opts] can still derive [PartialEq] & [Debug].
+pub(crate) struct ObserverSink<ID>(fn(ParseEvent<ID>));
+
+impl<ID> Clone for ObserverSink<ID> {
+  fn clone(&self) -> Self { *self }
+}
+
+impl<ID> Copy for ObserverSink<ID> {}
+
+impl<ID> PartialEq for ObserverSink<ID> {
+  fn eq(&self, other: &Self) -> bool {
+    core::ptr::fn_addr_eq(self.0, other.0)
+  }
+}
+
+impl<ID> core::fmt::Debug for ObserverSink<ID> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("ParseObserver")
+  }
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Send structured events about how the program was invoked to `observer`, alongside the normal handler,
+  /// eg; for audit logging or usage analytics.
+  /// ```
+  /// # use jaarg::{Opt, Opts, ParseEvent};
+  /// const OPTIONS: Opts<&str> = Opts::new(&[Opt::flag("verbose", &["-v"])])
+  ///   .with_observer(|event| if let ParseEvent::Option { option, .. } = event {
+  ///     eprintln!("used {}", option.first_name());
+  ///   });
+  /// ```
+  #[inline]
+  pub const fn with_observer(mut self, observer: fn(ParseEvent<ID>)) -> Self {
+    self.sinks.observer = Some(ObserverSink(observer));
+    self
+  }
+
+  /// Send an event to the observer, if there is one.
+  #[inline]
+  pub(crate) fn observe(&self, event: ParseEvent<ID>) {
+    if let Some(observer) = self.sinks.observer {
+      (observer.0)(event);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate std;
+  use std::cell::RefCell;
+  use std::format;
+  use std::vec::Vec;
+  use std::string::String;
+  use crate::{ParseControl, ParseResult};
+  use super::*;
+
+  std::thread_local! {
+    static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+  }
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::flag(0, &["-v"]),
+    Opt::value(1, &["-o", "--out"], "out"),
+    Opt::positional(2, "file").required(),
+  ]).with_observer(|event| EVENTS.with_borrow_mut(|events| events.push(match event {
+    ParseEvent::Option { option, name } => format!("option {} {name}", option.id),
+    ParseEvent::Value { option, name, value } => format!("value {} {name} {value}", option.id),
+    ParseEvent::Positional { option, value } => format!("positional {} {value}", option.id),
+    ParseEvent::Error(error) => format!("error {error}"),
+  })));
+
+  #[test]
+  fn test_observer() {
+    let mut handled = 0;
+    assert_eq!(OPTIONS.parse("", ["-v", "-o", "x", "--out=y", "in"].iter(), |_| {
+      handled += 1;
+      Ok(ParseControl::Continue)
+    }, |_, _| {}), ParseResult::ContinueSuccess);
+    assert_eq!(handled, 4);
+    assert_eq!(EVENTS.take(), [
+      "option 0 -v", "option 1 -o", "value 1 -o x", "option 1 --out", "value 1 --out y", "positional 2 in",
+    ]);
+
+    // Errors raised by the parser, by the handler, and when finishing are all observed
+    let _ = OPTIONS.parse("", ["-q"].iter(), |_| Ok(ParseControl::Continue), |_, _| {});
+    let _ = OPTIONS.parse("", ["-o", "x"].iter(), |_| Err(ParseError::InvalidValue("", "", "nope")), |_, _| {});
+    let _ = OPTIONS.parse("", ["-v"].iter(), |_| Ok(ParseControl::Continue), |_, _| {});
+    let errors = [
+      ParseError::UnknownOption("-q"), ParseError::InvalidValue("-o", "x", "nope"), ParseError::RequiredPositional("file"),
+    ].map(|error| format!("error {error}"));
+    assert_eq!(EVENTS.take(), [
+      errors[0].as_str(), "option 1 -o", "value 1 -o x", &errors[1], "option 0 -v", &errors[2],
+    ]);
+  }
+}
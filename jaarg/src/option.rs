@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: MIT OR Apache-2.0
  */
 
-use crate::const_utf8;
+use crate::const_str;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub(crate) enum OptType {
@@ -12,21 +12,67 @@ pub(crate) enum OptType {
   Value,
 }
 
-#[derive(Debug, PartialEq)]
-pub(crate) enum OptIdentifier {
-  Single(&'static str),
-  Multi(&'static[&'static str]),
+/// The kind of value an option or positional argument takes, used by typed parsing.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum ValueKind {
+  /// Any string (the default).
+  #[default]
+  String,
+  /// A signed integer.
+  Integer,
+  /// A floating-point number.
+  Float,
+  /// A [Toggle](crate::Toggle) switch, `on`, `off`, or `auto`.
+  Toggle,
 }
 
 /// Represents an option argument or positional argument to be parsed.
 #[derive(Debug, PartialEq)]
-pub struct Opt<ID> {
+pub struct Opt<'o, ID> {
   pub(crate) id: ID,
-  pub(crate) names: OptIdentifier,
-  pub(crate) value_name: Option<&'static str>,
-  pub(crate) help_string: Option<&'static str>,
+  /// Names of flag and value options, empty for positional arguments
+  names: &'o [&'o str],
+  /// Name of a positional argument, or the value name of a value option
+  name: &'o str,
+  pub(crate) help_string: Option<&'o str>,
   pub(crate) r#type: OptType,
+  pub(crate) value_kind: ValueKind,
   flags: OptFlag,
+  /// Rarely used settings attached with [Opt::extras], kept out of line so tables stay small
+  extras: Option<&'o OptExtras<'o, ID>>,
+}
+
+/// Rarely used settings of an option, attached with [Opt::extras].
+/// Declared separately so that every [Opt] in a table doesn't pay for them.
+///
+/// These are the [metavar](OptExtras::metavar), [value names](OptExtras::value_names),
+/// [choices](OptExtras::choices), [constant](OptExtras::constant), [map action](OptExtras::map_action),
+/// [callback](OptExtras::callback) and [print and exit](OptExtras::print_and_exit) settings. They aren't
+/// builders on [Opt] because a `const fn` can't create the out-of-line storage an [Opt] refers to,
+/// while `&OptExtras::new()...` in a `const` table is promoted to a `'static` reference.
+/// ```
+/// # use jaarg::{Choice, Opt, OptExtras, Opts};
+/// const OPTIONS: Opts<&str> = Opts::new(&[
+///   Opt::value("copy", &["--copy"], "src").extras(&OptExtras::new().value_names(&["SRC", "DST"])),
+///   Opt::positional("shape", "shape")
+///     .extras(&OptExtras::new().metavar("SHAPE").choices(&[Choice::new("circle"), Choice::new("square")])),
+/// ]);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct OptExtras<'o, ID> {
+  /// Value name of each value taken by value options, replacing the option's value name
+  pub(crate) value_names: &'o [&'o str],
+  /// Placeholder shown for a positional argument instead of its name
+  pub(crate) metavar: Option<&'o str>,
+  /// Values accepted by the option, or empty to accept anything
+  pub(crate) choices: &'o [crate::Choice<'o>],
+  /// Argument passed to the handler by flags
+  pub(crate) constant: Option<&'o str>,
+  pub(crate) terminal: Option<crate::Terminal<'o>>,
+  /// How the option is stored by [Opts::parse_map_typed](crate::Opts::parse_map_typed)
+  pub(crate) map_action: Option<crate::MapAction>,
+  /// Function called instead of the handler
+  pub(crate) callback: Option<crate::action::Callback<ID>>,
 }
 
 pub enum OptHide {
@@ -36,7 +82,7 @@ pub enum OptHide {
 }
 
 #[derive(Debug, PartialEq)]
-struct OptFlag(u8);
+struct OptFlag(u16);
 
 impl OptFlag {
   #[allow(dead_code)]
@@ -45,37 +91,47 @@ impl OptFlag {
   pub const HELP: Self          = OptFlag(1 << 1);
   pub const VISIBLE_SHORT: Self = OptFlag(1 << 2);
   pub const VISIBLE_FULL: Self  = OptFlag(1 << 3);
+  #[cfg(feature = "wild")]
+  pub const NO_WILDCARDS: Self  = OptFlag(1 << 4);
+  pub const GROUP_NEXT: Self    = OptFlag(1 << 5);
+  pub const POSITIONAL: Self    = OptFlag(1 << 6);
+  pub const NO_COMPLETION: Self = OptFlag(1 << 7);
+  pub const NON_EMPTY: Self     = OptFlag(1 << 8);
+  pub const COMPLETIONS: Self   = OptFlag(1 << 9);
+  pub const COLLECT_EXTRA: Self = OptFlag(1 << 10);
 
   pub const DEFAULT: Self = Self(Self::VISIBLE_SHORT.0 | Self::VISIBLE_FULL.0);
 }
 
-// TODO: Improve this interface by making the name field take AsOptIdentifier when const traits are stabilised
-impl<ID> Opt<ID> {
+// TODO: Improve this interface by making the name field take a trait for one or many names when const traits are stabilised
+impl<'o, ID> Opt<'o, ID> {
   #[inline]
-  const fn new(id: ID, names: OptIdentifier, value_name: Option<&'static str>, r#type: OptType) -> Self {
-    assert!(match names {
-      OptIdentifier::Single(_) => true,
-      OptIdentifier::Multi(names) => !names.is_empty(),
-    }, "Option names cannot be an empty slice");
-    Self { id, names, value_name, help_string: None, r#type, flags: OptFlag::DEFAULT }
+  const fn new(id: ID, names: &'o [&'o str], name: &'o str, r#type: OptType) -> Self {
+    assert!(!names.is_empty() || matches!(r#type, OptType::Positional), "Option names cannot be an empty slice");
+    Self { id, names, name, help_string: None, r#type, value_kind: ValueKind::String, flags: OptFlag::DEFAULT, extras: None }
   }
 
   /// A positional argument that is parsed sequentially without being invoked by an option flag.
-  pub const fn positional(id: ID, name: &'static str) -> Self {
-    Self::new(id, OptIdentifier::Single(name), None, OptType::Positional)
+  pub const fn positional(id: ID, name: &'o str) -> Self {
+    Self::new(id, &[], name, OptType::Positional)
   }
   /// A flag-type option that serves as the interface's help flag.
-  pub const fn help_flag(id: ID, names: &'static[&'static str]) -> Self {
-    Self::new(id, OptIdentifier::Multi(names), None, OptType::Flag)
+  pub const fn help_flag(id: ID, names: &'o [&'o str]) -> Self {
+    Self::new(id, names, "", OptType::Flag)
       .with_help_flag()
   }
   /// A flag-type option, takes no value.
-  pub const fn flag(id: ID, names: &'static[&'static str]) -> Self {
-    Self::new(id, OptIdentifier::Multi(names), None, OptType::Flag)
+  pub const fn flag(id: ID, names: &'o [&'o str]) -> Self {
+    Self::new(id, names, "", OptType::Flag)
   }
   /// An option argument that takes a value.
-  pub const fn value(id: ID, names: &'static[&'static str], value_name: &'static str) -> Self {
-    Self::new(id, OptIdentifier::Multi(names), Some(value_name), OptType::Value)
+  pub const fn value(id: ID, names: &'o [&'o str], value_name: &'o str) -> Self {
+    Self::new(id, names, value_name, OptType::Value)
+  }
+
+  /// An option argument that takes a [Toggle](crate::Toggle) value, shown as `<on|off|auto>` in help.
+  pub const fn toggle(id: ID, names: &'o [&'o str]) -> Self {
+    Self::value(id, names, crate::Toggle::VALUE_NAME).value_kind(ValueKind::Toggle)
   }
 
   /// This option is required, ie; parsing will fail if it is not specified.
@@ -86,13 +142,80 @@ impl<ID> Opt<ID> {
     self
   }
 
+  /// Groups this optional positional argument with the next one, so that both must be provided or neither,
+  /// shown as one bracketed group in usage, eg; `[host port]`. Chain it for groups of more than two.
+  #[inline]
+  pub const fn group_with_next(mut self) -> Self {
+    assert!(matches!(self.r#type, OptType::Positional), "Only positional arguments can be grouped");
+    self.flags.0 |= OptFlag::GROUP_NEXT.0;
+    self
+  }
+
+  /// Passes every positional token beyond the declared positional arguments to this one, instead of
+  /// failing with [ParseError::UnexpectedToken](crate::ParseError::UnexpectedToken), shown as `[args]...` in usage.
+  /// It must be the last positional argument.
+  #[inline]
+  pub const fn collect_extra(mut self) -> Self {
+    assert!(matches!(self.r#type, OptType::Positional), "Only positional arguments can collect extra positionals");
+    self.flags.0 |= OptFlag::COLLECT_EXTRA.0;
+    self
+  }
+
+  /// Lets this value option also be given positionally, taking its turn among the positional arguments
+  /// in the order it's declared, eg; `tool build target` as well as `tool build --target target`.
+  /// When given by name its positional turn is skipped, repeats reach the handler like repeated options do.
+  #[inline]
+  pub const fn or_positional(mut self) -> Self {
+    assert!(matches!(self.r#type, OptType::Value) && self.value_names().len() == 1,
+      "Only options taking a single value can be given positionally");
+    self.flags.0 |= OptFlag::POSITIONAL.0;
+    self
+  }
+
+  /// Rejects an empty argument to this value option, eg; `--name=` or `--name ""`, with
+  /// [ParseError::ExpectArgument](crate::ParseError::ExpectArgument) before the handler is called.
+  #[inline]
+  pub const fn non_empty(mut self) -> Self {
+    assert!(matches!(self.r#type, OptType::Value), "Only value options can reject empty arguments");
+    self.flags.0 |= OptFlag::NON_EMPTY.0;
+    self
+  }
+
   /// Sets the help string for an option.
   #[inline]
-  pub const fn help_text(mut self, help_string: &'static str) -> Self {
+  pub const fn help_text(mut self, help_string: &'o str) -> Self {
     self.help_string = Some(help_string);
     self
   }
 
+  /// Attaches rarely used settings declared with [OptExtras], which must suit this kind of option.
+  #[inline]
+  pub const fn extras(mut self, extras: &'o OptExtras<'o, ID>) -> Self {
+    let is_flag = matches!(self.r#type, OptType::Flag);
+    assert!(extras.value_names.is_empty() || matches!(self.r#type, OptType::Value), "Only value options take value names");
+    assert!(extras.value_names.len() <= 1 || (self.flags.0 & OptFlag::POSITIONAL.0) == 0,
+      "Only options taking a single value can be given positionally");
+    assert!(extras.metavar.is_none() || matches!(self.r#type, OptType::Positional),
+      "Only positional arguments take a metavar, value options take a value name");
+    assert!(extras.choices.is_empty() || !is_flag, "Flags don't take a value");
+    assert!(extras.constant.is_none() || (is_flag && !self.is_help()), "Only flags can carry a constant");
+    assert!(extras.terminal.is_none() || is_flag, "Only flags can print and exit");
+    assert!(!matches!(extras.map_action, Some(crate::MapAction::SetTrue)) || is_flag, "Only flags can be set to true");
+    assert!(extras.map_action.is_none() || extras.callback.is_none(), "Options with a callback aren't stored");
+    assert!((extras.map_action.is_none() && extras.callback.is_none()) || !self.is_help(),
+      "Help flags can't have an action or callback");
+    self.extras = Some(extras);
+    self
+  }
+
+  /// Sets the kind of value that the option or positional argument takes, for typed parsing.
+  #[inline]
+  pub const fn value_kind(mut self, kind: ValueKind) -> Self {
+    assert!(!matches!(self.r#type, OptType::Flag), "Flags don't take a value");
+    self.value_kind = kind;
+    self
+  }
+
   /// Marks the option to exclude it from appearing in short usage text, full help text, or both.
   #[inline]
   pub const fn hide_usage(mut self, from: OptHide) -> Self {
@@ -104,6 +227,31 @@ impl<ID> Opt<ID> {
     self
   }
 
+  /// Marks the option to exclude it from generated shell completions, eg; for internal or diagnostic options.
+  /// This is independent of [Opt::hide_usage], which only affects help text.
+  #[inline]
+  pub const fn hide_completion(mut self) -> Self {
+    self.flags.0 |= OptFlag::NO_COMPLETION.0;
+    self
+  }
+
+  /// Disables wildcard expansion of this positional argument by [Opts::parse_easy](crate::Opts::parse_easy) on Windows.
+  ///
+  /// Requires `features = ["wild"]`.
+  #[cfg(feature = "wild")]
+  #[inline]
+  pub const fn no_wildcards(mut self) -> Self {
+    assert!(matches!(self.r#type, OptType::Positional), "Only positional arguments are expanded");
+    self.flags.0 |= OptFlag::NO_WILDCARDS.0;
+    self
+  }
+
+  #[inline]
+  pub(crate) const fn with_completions_flag(mut self) -> Self {
+    self.flags.0 |= OptFlag::COMPLETIONS.0;
+    self
+  }
+
   #[inline]
   const fn with_help_flag(mut self) -> Self {
     assert!(matches!(self.r#type, OptType::Flag), "Only flags are allowed to be help options");
@@ -123,6 +271,53 @@ impl<ID> Opt<ID> {
     (self.flags.0 & OptFlag::HELP.0) != 0
   }
 
+  /// Returns true if this positional argument has wildcard expansion enabled.
+  #[cfg(feature = "wild")]
+  #[inline(always)]
+  pub(crate) const fn expands_wildcards(&self) -> bool {
+    matches!(self.r#type, OptType::Positional) && (self.flags.0 & OptFlag::NO_WILDCARDS.0) == 0
+  }
+
+  /// Returns true if the option should be offered by shell completions, see [Opt::hide_completion].
+  #[inline(always)]
+  pub const fn is_completion_visible(&self) -> bool {
+    (self.flags.0 & OptFlag::NO_COMPLETION.0) == 0
+  }
+
+  /// Returns true if this is a positional argument, or a value option that can be given positionally.
+  #[inline(always)]
+  pub(crate) const fn takes_positional(&self) -> bool {
+    match self.r#type {
+      OptType::Positional => true,
+      OptType::Value => (self.flags.0 & OptFlag::POSITIONAL.0) != 0,
+      OptType::Flag => false,
+    }
+  }
+
+  /// Returns true if an empty argument to this option is rejected, see [Opt::non_empty].
+  #[inline(always)]
+  pub(crate) const fn is_non_empty(&self) -> bool {
+    (self.flags.0 & OptFlag::NON_EMPTY.0) != 0
+  }
+
+  /// Returns true if this positional argument collects surplus positional tokens, see [Opt::collect_extra].
+  #[inline(always)]
+  pub(crate) const fn collects_extra(&self) -> bool {
+    (self.flags.0 & OptFlag::COLLECT_EXTRA.0) != 0
+  }
+
+  /// Returns true if this is the option declared with [Opt::generate_completions].
+  #[inline(always)]
+  pub(crate) const fn generates_completions(&self) -> bool {
+    (self.flags.0 & OptFlag::COMPLETIONS.0) != 0
+  }
+
+  /// Returns true if this positional argument must be provided together with the next one.
+  #[inline(always)]
+  pub(crate) const fn is_grouped_with_next(&self) -> bool {
+    (self.flags.0 & OptFlag::GROUP_NEXT.0) != 0
+  }
+
   #[inline(always)]
   pub(crate) const fn is_short_visible(&self) -> bool {
     (self.flags.0 & OptFlag::VISIBLE_SHORT.0) != 0
@@ -134,105 +329,229 @@ impl<ID> Opt<ID> {
   }
 }
 
+impl<'o, ID> OptExtras<'o, ID> {
+  /// Extras that change nothing, to chain the settings onto.
+  pub const fn new() -> Self {
+    Self { value_names: &[], metavar: None, choices: &[], constant: None, terminal: None, map_action: None, callback: None }
+  }
+
+  /// Makes a value option take a value for each of `value_names` from the following tokens,
+  /// shown with every value name in help, eg; `--copy <SRC> <DST>`.
+  /// The handler is called for each value in order, the first value may also be attached by an equals sign.
+  #[inline]
+  pub const fn value_names(mut self, value_names: &'o [&'o str]) -> Self {
+    assert!(!value_names.is_empty(), "Value names cannot be an empty slice");
+    self.value_names = value_names;
+    self
+  }
+
+  /// Sets the placeholder shown for a positional argument in usage, help, and error messages,
+  /// so that its name can remain an identifier, eg; `FILE` for `Opt::positional(Arg::Input, "input")`.
+  #[inline]
+  pub const fn metavar(mut self, metavar: &'o str) -> Self {
+    self.metavar = Some(metavar);
+    self
+  }
+
+  /// Restricts the values accepted by the option or positional argument to `choices`, listed in help.
+  /// Synonyms are passed to the handler as the canonical value, other values fail parsing with
+  /// [ParseErrorKind::InvalidChoice](crate::ParseErrorKind::InvalidChoice).
+  #[inline]
+  pub const fn choices(mut self, choices: &'o [crate::Choice<'o>]) -> Self {
+    assert!(!choices.is_empty(), "Choices cannot be an empty slice");
+    self.choices = choices;
+    self
+  }
+
+  /// Makes this flag pass `value` to the handler as its argument when present, instead of an empty string,
+  /// so a group of flags sharing an ID can fill in one destination, eg; `--json`, `--yaml` and `--toml` setting a
+  /// format through [Binding::Str](crate::Binding::Str) or the map results. Parse the value for enum destinations.
+  /// ```
+  /// # use jaarg::{Binding, Opt, OptExtras, Opts, ParseResult};
+  /// const OPTIONS: Opts<&str> = Opts::new(&[
+  ///   Opt::flag("format", &["--json"]).extras(&OptExtras::new().constant("json")),
+  ///   Opt::flag("format", &["--yaml"]).extras(&OptExtras::new().constant("yaml")),
+  /// ]);
+  /// let mut format = None;
+  /// OPTIONS.parse_bind("", &["--yaml"], &mut [("format", Binding::Str(&mut format))], |_| {}, |_, _| {});
+  /// assert_eq!(format, Some("yaml"));
+  /// ```
+  #[inline]
+  pub const fn constant(mut self, value: &'o str) -> Self {
+    self.constant = Some(value);
+    self
+  }
+}
+
+impl<ID> Default for OptExtras<'_, ID> {
+  fn default() -> Self { Self::new() }
+}
+
 #[allow(dead_code)]
-impl<ID: 'static> Opt<ID> {
+impl<'o, ID> Opt<'o, ID> {
   /// Get the first name of the option.
-  pub const fn first_name(&self) -> &str {
-    match self.names {
-      OptIdentifier::Single(name) => name,
-      OptIdentifier::Multi(names) => names.first().unwrap(),
+  pub const fn first_name(&self) -> &'o str {
+    match self.names() {
+      [name, ..] => name,
+      [] => "",
+    }
+  }
+
+  /// Get the name of a positional argument shown to the user, which is the metavar if one was set.
+  pub const fn display_name(&self) -> &'o str {
+    match self.metavar() {
+      Some(metavar) => metavar,
+      None => self.first_name(),
+    }
+  }
+
+  /// Get the placeholder shown for a positional argument declared with [OptExtras::metavar].
+  pub(crate) const fn metavar(&self) -> Option<&'o str> {
+    match self.extras {
+      Some(extras) => extras.metavar,
+      None => None,
+    }
+  }
+
+  /// Get every name of the option.
+  pub(crate) const fn names(&self) -> &[&'o str] {
+    match self.r#type {
+      OptType::Positional => core::slice::from_ref(&self.name),
+      OptType::Flag | OptType::Value => self.names,
+    }
+  }
+
+  /// Get the first value name of the option, which is [None] for flags and positional arguments.
+  pub(crate) const fn value_name(&self) -> Option<&'o str> {
+    match self.value_names() {
+      [value_name, ..] => Some(*value_name),
+      [] => None,
+    }
+  }
+
+  /// Get the value name of each value the option takes, which is empty for flags and positional arguments.
+  pub(crate) const fn value_names(&self) -> &[&'o str] {
+    match (&self.r#type, self.extras) {
+      (OptType::Value, Some(extras)) if !extras.value_names.is_empty() => extras.value_names,
+      (OptType::Value, _) => core::slice::from_ref(&self.name),
+      _ => &[],
+    }
+  }
+
+  /// Get the values accepted by the option, which is empty if it accepts anything.
+  pub(crate) const fn choices(&self) -> &'o [crate::Choice<'o>] {
+    match self.extras {
+      Some(extras) if !extras.choices.is_empty() => extras.choices,
+      // The shells are fixed, so the option declared by generate_completions doesn't need extras
+      _ if self.generates_completions() => &crate::Shell::CHOICES,
+      _ => &[],
+    }
+  }
+
+  /// Get the argument passed to the handler by a flag declared with [OptExtras::constant].
+  pub(crate) const fn constant(&self) -> Option<&'o str> {
+    match self.extras {
+      Some(extras) => extras.constant,
+      None => None,
+    }
+  }
+
+  /// Get what a flag declared with [OptExtras::print_and_exit] prints.
+  pub(crate) const fn terminal(&self) -> Option<&'o crate::Terminal<'o>> {
+    match self.extras {
+      Some(OptExtras { terminal: Some(terminal), .. }) => Some(terminal),
+      _ => None,
+    }
+  }
+
+  /// Get how the option is stored by the typed map parser, see [OptExtras::map_action].
+  pub(crate) const fn map_action(&self) -> Option<crate::MapAction> {
+    match self.extras {
+      Some(OptExtras { map_action: Some(action), .. }) => Some(*action),
+      _ => None,
+    }
+  }
+
+  /// Get the function called instead of the handler, see [OptExtras::callback].
+  pub(crate) const fn callback(&self) -> Option<fn(crate::ParseHandlerContext<ID>) -> crate::HandlerReturn<'static>> {
+    match self.extras {
+      Some(OptExtras { callback: Some(callback), .. }) => Some(callback.0),
+      _ => None,
     }
   }
 
   /// Get the first long option name, if one exists.
-  pub const fn first_long_name(&self) -> Option<&'static str> {
-    match self.names {
-      OptIdentifier::Single(name) => if name.len() >= 3 { Some(name) } else { None },
-      // Can be replaced with `find_map` once iterators are const fn
-      OptIdentifier::Multi(names) => {
-        let mut i = 0;
-        while i < names.len() {
-          if const_utf8::CharIterator::from(names[i]).count() >= 3 {
-            return Some(names[i]);
-          }
-          i += 1;
-        }
-        None
+  #[allow(clippy::indexing_slicing)]
+  pub const fn first_long_name(&self) -> Option<&'o str> {
+    let names = self.names();
+    // Can be replaced with `find_map` once iterators are const fn
+    let mut i = 0;
+    while i < names.len() {
+      if const_str::CharIterator::from(names[i]).count() >= 3 {
+        return Some(names[i]);
       }
+      i += 1;
     }
+    None
   }
 
   /// Get the first short option name, if one exists.
-  pub(crate) const fn first_short_name(&self) -> Option<&'static str> {
-    const fn predicate(name: &str) -> bool {
-      let mut chars = const_utf8::CharIterator::from(name);
-      if let Some(first) = chars.next() {
-        if let Some(c) = chars.next() {
-          if c != first && chars.next().is_none() {
-            return true
-          }
-        }
-      }
-      false
-    }
-    match self.names {
-      OptIdentifier::Single(name) => if predicate(name) { Some(name) } else { None },
-      // Can be replaced with `find_map` once iterators are const fn
-      OptIdentifier::Multi(names) => {
-        let mut i = 0;
-        while i < names.len() {
-          if predicate(names[i]) {
-            return Some(names[i]);
-          }
-          i += 1;
-        }
-        None
+  #[allow(clippy::indexing_slicing)]
+  pub(crate) const fn first_short_name(&self) -> Option<&'o str> {
+    let names = self.names();
+    // Can be replaced with `find_map` once iterators are const fn
+    let mut i = 0;
+    while i < names.len() {
+      if short_name_char(names[i]).is_some() {
+        return Some(names[i]);
       }
+      i += 1;
     }
+    None
   }
 
   /// Get the first applicable short option's flag character, if one exists.
+  #[allow(clippy::indexing_slicing)]
   pub(crate) const fn first_short_name_char(&self) -> Option<char> {
-    const fn predicate(name: &str) -> Option<char> {
-      let mut chars = const_utf8::CharIterator::from(name);
-      if let Some(first) = chars.next() {
-        if let Some(c) = chars.next() {
-          if c != first && chars.next().is_none() {
-            return Some(c)
-          }
-        }
-      }
-      None
-    }
-    match self.names {
-      OptIdentifier::Single(name) => predicate(name),
-      // Can be replaced with `find_map` once iterators are const fn.
-      OptIdentifier::Multi(names) => {
-        let mut i = 0;
-        while i < names.len() {
-          if let Some(c) = predicate(names[i]) {
-            return Some(c);
-          }
-          i += 1;
-        }
-        None
+    let names = self.names();
+    // Can be replaced with `find_map` once iterators are const fn
+    let mut i = 0;
+    while i < names.len() {
+      if let Some(c) = short_name_char(names[i]) {
+        return Some(c);
       }
+      i += 1;
     }
+    None
   }
 
   /// Search for a matching name in the option, offset allows to skip the first `n = offset` characters in the comparison.
-  pub(crate) fn match_name(&self, string: &str, offset: usize) -> Option<&'static str> {
-    let rhs = &string[offset..];
-    if rhs.is_empty() {
-      return None;
-    }
-    match self.names {
-      OptIdentifier::Single(name) =>
-        if &name[offset..] == rhs { Some(name) } else { None },
-      OptIdentifier::Multi(names) =>
-        names.iter().find(|name| &name[offset..] == rhs).map(|v| &**v),
+  pub(crate) fn match_name(&self, string: &str, offset: usize) -> Option<&'o str> {
+    let rhs = skip_chars(string, offset).filter(|rhs| !rhs.is_empty())?;
+    self.names().iter().find(|name| skip_chars(name, offset) == Some(rhs)).copied()
+  }
+}
+
+/// The flag character of a short option name, which is a flag character followed by a different character.
+const fn short_name_char(name: &str) -> Option<char> {
+  let mut chars = const_str::CharIterator::from(name);
+  if let Some(first) = chars.next() {
+    if let Some(c) = chars.next() {
+      if c != first && chars.next().is_none() {
+        return Some(c)
+      }
     }
   }
+  None
+}
+
+/// The rest of a string after skipping `count` characters, or [None] if it's shorter than that.
+fn skip_chars(string: &str, count: usize) -> Option<&str> {
+  let mut chars = string.chars();
+  for _ in 0..count {
+    chars.next()?;
+  }
+  Some(chars.as_str())
 }
 
 impl core::ops::BitOr for OptFlag {
@@ -247,61 +566,111 @@ mod tests {
   #[test]
   #[should_panic(expected = "Option names cannot be an empty slice")]
   fn test_new_empty_names_disallowed() {
-    Opt::new((), OptIdentifier::Multi(&[]), None, OptType::Positional);
+    Opt::new((), &[], "", OptType::Flag);
   }
 
   #[test]
   fn test_public_initialisers() {
     assert_eq!(Opt::positional((), "name"), Opt { id: (),
-      names: OptIdentifier::Single("name"), value_name: None, help_string: None,
-      r#type: OptType::Positional, flags: OptFlag::DEFAULT,
+      names: &[], name: "name", help_string: None,
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::DEFAULT, extras: None,
     });
     assert_eq!(Opt::help_flag((), &["name"]), Opt { id: (),
-      names: OptIdentifier::Multi(&["name"]), value_name: None, help_string: None,
-      r#type: OptType::Flag, flags: OptFlag::DEFAULT | OptFlag::HELP,
+      names: &["name"], name: "", help_string: None,
+      r#type: OptType::Flag, value_kind: ValueKind::String, flags: OptFlag::DEFAULT | OptFlag::HELP, extras: None,
     });
     assert_eq!(Opt::flag((), &["name"]), Opt { id: (),
-      names: OptIdentifier::Multi(&["name"]), value_name: None, help_string: None,
-      r#type: OptType::Flag, flags: OptFlag::DEFAULT,
+      names: &["name"], name: "", help_string: None,
+      r#type: OptType::Flag, value_kind: ValueKind::String, flags: OptFlag::DEFAULT, extras: None,
     });
     assert_eq!(Opt::value((), &["name"], "value"), Opt { id: (),
-      names: OptIdentifier::Multi(&["name"]), value_name: Some("value"), help_string: None,
-      r#type: OptType::Value, flags: OptFlag::DEFAULT,
+      names: &["name"], name: "value", help_string: None,
+      r#type: OptType::Value, value_kind: ValueKind::String, flags: OptFlag::DEFAULT, extras: None,
     });
   }
 
   #[test]
   fn test_valid_with_chains() {
     assert_eq!(Opt::positional((), "").required(), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: None,
-      r#type: OptType::Positional, flags: OptFlag::DEFAULT | OptFlag::REQUIRED,
+      names: &[], name: "", help_string: None,
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::DEFAULT | OptFlag::REQUIRED, extras: None,
     });
     assert_eq!(Opt::positional((), "").required().help_text("help string"), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: Some("help string"),
-      r#type: OptType::Positional, flags: OptFlag::DEFAULT | OptFlag::REQUIRED,
+      names: &[], name: "", help_string: Some("help string"),
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::DEFAULT | OptFlag::REQUIRED, extras: None,
     });
     assert_eq!(Opt::positional((), "").help_text("help string"), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: Some("help string"),
-      r#type: OptType::Positional, flags: OptFlag::DEFAULT,
+      names: &[], name: "", help_string: Some("help string"),
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::DEFAULT, extras: None,
     });
     assert_eq!(Opt::positional((), "").hide_usage(OptHide::Short), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: None,
-      r#type: OptType::Positional, flags: OptFlag::VISIBLE_FULL,
+      names: &[], name: "", help_string: None,
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::VISIBLE_FULL, extras: None,
     });
     assert_eq!(Opt::positional((), "").hide_usage(OptHide::Full), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: None,
-      r#type: OptType::Positional, flags: OptFlag::VISIBLE_SHORT,
+      names: &[], name: "", help_string: None,
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::VISIBLE_SHORT, extras: None,
     });
     assert_eq!(Opt::positional((), "").hide_usage(OptHide::All), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: None,
-      r#type: OptType::Positional, flags: OptFlag::NONE,
+      names: &[], name: "", help_string: None,
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::NONE, extras: None,
     });
     assert_eq!(Opt::positional((), "").required().hide_usage(OptHide::All), Opt { id: (),
-      names: OptIdentifier::Single(""), value_name: None, help_string: None,
-      r#type: OptType::Positional, flags: OptFlag::REQUIRED,
+      names: &[], name: "", help_string: None,
+      r#type: OptType::Positional, value_kind: ValueKind::String, flags: OptFlag::REQUIRED, extras: None,
     });
   }
 
+  #[test]
+  fn test_hide_completion() {
+    // Hidden from completions and help independently
+    let option = Opt::flag((), &["--debug"]).hide_completion();
+    assert!(!option.is_completion_visible() && option.is_short_visible() && option.is_full_visible());
+    let option = Opt::flag((), &["--debug"]).hide_usage(OptHide::All);
+    assert!(option.is_completion_visible() && !option.is_short_visible() && !option.is_full_visible());
+  }
+
+  #[test]
+  fn test_constant() {
+    assert_eq!(Opt::flag((), &["--json"]).extras(&OptExtras::new().constant("json")).constant(), Some("json"));
+    assert_eq!(Opt::flag((), &["--json"]).constant(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "Only flags can carry a constant")]
+  fn test_value_constant_disallowed() {
+    Opt::value((), &["--format"], "format").extras(&OptExtras::new().constant("json"));
+  }
+
+  #[test]
+  fn test_extras() {
+    const VALUES: Opt<()> = Opt::value((), &["--copy"], "src").extras(&OptExtras::new().value_names(&["SRC", "DST"]));
+    assert_eq!((VALUES.value_name(), VALUES.value_names()), (Some("SRC"), &["SRC", "DST"][..]));
+    const METAVAR: Opt<()> = Opt::positional((), "input").extras(&OptExtras::new().metavar("FILE"));
+    assert_eq!((METAVAR.first_name(), METAVAR.display_name(), METAVAR.value_name()), ("input", "FILE", None));
+    // Tables pay for a single reference to the extras of each option
+    assert!(size_of::<Opt<()>>() <= 4 * size_of::<&str>());
+  }
+
+  #[test]
+  #[should_panic(expected = "Only positional arguments take a metavar, value options take a value name")]
+  fn test_value_metavar_disallowed() {
+    Opt::value((), &["--in"], "file").extras(&OptExtras::new().metavar("FILE"));
+  }
+
+  #[test]
+  fn test_value_kind() {
+    assert_eq!(Opt::value((), &["-n"], "").value_kind(ValueKind::Integer).value_kind, ValueKind::Integer);
+    assert_eq!(Opt::positional((), "").value_kind(ValueKind::Float).value_kind, ValueKind::Float);
+    assert_eq!(Opt::positional((), "").value_kind, ValueKind::String);
+  }
+
+  #[test]
+  #[should_panic(expected = "Flags don't take a value")]
+  fn test_flag_value_kind_disallowed() {
+    Opt::flag((), &["-f"]).value_kind(ValueKind::Integer);
+  }
+
   #[test]
   #[should_panic(expected = "Help flag cannot be made required")]
   fn test_required_help_disallowed() {
@@ -376,6 +745,15 @@ mod tests {
     assert_eq!(Opt::positional((), "-s").match_name("-s", 1), Some("-s"));
 
     assert_eq!(Opt::flag((), &["-x", "-s"]).match_name("-s", 2), None);
+    assert_eq!(Opt::flag((), &["éx", "-s"]).match_name("-x", 1), Some("éx"));
+    assert_eq!(Opt::flag((), &["", "-s"]).match_name("é", 1), None);
+    assert_eq!(Opt::flag((), &["", "-s"]).match_name("éé", 1), None);
     assert_eq!(Opt::positional((), "-x").match_name("-s", 2), None);
   }
+
+  #[test]
+  #[should_panic(expected = "Only value options can reject empty arguments")]
+  fn test_non_empty_positional_disallowed() {
+    Opt::positional((), "file").non_empty();
+  }
 }
@@ -3,63 +3,508 @@
  * SPDX-License-Identifier: MIT OR Apache-2.0
  */
 
-use crate::{ordered_bitset, Opt};
+use crate::{const_str, ordered_bitset, ErrorUsage, HelpLayout, HelpSections, Opt, ValueStyle};
 use crate::option::OptType;
 
-/// Static structure that contains instructions for parsing command-line arguments.
+/// Structure that contains instructions for parsing command-line arguments,
+/// typically a `const` table but may also borrow option names & help text for a shorter lifetime `'o`.
+///
+/// `R` is the number of 32-bit words the parser reserves for tracking required non-positional options,
+/// giving a capacity of `32 * R` ([MAX_REQUIRED_OPTIONS] by default).
+/// With `features = ["alloc"]` the tracking is heap-backed instead, `R` is ignored and there is no limit.
 #[derive(Debug, PartialEq)]
-pub struct Opts<ID: 'static> {
+pub struct Opts<'o, ID, const R: usize = 4> {
   /// List of options
-  pub(crate) options: &'static[Opt<ID>],
+  pub(crate) options: &'o [Opt<'o, ID>],
   /// String containing single characters that match option prefixes
-  pub(crate) flag_chars: &'static str,
+  pub(crate) flag_chars: &'o str,
+  /// Bitmask of the ASCII characters in `flag_chars`, for quickly checking token prefixes
+  pub(crate) flag_ascii: u128,
   /// A description of what the program does
-  pub(crate) description: Option<&'static str>,
+  pub(crate) description: Option<&'o str>,
+  /// Where to report bugs, shown at the end of the full help
+  pub(crate) bug_report: Option<&'o str>,
+  /// What the standard error writer prints after an error
+  pub(crate) error_usage: ErrorUsage,
+  /// How value names are rendered by every help writer, or [None] for each writer's own convention
+  pub(crate) value_style: Option<ValueStyle>,
+  /// Width of the longest option in the full help that help text is aligned after, computed once
+  pub(crate) help_align: usize,
+  /// Column layout of the full help
+  pub(crate) help_layout: HelpLayout,
+  /// Section headers and order of the full help
+  pub(crate) help_sections: HelpSections<'o>,
+  /// Optional hooks, unset by default
+  pub(crate) sinks: Sinks<'o, ID>,
+  /// Optional sorted index of option names for faster lookup
+  pub(crate) index: Option<&'o [crate::name_index::IndexEntry]>,
+  /// Whether [Opts::parse_easy] turns handler panics into errors
+  #[cfg(feature = "std")]
+  pub(crate) contain_panics: bool,
 }
 
-pub(crate) type RequiredParamsBitSet = ordered_bitset::OrderedBitSet<u32, 4>;
+/// Hooks that an options table can hand output and events to, kept together so they can grow
+/// without adding to the fields of [Opts].
+#[derive(Debug, PartialEq)]
+pub(crate) struct Sinks<'o, ID> {
+  /// Translations of the help text
+  pub(crate) catalog: Option<crate::catalog::CatalogRef<'o, ID>>,
+  /// Sink for the output of terminal flags
+  pub(crate) output: Option<crate::terminal::OutputSink>,
+  /// Sink that help flags write the full help to in place of the handler
+  pub(crate) help: Option<crate::terminal::OutputSink>,
+  /// Receives structured events alongside the handler
+  pub(crate) observer: Option<crate::observer::ObserverSink<ID>>,
+  /// Sink for parsing decisions, for debugging
+  #[cfg(feature = "trace")]
+  pub(crate) trace: Option<crate::trace::TraceSink>,
+}
+
+impl<ID> Sinks<'_, ID> {
+  pub(crate) const NONE: Self = Self {
+    catalog: None,
+    output: None,
+    help: None,
+    observer: None,
+    #[cfg(feature = "trace")]
+    trace: None,
+  };
+}
+
+#[cfg(not(feature = "alloc"))]
+pub(crate) type RequiredParamsBitSet<const R: usize> = ordered_bitset::OrderedBitSet<u32, R>;
+#[cfg(feature = "alloc")]
+pub(crate) type RequiredParamsBitSet<const R: usize> = ordered_bitset::HeapBitSet;
+
+/// The maximum amount of allowed required non-positional options with the default capacity.
+pub const MAX_REQUIRED_OPTIONS: usize = Opts::<(), 4>::MAX_REQUIRED_OPTIONS;
+
+/// A consistency problem found in an options table by [Opts::assert_valid], or listed by `Opts::validate`.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OptsIssue<'o> {
+  /// The option name is only a flag character.
+  BareFlagChar(&'o str),
+  /// The option name doesn't start with any of the configured flag characters.
+  MissingFlagChar(&'o str),
+  /// The option name matches the same arguments as a preceding name, differing at most by its flag character.
+  DuplicateName(&'o str),
+  /// The flag or value option at this index of the table has no names.
+  Unnamed(usize),
+  /// A required positional argument that follows an optional one.
+  RequiredAfterOptional(&'o str),
+  /// A required positional argument that's part of a group.
+  GroupedRequired(&'o str),
+  /// The last positional argument of a group, which isn't followed by another positional.
+  UnfinishedGroup(&'o str),
+  /// A positional argument collecting extra positionals that's followed by another positional.
+  ExtraNotLast(&'o str),
+  /// A help flag that follows another help flag.
+  MultipleHelp(&'o str),
+}
+
+impl OptsIssue<'_> {
+  /// The rule broken by the table, as [Opts::assert_valid] panics with.
+  pub const fn rule(&self) -> &'static str {
+    match self {
+      Self::BareFlagChar(_) => "Option names must have more than a flag character",
+      Self::MissingFlagChar(_) => "Option names must start with one of the flag characters",
+      Self::DuplicateName(_) => "Option names must be unique",
+      Self::Unnamed(_) => "Options must have at least one name",
+      Self::RequiredAfterOptional(_) => "Required positional arguments must come before optional ones",
+      Self::GroupedRequired(_) => "Only optional positional arguments can be grouped",
+      Self::UnfinishedGroup(_) => "Grouped positional arguments must be followed by another positional argument",
+      Self::ExtraNotLast(_) => "Extra positionals must be collected by the last positional argument",
+      Self::MultipleHelp(_) => "Only one help option is allowed",
+    }
+  }
+}
 
-/// The maximum amount of allowed required non-positional options.
-pub const MAX_REQUIRED_OPTIONS: usize = RequiredParamsBitSet::CAPACITY;
+impl core::fmt::Display for OptsIssue<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::BareFlagChar(n) => write!(f, "Option name '{n}' is only a flag character"),
+      Self::MissingFlagChar(n) => write!(f, "Option name '{n}' doesn't start with a flag character"),
+      Self::DuplicateName(n) => write!(f, "Option name '{n}' matches the same arguments as a preceding name"),
+      Self::Unnamed(i) => write!(f, "Option {i} has no names"),
+      Self::RequiredAfterOptional(n) => write!(f, "Required positional argument '{n}' follows an optional one"),
+      Self::GroupedRequired(n) => write!(f, "Required positional argument '{n}' is grouped"),
+      Self::UnfinishedGroup(n) => write!(f, "Grouped positional argument '{n}' isn't followed by another positional"),
+      Self::ExtraNotLast(n) => write!(f, "Positional argument '{n}' collects extra positionals but isn't the last"),
+      Self::MultipleHelp(n) => write!(f, "Help flag '{n}' follows another help flag"),
+    }
+  }
+}
 
-impl<ID: 'static> Opts<ID> {
+impl<'o, ID> Opts<'o, ID> {
   /// Build argument parser options with the default flag character of '-'.
-  pub const fn new(options: &'static[Opt<ID>]) -> Self {
-    // Validate passed options
+  #[inline]
+  pub const fn new(options: &'o [Opt<'o, ID>]) -> Self {
+    Self::new_with_capacity(options)
+  }
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// The maximum amount of allowed required non-positional options.
+  pub const MAX_REQUIRED_OPTIONS: usize = RequiredParamsBitSet::<R>::CAPACITY;
+
+  /// Build argument parser options with the default flag character of '-',
+  /// and a required option capacity set by the `R` parameter.
+  #[allow(clippy::indexing_slicing)]
+  pub const fn new_with_capacity(options: &'o [Opt<'o, ID>]) -> Self {
+    // Validate passed options, there's no fixed limit on required options when alloc is available
+    #[cfg(not(feature = "alloc"))]
+    {
+      let mut opt_idx = 0;
+      let mut num_required_parameters = 0;
+      let mut num_named_positionals = 0;
+      while opt_idx < options.len() {
+        if matches!(options[opt_idx].r#type, OptType::Flag | OptType::Value) && options[opt_idx].is_required() {
+          num_required_parameters += 1;
+        }
+        // Options given by name are tracked to skip their positional turn
+        if matches!(options[opt_idx].r#type, OptType::Value) && options[opt_idx].takes_positional() {
+          num_named_positionals += 1;
+        }
+        opt_idx += 1;
+      }
+      assert!(num_required_parameters <= Self::MAX_REQUIRED_OPTIONS,
+        "Number of non-positional required option entries exceeds the capacity of the options table");
+      assert!(num_named_positionals <= Self::MAX_REQUIRED_OPTIONS,
+        "Number of options that can be given positionally exceeds the capacity of the options table");
+    }
+
+    // Ensure no two option names are the same, as only the first declaration could ever be matched
     let mut opt_idx = 0;
-    let mut num_required_parameters = 0;
     while opt_idx < options.len() {
-      if matches!(options[opt_idx].r#type, OptType::Flag | OptType::Value) && options[opt_idx].is_required() {
-        num_required_parameters += 1;
+      if matches!(options[opt_idx].r#type, OptType::Flag | OptType::Value) {
+        let names = options[opt_idx].names();
+        let mut name_idx = 0;
+        while name_idx < names.len() {
+          assert!(names[name_idx].is_empty() || !Self::name_declared_after(options, opt_idx, name_idx),
+            "Option names must be unique");
+          name_idx += 1;
+        }
       }
       opt_idx += 1;
     }
-    assert!(num_required_parameters <= RequiredParamsBitSet::CAPACITY,
-      "More than 128 non-positional required option entries is not supported at this time");
 
     Self {
       options,
       flag_chars: "-",
+      flag_ascii: 1 << b'-',
       description: None,
+      bug_report: None,
+      error_usage: ErrorUsage::Short,
+      value_style: None,
+      help_align: Self::help_align_width(options, None),
+      help_layout: HelpLayout::DEFAULT,
+      help_sections: HelpSections::DEFAULT,
+      sinks: Sinks::NONE,
+      index: None,
+      #[cfg(feature = "std")]
+      contain_panics: false,
+    }
+  }
+
+  /// Determine the width of the longest option usage line, that help text is aligned after.
+  #[allow(clippy::indexing_slicing)]
+  const fn help_align_width(options: &[Opt<ID>], style: Option<ValueStyle>) -> usize {
+    let mut longest = 0;
+    let mut i = 0;
+    while i < options.len() {
+      let option = &options[i];
+      let length = if let OptType::Positional = option.r#type {
+        // Positionals are shown by their metavar alone, bare unless a value style is set
+        let brackets = match style { Some(style) => style.brackets(!option.is_required()).is_some(), None => false };
+        const_str::CharIterator::from(option.display_name()).count() + if brackets { 2 } else { 0 }
+      } else {
+        // Names are separated by " | ", and value options are followed by " <value_name>" for each value
+        let names = option.names();
+        let mut length = (names.len() - 1) * 3;
+        let mut j = 0;
+        while j < names.len() {
+          length += const_str::CharIterator::from(names[j]).count();
+          j += 1;
+        }
+        let value_names = option.value_names();
+        let brackets = match style { Some(style) => style.brackets(false).is_some(), None => true };
+        j = 0;
+        while j < value_names.len() {
+          length += 1 + const_str::CharIterator::from(value_names[j]).count() + if brackets { 2 } else { 0 };
+          j += 1;
+        }
+        length
+      };
+      if length > longest {
+        longest = length;
+      }
+      i += 1;
+    }
+    longest
+  }
+
+  /// Checks if the name at `name_idx` in option `opt_idx` is declared again by any name following it.
+  #[allow(clippy::indexing_slicing)]
+  const fn name_declared_after(options: &[Opt<ID>], opt_idx: usize, name_idx: usize) -> bool {
+    let name = options[opt_idx].names()[name_idx];
+    let (mut i, mut j) = (opt_idx, name_idx + 1);
+    while i < options.len() {
+      if matches!(options[i].r#type, OptType::Flag | OptType::Value) {
+        let names = options[i].names();
+        while j < names.len() {
+          if const_str::eq(name, names[j]) {
+            return true;
+          }
+          j += 1;
+        }
+      }
+      (i, j) = (i + 1, 0);
+    }
+    false
+  }
+
+  /// Checks if a name preceding the one at `name_idx` in option `opt_idx` matches the same arguments,
+  /// which are compared after their flag character.
+  #[allow(clippy::indexing_slicing)]
+  const fn name_matched_before(options: &[Opt<ID>], opt_idx: usize, name_idx: usize) -> bool {
+    let name = options[opt_idx].names()[name_idx];
+    let (mut i, mut j) = (0, 0);
+    while i < opt_idx || (i == opt_idx && j < name_idx) {
+      let names = options[i].names();
+      if matches!(options[i].r#type, OptType::Flag | OptType::Value) && j < names.len() {
+        if !names[j].is_empty() && Self::eq_after_flag_char(name, names[j]) {
+          return true;
+        }
+        j += 1;
+      } else {
+        (i, j) = (i + 1, 0);
+      }
+    }
+    false
+  }
+
+  /// Compares two option names skipping their first character.
+  const fn eq_after_flag_char(lhs: &str, rhs: &str) -> bool {
+    let (mut lhs, mut rhs) = (const_str::CharIterator::from(lhs), const_str::CharIterator::from(rhs));
+    let _ = (lhs.next(), rhs.next());
+    loop {
+      match (lhs.next(), rhs.next()) {
+        (Some(l), Some(r)) if l == r => {}
+        (None, None) => return true,
+        _ => return false,
+      }
+    }
+  }
+
+  /// Runs every consistency check on the table, panicking with a description of the first problem found.
+  /// Use [assert_opts](crate::assert_opts) to run it at compile time, or `Opts::validate` with the `alloc`
+  /// feature to list every problem instead.
+  ///
+  /// In addition to the checks made while building the table, this checks that:
+  /// - every flag and value option has a name longer than its flag character,
+  /// - every option name starts with one of the flag characters,
+  /// - no two option names differ only by their flag character, as only the first could ever be matched,
+  /// - required positional arguments aren't declared after optional ones, which would always consume them first,
+  /// - grouped positional arguments are optional, and the last in each group is followed by another positional,
+  /// - a positional argument collecting extra positionals is the last one,
+  /// - there's at most one help option.
+  #[allow(clippy::panic)]
+  pub const fn assert_valid(&self) {
+    if let Some(issue) = self.issue(0) {
+      panic!("{}", issue.rule());
+    }
+  }
+
+  /// Finds the problem at position `skip` in the order the table is checked, shared by [Opts::assert_valid]
+  /// and `Opts::validate` so the two always agree. The table is walked again for each problem found.
+  #[allow(clippy::indexing_slicing)]
+  pub(crate) const fn issue(&self, mut skip: usize) -> Option<OptsIssue<'o>> {
+    /// Counts down the problems to skip, evaluating to the first one that isn't skipped.
+    macro_rules! report {
+      ($issue:expr) => {
+        if skip == 0 {
+          return Some($issue);
+        }
+        skip -= 1;
+      };
+    }
+
+    let options = self.options;
+    let mut optional_positional = false;
+    let mut group: Option<&'o str> = None;
+    let mut extra: Option<&'o str> = None;
+    let mut help_seen = false;
+    let mut opt_idx = 0;
+    while opt_idx < options.len() {
+      let option = &options[opt_idx];
+      if option.takes_positional() {
+        if let Some(name) = extra {
+          report!(OptsIssue::ExtraNotLast(name));
+        }
+        extra = if option.collects_extra() { Some(option.first_name()) } else { None };
+      }
+      match option.r#type {
+        OptType::Positional => {
+          if option.is_required() && optional_positional {
+            report!(OptsIssue::RequiredAfterOptional(option.first_name()));
+          }
+          optional_positional |= !option.is_required();
+          if option.is_required() && (group.is_some() || option.is_grouped_with_next()) {
+            report!(OptsIssue::GroupedRequired(option.first_name()));
+          }
+          group = if option.is_grouped_with_next() { Some(option.first_name()) } else { None };
+        }
+        OptType::Flag | OptType::Value => {
+          let names = option.names();
+          let mut named = false;
+          let mut name_idx = 0;
+          while name_idx < names.len() {
+            let name = names[name_idx];
+            if !name.is_empty() {
+              if const_str::CharIterator::from(name).count() <= 1 {
+                report!(OptsIssue::BareFlagChar(name));
+              } else if !Self::starts_with_flag_char(name, self.flag_chars) {
+                report!(OptsIssue::MissingFlagChar(name));
+              } else if Self::name_matched_before(options, opt_idx, name_idx) {
+                report!(OptsIssue::DuplicateName(name));
+              }
+              named = true;
+            }
+            name_idx += 1;
+          }
+          if !named {
+            report!(OptsIssue::Unnamed(opt_idx));
+          }
+        }
+      }
+      if option.is_help() {
+        if help_seen {
+          report!(OptsIssue::MultipleHelp(option.first_name()));
+        }
+        help_seen = true;
+      }
+      opt_idx += 1;
+    }
+    match (group, skip) {
+      (Some(name), 0) => Some(OptsIssue::UnfinishedGroup(name)),
+      _ => None,
     }
   }
 
   /// Sets the recognised flag/option characters.
   #[inline]
-  pub const fn with_flag_chars(mut self, flag_chars: &'static str) -> Self {
+  #[allow(clippy::indexing_slicing)]
+  pub const fn with_flag_chars(mut self, flag_chars: &'o str) -> Self {
+    // Ensure every option name is prefixed by one of the new flag characters
+    let mut opt_idx = 0;
+    while opt_idx < self.options.len() {
+      if matches!(self.options[opt_idx].r#type, OptType::Flag | OptType::Value) {
+        let names = self.options[opt_idx].names();
+        let mut name_idx = 0;
+        while name_idx < names.len() {
+          assert!(names[name_idx].is_empty() || Self::starts_with_flag_char(names[name_idx], flag_chars),
+            "Option names must start with one of the flag characters");
+          name_idx += 1;
+        }
+      }
+      opt_idx += 1;
+    }
+
     self.flag_chars = flag_chars;
+    self.flag_ascii = 0;
+    let bytes = flag_chars.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+      if bytes[i].is_ascii() {
+        self.flag_ascii |= 1 << bytes[i];
+      }
+      i += 1;
+    }
     self
   }
 
+  /// Checks if a token starts with one of the flag characters,
+  /// using the ASCII bitmask and only falling back to searching `flag_chars` for non-ASCII prefixes.
+  #[inline]
+  pub(crate) fn starts_with_flag(&self, token: &str) -> bool {
+    match token.as_bytes().first() {
+      Some(&b) if b.is_ascii() => self.flag_ascii & (1 << b) != 0,
+      Some(_) => self.flag_chars.chars().any(|c| !c.is_ascii() && token.starts_with(c)),
+      None => false,
+    }
+  }
+
+  /// Checks if the first character of `name` is contained in `flag_chars`.
+  const fn starts_with_flag_char(name: &str, flag_chars: &str) -> bool {
+    let Some(first) = const_str::CharIterator::from(name).next() else {
+      return false;
+    };
+    let mut chars = const_str::CharIterator::from(flag_chars);
+    while let Some(c) = chars.next() {
+      if c == first {
+        return true;
+      }
+    }
+    false
+  }
+
   /// Sets the description of the program, available to help writers.
   #[inline]
-  pub const fn with_description(mut self, description: &'static str) -> Self {
+  pub const fn with_description(mut self, description: &'o str) -> Self {
     self.description = Some(description);
     self
   }
 
+  /// Sets where to report bugs, eg; an issue tracker URL or email address,
+  /// shown as a trailing `Report bugs to:` line by the full help writers.
+  #[inline]
+  pub const fn with_bug_report(mut self, bug_report: &'o str) -> Self {
+    self.bug_report = Some(bug_report);
+    self
+  }
+
+  /// Sets what [StandardErrorUsageWriter](crate::StandardErrorUsageWriter) prints after an error message.
+  #[inline]
+  pub const fn with_error_usage(mut self, error_usage: ErrorUsage) -> Self {
+    self.error_usage = error_usage;
+    self
+  }
+
+  /// Sets how value names and positional names are rendered by every help writer, overriding each writer's own
+  /// convention, eg; [ValueStyle::GNU] for `--name NAME` or [ValueStyle::CLAP] for `--name <NAME>`.
+  #[inline]
+  pub const fn with_value_style(mut self, value_style: ValueStyle) -> Self {
+    self.value_style = Some(value_style);
+    self.help_align = Self::help_align_width(self.options, self.value_style);
+    self
+  }
+
+  /// Sets the column layout of the full help written by [StandardFullHelpWriter](crate::StandardFullHelpWriter).
+  #[inline]
+  pub const fn with_help_layout(mut self, help_layout: HelpLayout) -> Self {
+    self.help_layout = help_layout;
+    self
+  }
+
+  /// Sets the section headers and order of the full help, eg; to write options before positional arguments.
+  /// ```
+  /// # use jaarg::{HelpSection, HelpSections, Opt, Opts};
+  /// const OPTIONS: Opts<()> = Opts::new(&[Opt::positional((), "file")]).with_help_sections(HelpSections {
+  ///   positionals: Some("Inputs:"),
+  ///   order: [HelpSection::Options, HelpSection::Positionals],
+  ///   ..HelpSections::DEFAULT
+  /// });
+  /// ```
+  #[inline]
+  pub const fn with_help_sections(mut self, help_sections: HelpSections<'o>) -> Self {
+    self.help_sections = help_sections;
+    self
+  }
+
   /// Gets the first available help option if one exists.
-  pub const fn help_option(&self) -> Option<&'static Opt<ID>> {
+  #[allow(clippy::indexing_slicing)]
+  pub const fn help_option(&self) -> Option<&'o Opt<'o, ID>> {
     let mut i = 0;
     while i < self.options.len() {
       if self.options[i].is_help() {
@@ -70,22 +515,58 @@ impl<ID: 'static> Opts<ID> {
     None
   }
 
+  /// Finds the first option or positional argument that has a name exactly matching `name`.
+  pub fn find_by_name(&self, name: &str) -> Option<&'o Opt<'o, ID>> {
+    self.iter().find(|o| o.match_name(name, 0).is_some())
+  }
+
+  /// Finds the first option or positional argument with a matching ID.
+  pub fn find_by_id(&self, id: &ID) -> Option<&'o Opt<'o, ID>> where ID: PartialEq {
+    self.iter().find(|o| o.id == *id)
+  }
+
   /// Gets an iterator over the parser's options.
   #[inline]
-  pub fn iter(&self) -> core::slice::Iter<'static, Opt<ID>> {
+  pub fn iter(&self) -> core::slice::Iter<'o, Opt<'o, ID>> {
     self.options.iter()
   }
+
+  /// Gets an iterator over the parser's positional arguments.
+  pub fn positionals(&self) -> impl Iterator<Item = &'o Opt<'o, ID>> {
+    self.iter().filter(|o| matches!(o.r#type, OptType::Positional))
+  }
+
+  /// Gets an iterator over the parser's flag-type options.
+  pub fn flags(&self) -> impl Iterator<Item = &'o Opt<'o, ID>> {
+    self.iter().filter(|o| matches!(o.r#type, OptType::Flag))
+  }
+
+  /// Gets an iterator over the parser's options that take a value.
+  pub fn value_options(&self) -> impl Iterator<Item = &'o Opt<'o, ID>> {
+    self.iter().filter(|o| matches!(o.r#type, OptType::Value))
+  }
+
+  /// Gets an iterator over the parser's required positional arguments and options.
+  pub fn required(&self) -> impl Iterator<Item = &'o Opt<'o, ID>> {
+    self.iter().filter(|o| o.is_required())
+  }
 }
 
 
 #[cfg(test)]
 mod tests {
+  extern crate alloc;
+  use alloc::vec::Vec;
   use super::*;
 
   #[test]
   #[allow(unused)]
   fn test_required_opt_limit() {
+    #[cfg(not(feature = "alloc"))]
     const NUM_OPTS: usize = MAX_REQUIRED_OPTIONS + 2;
+    #[cfg(feature = "alloc")]
+    const NUM_OPTS: usize = 256;
+    // Must be const so the table is checked at compile time
     const OPT_LIST: [Opt<()>; NUM_OPTS] = {
       const REQUIRED: Opt<()> = Opt::flag((), &[""]).required();
       let mut array: [Opt<()>; NUM_OPTS] = [REQUIRED; NUM_OPTS];
@@ -96,12 +577,164 @@ mod tests {
     const OPTIONS: Opts<()> = Opts::new(&OPT_LIST);
   }
 
+  #[test]
+  #[allow(unused)]
+  fn test_required_opt_custom_capacity() {
+    #[cfg(not(feature = "alloc"))]
+    assert_eq!(Opts::<(), 1>::MAX_REQUIRED_OPTIONS, 32);
+    #[cfg(not(feature = "alloc"))]
+    assert_eq!(Opts::<(), 8>::MAX_REQUIRED_OPTIONS, 256);
+    const OPT_LIST: [Opt<()>; 32] = [const { Opt::flag((), &[""]).required() }; 32];
+    const OPTIONS: Opts<(), 1> = Opts::new_with_capacity(&OPT_LIST);
+  }
+
+  #[test]
+  #[cfg(not(feature = "alloc"))]
+  #[should_panic(expected = "Number of non-positional required option entries exceeds the capacity")]
+  fn test_required_opt_capacity_exceeded() {
+    static OPT_LIST: [Opt<()>; 1] = [Opt::flag((), &["-r"]).required()];
+    Opts::<(), 0>::new_with_capacity(&OPT_LIST);
+  }
+
+  #[test]
+  #[should_panic(expected = "Option names must be unique")]
+  fn test_duplicate_names_disallowed() {
+    static OPT_LIST: [Opt<()>; 3] = [
+      Opt::flag((), &["-a", "--alpha"]),
+      Opt::value((), &["-b"], ""),
+      Opt::flag((), &["--bravo", "--alpha"]),
+    ];
+    Opts::new(&OPT_LIST);
+  }
+
+  #[test]
+  #[should_panic(expected = "Option names must be unique")]
+  fn test_duplicate_names_in_option_disallowed() {
+    static OPT_LIST: [Opt<()>; 1] = [Opt::flag((), &["-a", "-a"])];
+    Opts::new(&OPT_LIST);
+  }
+
+  #[test]
+  #[allow(unused)]
+  fn test_positional_names_may_overlap() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::positional((), "-a"),
+      Opt::flag((), &["-a"]),
+      Opt::positional((), "-a"),
+    ]);
+  }
+
+  #[test]
+  #[allow(unused)]
+  fn test_flag_chars_match_names() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::positional((), "file"),
+      Opt::flag((), &["-a", "/alpha"]),
+      Opt::value((), &["+b"], "value"),
+    ]).with_flag_chars("-/+");
+  }
+
+  #[test]
+  #[should_panic(expected = "Option names must start with one of the flag characters")]
+  fn test_flag_chars_mismatch_disallowed() {
+    static OPT_LIST: [Opt<()>; 2] = [
+      Opt::flag((), &["/a"]),
+      Opt::value((), &["/b", "--bravo"], "value"),
+    ];
+    let _ = Opts::new(&OPT_LIST).with_flag_chars("/");
+  }
+
+  #[test]
+  fn test_validate() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::help_flag((), &["-h", "/help"]),
+      Opt::positional((), "file").required(),
+      Opt::positional((), "out"),
+      Opt::value((), &["", "/o"], "value"),
+    ]).with_flag_chars("-/");
+    crate::assert_opts!(OPTIONS);
+  }
+
+  #[test]
+  #[should_panic(expected = "Required positional arguments must come before optional ones")]
+  fn test_validate_positional_order() {
+    static OPT_LIST: [Opt<()>; 2] = [Opt::positional((), "out"), Opt::positional((), "file").required()];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Only optional positional arguments can be grouped")]
+  fn test_validate_group_required() {
+    static OPT_LIST: [Opt<()>; 2] = [Opt::positional((), "host").required().group_with_next(), Opt::positional((), "port")];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Grouped positional arguments must be followed by another positional argument")]
+  fn test_validate_group_unfinished() {
+    static OPT_LIST: [Opt<()>; 2] = [Opt::positional((), "host").group_with_next(), Opt::flag((), &["-v"])];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Extra positionals must be collected by the last positional argument")]
+  fn test_validate_collect_extra_last() {
+    static OPT_LIST: [Opt<()>; 2] = [Opt::positional((), "args").collect_extra(), Opt::positional((), "out")];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Only one help option is allowed")]
+  fn test_validate_help_unique() {
+    static OPT_LIST: [Opt<()>; 2] = [Opt::help_flag((), &["-h"]), Opt::help_flag((), &["--help"])];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Option names must start with one of the flag characters")]
+  fn test_validate_default_flag_chars() {
+    static OPT_LIST: [Opt<()>; 1] = [Opt::flag((), &["/a"])];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Option names must be unique")]
+  fn test_validate_flag_char_duplicate() {
+    static OPT_LIST: [Opt<()>; 2] = [Opt::flag((), &["-a"]), Opt::value((), &["--bravo", "/a"], "value")];
+    Opts::new(&OPT_LIST).with_flag_chars("-/").assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Option names must have more than a flag character")]
+  fn test_validate_bare_flag_char() {
+    static OPT_LIST: [Opt<()>; 1] = [Opt::flag((), &["-"])];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
+  #[test]
+  #[should_panic(expected = "Options must have at least one name")]
+  fn test_validate_unnamed() {
+    static OPT_LIST: [Opt<()>; 1] = [Opt::flag((), &[""])];
+    Opts::new(&OPT_LIST).assert_valid();
+  }
+
   #[test]
   fn test_with_chains() {
-    assert_eq!(Opts::<()>::new(&[]).with_flag_chars("-/"),
-      Opts { options: &[], flag_chars: "-/", description: None });
-    assert_eq!(Opts::<()>::new(&[]).with_description("test description"),
-      Opts { options: &[], flag_chars: "-", description: Some("test description") });
+    let options = Opts::<()>::new(&[]).with_flag_chars("-/");
+    assert_eq!((options.flag_chars, options.flag_ascii), ("-/", 1 << b'-' | 1 << b'/'));
+    assert_eq!(Opts::<()>::new(&[]).with_description("test description").description, Some("test description"));
+    assert_eq!(Opts::<()>::new(&[]).with_error_usage(ErrorUsage::Full).error_usage, ErrorUsage::Full);
+    assert_eq!(Opts::<()>::new(&[]).sinks, Sinks::NONE);
+  }
+
+  #[test]
+  fn test_starts_with_flag() {
+    let options = Opts::<()>::new(&[]);
+    assert!(options.starts_with_flag("-a"));
+    assert!(!options.starts_with_flag("/a") && !options.starts_with_flag("") && !options.starts_with_flag("é"));
+    let options = options.with_flag_chars("/é+");
+    assert!(options.starts_with_flag("/a") && options.starts_with_flag("+") && options.starts_with_flag("éa"));
+    assert!(!options.starts_with_flag("-a") && !options.starts_with_flag("ü"));
   }
 
   #[test]
@@ -123,4 +756,38 @@ mod tests {
     assert_eq!(OPTS1.help_option(), Some(&Opt::help_flag((), &["--help"])));
     assert_eq!(OPTS2.help_option(), None);
   }
+
+  #[test]
+  fn test_find() {
+    const OPTS: Opts<u32> = Opts::new(&[
+      Opt::help_flag(0, &["-h", "--help"]),
+      Opt::positional(1, "file"),
+      Opt::value(2, &["-o", "--out"], "path"),
+      Opt::flag(3, &["--flag"]),
+    ]);
+    assert_eq!(OPTS.find_by_name("--help").map(|o| o.id), Some(0));
+    assert_eq!(OPTS.find_by_name("file").map(|o| o.id), Some(1));
+    assert_eq!(OPTS.find_by_name("--out").map(|o| o.id), Some(2));
+    assert_eq!(OPTS.find_by_name("-x"), None);
+    assert_eq!(OPTS.find_by_name(""), None);
+    assert_eq!(OPTS.find_by_id(&3).map(|o| o.first_name()), Some("--flag"));
+    assert_eq!(OPTS.find_by_id(&4), None);
+  }
+
+  #[test]
+  fn test_filtered_iterators() {
+    const OPTS: Opts<u32> = Opts::new(&[
+      Opt::help_flag(0, &["-h"]),
+      Opt::positional(1, "one").required(),
+      Opt::value(2, &["-v"], "value"),
+      Opt::flag(3, &["-f"]).required(),
+      Opt::positional(4, "four"),
+      Opt::value(5, &["-w"], "value").required(),
+    ]);
+    fn ids<'a>(iter: impl Iterator<Item = &'a Opt<'a, u32>>) -> Vec<u32> { iter.map(|o| o.id).collect() }
+    assert_eq!(ids(OPTS.positionals()), [1, 4]);
+    assert_eq!(ids(OPTS.flags()), [0, 3]);
+    assert_eq!(ids(OPTS.value_options()), [2, 5]);
+    assert_eq!(ids(OPTS.required()), [1, 3, 5]);
+  }
 }
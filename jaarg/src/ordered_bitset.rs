@@ -7,6 +7,10 @@
 
 use core::ops::{BitAnd, BitAndAssign, BitOrAssign, Not, Shl};
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg_attr(feature = "alloc", allow(dead_code))]
 pub(crate) struct OrderedBitSet<T: OrderedBitSetStorage, const S: usize>([T; S]);
 
 impl<T: OrderedBitSetStorage, const S: usize> Default for OrderedBitSet<T, S> {
@@ -14,6 +18,7 @@ impl<T: OrderedBitSetStorage, const S: usize> Default for OrderedBitSet<T, S> {
 }
 
 // TODO: Obvious target for improvement when const traits land
+#[cfg_attr(feature = "alloc", allow(dead_code))]
 impl<T: OrderedBitSetStorage, const S: usize> OrderedBitSet<T, S> {
   /// Number of slots in the bit set.
   pub(crate) const CAPACITY: usize = T::BITS as usize * S;
@@ -25,10 +30,12 @@ impl<T: OrderedBitSetStorage, const S: usize> OrderedBitSet<T, S> {
   pub(crate) fn insert(&mut self, index: usize, value: bool) {
     let (array_idx, bit_idx) = self.internal_index(index);
     let bit_mask = T::from_usize(0b1) << T::from_usize(bit_idx);
-    if value {
-      self.0[array_idx] |= bit_mask;
-    } else {
-      self.0[array_idx] &= !bit_mask;
+    if let Some(word) = self.0.get_mut(array_idx) {
+      if value {
+        *word |= bit_mask;
+      } else {
+        *word &= !bit_mask;
+      }
     }
   }
 
@@ -36,7 +43,7 @@ impl<T: OrderedBitSetStorage, const S: usize> OrderedBitSet<T, S> {
   pub(crate) fn get(&self, index: usize) -> bool {
     let (array_idx, bit_idx) = self.internal_index(index);
     let bit_mask = T::from_usize(0b1) << T::from_usize(bit_idx);
-    (self.0[array_idx] & bit_mask) != T::from_usize(0)
+    self.0.get(array_idx).is_some_and(|&word| (word & bit_mask) != T::from_usize(0))
   }
 
   #[inline]
@@ -48,6 +55,41 @@ impl<T: OrderedBitSetStorage, const S: usize> OrderedBitSet<T, S> {
   }
 }
 
+/// Growable heap-backed bit set, for when the number of slots isn't bounded ahead of time.
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub(crate) struct HeapBitSet(alloc::vec::Vec<u32>);
+
+#[cfg(feature = "alloc")]
+impl HeapBitSet {
+  /// Number of slots in the bit set.
+  pub(crate) const CAPACITY: usize = usize::MAX;
+
+  /// Sets the slot at `index` to a binary value, growing the set if needed.
+  pub(crate) fn insert(&mut self, index: usize, value: bool) {
+    let (array_idx, bit_mask) = (index / u32::BITS as usize, 1 << (index % u32::BITS as usize));
+    if array_idx >= self.0.len() {
+      if !value {
+        return;
+      }
+      self.0.resize(array_idx + 1, 0);
+    }
+    if let Some(word) = self.0.get_mut(array_idx) {
+      if value {
+        *word |= bit_mask;
+      } else {
+        *word &= !bit_mask;
+      }
+    }
+  }
+
+  /// Gets the binary value at slot `index`, slots that were never set are false.
+  pub(crate) fn get(&self, index: usize) -> bool {
+    let (array_idx, bit_mask) = (index / u32::BITS as usize, 1 << (index % u32::BITS as usize));
+    self.0.get(array_idx).is_some_and(|bits| bits & bit_mask != 0)
+  }
+}
+
 trait OrderedBitSetStorage: core::fmt::Debug
     + Default + Copy + Clone + Eq + PartialEq
     + BitAnd<Output = Self> + Shl<Output = Self> + Not<Output = Self>
@@ -124,4 +166,24 @@ mod tests {
     harness::<u64, 2>(&indices);
     harness::<u128, 1>(&indices);
   }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn test_heap_bitset() {
+    let indices = [1, 32, 33, 127, 44, 47, 49, 1000];
+    let mut bitset = HeapBitSet::default();
+    assert!(!bitset.get(2000));
+    bitset.insert(2000, false);
+    assert_eq!(bitset.0.len(), 0);
+    for &index in &indices {
+      bitset.insert(index, true);
+    }
+    for slot in 0..1024 {
+      assert_eq!(bitset.get(slot), indices.contains(&slot));
+    }
+    for &index in &indices {
+      bitset.insert(index, false);
+      assert!(!bitset.get(index));
+    }
+  }
 }
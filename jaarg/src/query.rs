@@ -0,0 +1,173 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+//! Lightweight query API over a pre-tokenized argument list, for tiny tools that don't want to
+//! declare a full options table. Options are matched by name the same way the table parser does,
+//! and values can be separated by whitespace or an equals sign.
+//!
+//! Requires `features = ["alloc"]`.
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str::FromStr;
+use crate::Opt;
+
+/// Option names to query, either a single name (`"--width"`) or an array of names (`["-w", "--width"]`).
+pub trait Keys {
+  fn names(&self) -> &[&str];
+}
+
+impl Keys for &str {
+  fn names(&self) -> &[&str] { core::slice::from_ref(self) }
+}
+
+impl Keys for &[&str] {
+  fn names(&self) -> &[&str] { self }
+}
+
+impl<const N: usize> Keys for [&str; N] {
+  fn names(&self) -> &[&str] { self }
+}
+
+/// Errors from querying [Arguments].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+  /// A free argument was expected but none were left.
+  MissingArgument,
+  /// A required option wasn't given.
+  MissingOption(String),
+  /// An option that takes a value was given without one.
+  OptionWithoutAValue(String),
+  /// The value couldn't be converted to the requested type.
+  ArgumentParsingFailed { value: String, cause: String },
+}
+
+impl core::fmt::Display for Error {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::MissingArgument => write!(f, "Missing positional argument"),
+      Self::MissingOption(o) => write!(f, "Missing required option '{o}'"),
+      Self::OptionWithoutAValue(o) => write!(f, "Option '{o}' requires an argument"),
+      Self::ArgumentParsingFailed { value, cause } => write!(f, "Invalid argument '{value}': {cause}"),
+    }
+  }
+}
+
+impl core::error::Error for Error {}
+
+/// Remaining command line arguments, consumed as they're queried.
+#[derive(Debug, Clone, Default)]
+pub struct Arguments(Vec<String>);
+
+impl Arguments {
+  /// Wrap a list of argument tokens, not including the program name.
+  pub fn from_vec(args: Vec<String>) -> Self {
+    Self(args)
+  }
+
+  /// Find the next token matching one of the names, returning its index and inline value if any.
+  fn find(&self, keys: &impl Keys) -> Option<(usize, Option<&str>)> {
+    let matcher = Opt::flag((), keys.names());
+    self.0.iter().enumerate().find_map(|(i, token)| {
+      let (name, value) = token.split_once('=').map_or((token.as_str(), None), |(k, v)| (k, Some(v)));
+      matcher.match_name(name, 0).map(|_| (i, value))
+    })
+  }
+
+  /// Removes the next occurrence of a flag, returning true if it was present.
+  pub fn contains(&mut self, keys: impl Keys) -> bool {
+    match self.find(&keys) {
+      Some((i, None)) => { self.0.remove(i); true }
+      _ => false,
+    }
+  }
+
+  /// Removes the next occurrence of an option and its value, if present.
+  pub fn opt_value_from_str<T: FromStr>(&mut self, keys: impl Keys) -> Result<Option<T>, Error>
+    where T::Err: core::fmt::Display {
+    let Some((i, inline)) = self.find(&keys) else { return Ok(None); };
+    let value = match inline {
+      Some(value) => {
+        let value = value.to_string();
+        self.0.remove(i);
+        value
+      }
+      None if i + 1 < self.0.len() => {
+        let value = self.0.remove(i + 1);
+        self.0.remove(i);
+        value
+      }
+      None => return Err(Error::OptionWithoutAValue(self.0.remove(i))),
+    };
+    value.parse().map(Some).map_err(|err: T::Err| Error::ArgumentParsingFailed { cause: err.to_string(), value })
+  }
+
+  /// Removes the next occurrence of a required option and its value.
+  pub fn value_from_str<T: FromStr>(&mut self, keys: impl Keys) -> Result<T, Error> where T::Err: core::fmt::Display {
+    let name = keys.names().first().map_or(String::new(), |n| n.to_string());
+    self.opt_value_from_str(keys)?.ok_or(Error::MissingOption(name))
+  }
+
+  /// Removes every occurrence of an option and its value, in order.
+  pub fn values_from_str<T: FromStr>(&mut self, keys: impl Keys) -> Result<Vec<T>, Error>
+    where T::Err: core::fmt::Display {
+    let mut values = Vec::new();
+    while let Some(value) = self.opt_value_from_str(keys.names())? {
+      values.push(value);
+    }
+    Ok(values)
+  }
+
+  /// Removes the next free argument, if any. Should be called after querying all options.
+  pub fn opt_free_from_str<T: FromStr>(&mut self) -> Result<Option<T>, Error> where T::Err: core::fmt::Display {
+    if self.0.is_empty() {
+      return Ok(None);
+    }
+    let value = self.0.remove(0);
+    value.parse().map(Some).map_err(|err: T::Err| Error::ArgumentParsingFailed { cause: err.to_string(), value })
+  }
+
+  /// Removes the next free argument. Should be called after querying all options.
+  pub fn free_from_str<T: FromStr>(&mut self) -> Result<T, Error> where T::Err: core::fmt::Display {
+    self.opt_free_from_str()?.ok_or(Error::MissingArgument)
+  }
+
+  /// Returns the remaining unqueried arguments.
+  pub fn finish(self) -> Vec<String> {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args(tokens: &[&str]) -> Arguments {
+    Arguments::from_vec(tokens.iter().map(|t| t.to_string()).collect())
+  }
+
+  #[test]
+  fn test_query() {
+    let mut args = args(&["-v", "--width=10", "-I", "a", "in.txt", "--include", "b", "-x"]);
+    assert!(args.contains(["-v", "--verbose"]));
+    assert!(!args.contains("--verbose"));
+    assert_eq!(args.value_from_str::<u32>(["-w", "--width"]), Ok(10));
+    assert_eq!(args.opt_value_from_str::<u32>("--height"), Ok(None));
+    assert_eq!(args.values_from_str::<String>(["-I", "--include"]), Ok(["a", "b"].map(String::from).to_vec()));
+    assert_eq!(args.free_from_str::<String>(), Ok("in.txt".into()));
+    assert_eq!(args.finish(), ["-x"]);
+  }
+
+  #[test]
+  fn test_query_errors() {
+    let mut args = args(&["-w", "x", "-h"]);
+    assert_eq!(args.value_from_str::<u32>("--height"), Err(Error::MissingOption("--height".into())));
+    assert!(matches!(args.value_from_str::<u32>("-w"), Err(Error::ArgumentParsingFailed { value, .. }) if value == "x"));
+    assert_eq!(args.opt_value_from_str::<u32>("-h"), Err(Error::OptionWithoutAValue("-h".into())));
+    assert_eq!(args.free_from_str::<String>(), Err(Error::MissingArgument));
+  }
+}
@@ -0,0 +1,96 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Opts, ParseError, ParseHandler, ParseResult};
+use crate::ordered_bitset::OrderedBitSet;
+
+/// Set of option indices matched by [Opts::parse_seen],
+/// `W` is the number of 32-bit words of storage, giving a capacity of `32 * W` options.
+pub struct SeenSet<const W: usize = 4>(OrderedBitSet<u32, W>);
+
+impl<const W: usize> SeenSet<W> {
+  /// Maximum number of options that can be tracked.
+  pub const CAPACITY: usize = OrderedBitSet::<u32, W>::CAPACITY;
+
+  /// Returns true if the option at `index` in the options table was matched.
+  pub fn contains(&self, index: usize) -> bool {
+    index < Self::CAPACITY && self.0.get(index)
+  }
+
+  /// Returns true if any option with the given ID was matched.
+  pub fn contains_id<ID: PartialEq, const R: usize>(&self, options: &Opts<ID, R>, id: &ID) -> bool {
+    options.iter().enumerate().any(|(i, o)| o.id == *id && self.contains(i))
+  }
+}
+
+impl<const W: usize> Default for SeenSet<W> {
+  fn default() -> Self { Self(OrderedBitSet::new()) }
+}
+
+impl<ID, const R: usize> Opts<'_, ID, R> {
+  /// Parses an iterator of strings as argument tokens like [Opts::parse],
+  /// additionally returning the set of options that were matched.
+  ///
+  /// Matching an option beyond the capacity of the [SeenSet] fails with [ParseError::TooManyValues].
+  pub fn parse_seen<'a, const W: usize, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str,
+    args: I, mut handler: impl ParseHandler<'a, ID>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> (ParseResult, SeenSet<W>) {
+    let mut seen = SeenSet::default();
+    let result = self.parse(program_name, args, |ctx| {
+      if let Some(index) = self.options.iter().position(|o| core::ptr::eq(o, ctx.option)) {
+        if index >= SeenSet::<W>::CAPACITY {
+          // The option name is filled in by the parser
+          return Err(ParseError::TooManyValues(""));
+        }
+        seen.0.insert(index, true);
+      }
+      handler(ctx)
+    }, error);
+    (result, seen)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Opt, ParseControl};
+
+  #[test]
+  fn test_parse_seen() {
+    #[derive(PartialEq)]
+    enum Arg { Color, NoColor, Value }
+    const OPTIONS: Opts<Arg> = Opts::new(&[
+      Opt::flag(Arg::Color, &["--color"]),
+      Opt::flag(Arg::NoColor, &["--no-color"]),
+      Opt::value(Arg::Value, &["--value"], "value"),
+    ]);
+    let (result, seen): (_, SeenSet) = OPTIONS.parse_seen("", ["--value", "x", "--no-color"].iter(),
+      |_| Ok(ParseControl::Continue), |_, error| panic!("unreachable: {error:?}"));
+    assert!(matches!(result, ParseResult::ContinueSuccess));
+    assert!(!seen.contains(0));
+    assert!(seen.contains(1));
+    assert!(seen.contains(2));
+    assert!(!seen.contains(3));
+    assert!(!seen.contains(usize::MAX));
+    assert!(seen.contains_id(&OPTIONS, &Arg::NoColor));
+    assert!(!seen.contains_id(&OPTIONS, &Arg::Color));
+  }
+
+  #[test]
+  fn test_parse_seen_capacity() {
+    const OPTION: Opt<u32> = Opt::positional(0, "a");
+    const OPTIONS: Opts<u32> = Opts::new(&[OPTION; 33]);
+    let (result, seen) = OPTIONS.parse_seen::<1, &str, _>("", ["x"; 32].into_iter(),
+      |_| Ok(ParseControl::Continue), |_, error| panic!("unreachable: {error:?}"));
+    assert_eq!(result, ParseResult::ContinueSuccess);
+    assert!(seen.contains(31));
+    let mut failed = None;
+    let (result, _) = OPTIONS.parse_seen::<1, &str, _>("", ["x"; 33].into_iter(),
+      |_| Ok(ParseControl::Continue), |_, error| failed = Some(error == ParseError::TooManyValues("a")));
+    assert_eq!(result, ParseResult::ExitFailure);
+    assert_eq!(failed, Some(true));
+  }
+}
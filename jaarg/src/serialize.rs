@@ -0,0 +1,507 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use crate::{Choice, MapAction, Opt, Opts, ValueKind};
+use crate::option::OptType;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+#[cfg(feature = "alloc")]
+use {
+  alloc::{boxed::Box, string::String, vec::Vec},
+  crate::{OptExtras, OptHide},
+  serde::de::{Deserialize, Deserializer, Error},
+};
+
+impl Serialize for OptType {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match self {
+      Self::Positional => "positional",
+      Self::Flag       => "flag",
+      Self::Value      => "value",
+    })
+  }
+}
+
+impl Serialize for Choice<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("Choice", 2)?;
+    s.serialize_field("value", self.value)?;
+    match self.synonyms {
+      [] => s.skip_field("synonyms")?,
+      synonyms => s.serialize_field("synonyms", synonyms)?,
+    }
+    s.end()
+  }
+}
+
+impl Serialize for ValueKind {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match self {
+      Self::String  => "string",
+      Self::Integer => "integer",
+      Self::Float   => "float",
+      Self::Toggle  => "toggle",
+    })
+  }
+}
+
+impl<ID: Serialize> Serialize for Opt<'_, ID> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("Opt", 19)?;
+    s.serialize_field("id", &self.id)?;
+    s.serialize_field("type", &self.r#type)?;
+    s.serialize_field("names", self.names())?;
+    // Positional arguments describe their metavar as their value name
+    s.serialize_field("value_name", &self.value_name().or(self.metavar()))?;
+    // Only options declared with OptExtras::value_names list each value name
+    match self.value_names() {
+      value_names @ [_, _, ..] => s.serialize_field("value_names", value_names)?,
+      _ => s.skip_field("value_names")?,
+    }
+    s.serialize_field("value_kind", &self.value_kind)?;
+    match self.choices() {
+      [] => s.skip_field("choices")?,
+      choices => s.serialize_field("choices", choices)?,
+    }
+    s.serialize_field("help_text", &self.help_string)?;
+    s.serialize_field("required", &self.is_required())?;
+    s.serialize_field("help", &self.is_help())?;
+    s.serialize_field("visible_short", &self.is_short_visible())?;
+    s.serialize_field("visible_full", &self.is_full_visible())?;
+    match self.is_completion_visible() {
+      true  => s.skip_field("visible_completion")?,
+      false => s.serialize_field("visible_completion", &false)?,
+    }
+    match self.is_grouped_with_next() {
+      true  => s.serialize_field("group_with_next", &true)?,
+      false => s.skip_field("group_with_next")?,
+    }
+    match matches!(self.r#type, OptType::Value) && self.takes_positional() {
+      true  => s.serialize_field("or_positional", &true)?,
+      false => s.skip_field("or_positional")?,
+    }
+    match self.collects_extra() {
+      true  => s.serialize_field("collect_extra", &true)?,
+      false => s.skip_field("collect_extra")?,
+    }
+    match self.is_non_empty() {
+      true  => s.serialize_field("non_empty", &true)?,
+      false => s.skip_field("non_empty")?,
+    }
+    match self.constant() {
+      Some(constant) => s.serialize_field("constant", constant)?,
+      None => s.skip_field("constant")?,
+    }
+    // Callbacks can't be described, so tables using them only round-trip the map actions
+    match self.map_action() {
+      Some(MapAction::SetTrue) => s.serialize_field("action", "set_true")?,
+      Some(MapAction::SetValue) => s.serialize_field("action", "set_value")?,
+      Some(MapAction::Append) => s.serialize_field("action", "append")?,
+      Some(MapAction::Count) => s.serialize_field("action", "count")?,
+      None => s.skip_field("action")?,
+    }
+    s.end()
+  }
+}
+
+impl<ID: Serialize, const R: usize> Serialize for Opts<'_, ID, R> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("Opts", 3)?;
+    s.serialize_field("description", &self.description)?;
+    s.serialize_field("flag_chars", self.flag_chars)?;
+    s.serialize_field("options", self.options)?;
+    s.end()
+  }
+}
+
+/// Interface description for runtime built options, mirrors the serialised form of [Opts].
+#[cfg(feature = "alloc")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptsSpec<ID> {
+  #[serde(default)]
+  description: Option<String>,
+  #[serde(default)]
+  flag_chars: Option<String>,
+  options: Vec<OptSpec<ID>>,
+}
+
+/// Option entry of an interface description, mirrors the serialised form of [Opt].
+#[cfg(feature = "alloc")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptSpec<ID> {
+  id: ID,
+  #[serde(rename = "type")]
+  r#type: OptSpecType,
+  names: Vec<String>,
+  #[serde(default)]
+  value_name: Option<String>,
+  #[serde(default)]
+  value_names: Vec<String>,
+  #[serde(default)]
+  value_kind: Option<ValueKindSpec>,
+  #[serde(default)]
+  choices: Vec<ChoiceSpec>,
+  #[serde(default)]
+  help_text: Option<String>,
+  #[serde(default)]
+  required: bool,
+  #[serde(default)]
+  help: bool,
+  #[serde(default = "visible_default")]
+  visible_short: bool,
+  #[serde(default = "visible_default")]
+  visible_full: bool,
+  #[serde(default = "visible_default")]
+  visible_completion: bool,
+  #[serde(default)]
+  group_with_next: bool,
+  #[serde(default)]
+  or_positional: bool,
+  #[serde(default)]
+  collect_extra: bool,
+  #[serde(default)]
+  non_empty: bool,
+  #[serde(default)]
+  constant: Option<String>,
+  #[serde(default)]
+  action: Option<ActionSpec>,
+}
+
+/// Choice of an option entry, mirrors the serialised form of [Choice].
+#[cfg(feature = "alloc")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChoiceSpec {
+  value: String,
+  #[serde(default)]
+  synonyms: Vec<String>,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OptSpecType {
+  Positional,
+  Flag,
+  Value,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ValueKindSpec {
+  String,
+  Integer,
+  Float,
+  Toggle,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ActionSpec {
+  SetTrue,
+  SetValue,
+  Append,
+  Count,
+}
+
+#[cfg(feature = "alloc")]
+const fn visible_default() -> bool { true }
+
+#[cfg(feature = "alloc")]
+impl<ID> OptsSpec<ID> {
+  /// Check the description for everything that would otherwise cause [Opts] & [Opt] construction to panic.
+  fn validate(&self) -> Result<(), &'static str> {
+    let flag_chars = self.flag_chars.as_deref().unwrap_or("-");
+    let mut seen_names: Vec<&str> = Vec::new();
+    for option in &self.options {
+      if option.names.is_empty() {
+        return Err("Option names cannot be an empty slice");
+      }
+      if option.names.iter().any(String::is_empty) {
+        return Err("Option names cannot be empty");
+      }
+      match option.r#type {
+        OptSpecType::Positional => if option.names.len() != 1 {
+          return Err("Positional arguments must have exactly one name");
+        }
+        OptSpecType::Flag | OptSpecType::Value => {
+          for name in &option.names {
+            if seen_names.contains(&name.as_str()) {
+              return Err("Option names must be unique");
+            }
+            if !flag_chars.chars().any(|c| name.starts_with(c)) {
+              return Err("Option names must start with one of the flag characters");
+            }
+            seen_names.push(name);
+          }
+        }
+      }
+      if matches!(option.r#type, OptSpecType::Value) && option.value_name.is_none() {
+        return Err("Value options must have a value name");
+      }
+      if !option.choices.is_empty() && matches!(option.r#type, OptSpecType::Flag) {
+        return Err("Flags don't take a value");
+      }
+      if option.group_with_next && !matches!(option.r#type, OptSpecType::Positional) {
+        return Err("Only positional arguments can be grouped");
+      }
+      if option.or_positional && (!matches!(option.r#type, OptSpecType::Value) || option.value_names.len() > 1) {
+        return Err("Only options taking a single value can be given positionally");
+      }
+      if option.collect_extra && !matches!(option.r#type, OptSpecType::Positional) {
+        return Err("Only positional arguments can collect extra positionals");
+      }
+      if option.non_empty && !matches!(option.r#type, OptSpecType::Value) {
+        return Err("Only value options can reject empty arguments");
+      }
+      if option.constant.is_some() && (!matches!(option.r#type, OptSpecType::Flag) || option.help) {
+        return Err("Only flags can carry a constant");
+      }
+      if matches!(option.action, Some(ActionSpec::SetTrue)) && !matches!(option.r#type, OptSpecType::Flag) {
+        return Err("Only flags can be set to true");
+      }
+      if option.action.is_some() && option.help {
+        return Err("Help flags can't have an action");
+      }
+      if !matches!(option.r#type, OptSpecType::Value) && !option.value_names.is_empty() {
+        return Err("Only value options take a value name for each value");
+      }
+      if matches!((&option.r#type, &option.value_kind),
+          (OptSpecType::Flag, Some(ValueKindSpec::Integer | ValueKindSpec::Float | ValueKindSpec::Toggle))) {
+        return Err("Flags don't take a value");
+      }
+      if option.help && !matches!(option.r#type, OptSpecType::Flag) {
+        return Err("Only flags are allowed to be help options");
+      }
+      if option.help && option.required {
+        return Err("Help flag cannot be made required");
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<ID> OptSpec<ID> {
+  fn into_opt(self) -> Opt<'static, ID> {
+    fn leak(s: String) -> &'static str { Box::leak(s.into_boxed_str()) }
+    let names: Vec<&'static str> = self.names.into_iter().map(leak).collect();
+    // Validation ensures positional arguments have exactly one name
+    let first_name = names.first().copied().unwrap_or_default();
+    let mut extras = OptExtras::new();
+    let mut opt = match (self.r#type, self.help) {
+      (OptSpecType::Positional, _) => {
+        if let Some(metavar) = self.value_name {
+          extras = extras.metavar(leak(metavar));
+        }
+        Opt::positional(self.id, first_name)
+      }
+      (OptSpecType::Flag, true)    => Opt::help_flag(self.id, names.leak()),
+      (OptSpecType::Flag, false)   => Opt::flag(self.id, names.leak()),
+      (OptSpecType::Value, _)      => {
+        if !self.value_names.is_empty() {
+          extras = extras.value_names(self.value_names.into_iter().map(leak).collect::<Vec<_>>().leak());
+        }
+        Opt::value(self.id, names.leak(), leak(self.value_name.unwrap_or_default()))
+      }
+    };
+    if self.required {
+      opt = opt.required();
+    }
+    match self.value_kind {
+      Some(ValueKindSpec::Integer) => opt = opt.value_kind(ValueKind::Integer),
+      Some(ValueKindSpec::Float)   => opt = opt.value_kind(ValueKind::Float),
+      Some(ValueKindSpec::Toggle)  => opt = opt.value_kind(ValueKind::Toggle),
+      Some(ValueKindSpec::String) | None => (),
+    }
+    if let Some(help_text) = self.help_text {
+      opt = opt.help_text(leak(help_text));
+    }
+    if !self.choices.is_empty() {
+      extras = extras.choices(self.choices.into_iter().map(|choice| Choice::new(leak(choice.value))
+        .synonyms(choice.synonyms.into_iter().map(leak).collect::<Vec<_>>().leak())).collect::<Vec<_>>().leak());
+    }
+    if let Some(constant) = self.constant {
+      extras = extras.constant(leak(constant));
+    }
+    match self.action {
+      Some(ActionSpec::SetTrue)  => extras = extras.map_action(MapAction::SetTrue),
+      Some(ActionSpec::SetValue) => extras = extras.map_action(MapAction::SetValue),
+      Some(ActionSpec::Append)   => extras = extras.map_action(MapAction::Append),
+      Some(ActionSpec::Count)    => extras = extras.map_action(MapAction::Count),
+      None => (),
+    }
+    // Only options that use extras pay for them
+    if !extras.value_names.is_empty() || extras.metavar.is_some() || !extras.choices.is_empty()
+      || extras.constant.is_some() || extras.map_action.is_some() {
+      opt = opt.extras(Box::leak(Box::new(extras)));
+    }
+    if self.group_with_next {
+      opt = opt.group_with_next();
+    }
+    if self.or_positional {
+      opt = opt.or_positional();
+    }
+    if self.collect_extra {
+      opt = opt.collect_extra();
+    }
+    if self.non_empty {
+      opt = opt.non_empty();
+    }
+    if !self.visible_completion {
+      opt = opt.hide_completion();
+    }
+    match (self.visible_short, self.visible_full) {
+      (true, true)   => opt,
+      (false, true)  => opt.hide_usage(OptHide::Short),
+      (true, false)  => opt.hide_usage(OptHide::Full),
+      (false, false) => opt.hide_usage(OptHide::All),
+    }
+  }
+}
+
+/// Build options at runtime from an interface description in the same shape as the serialised form.
+/// Strings and the option list are leaked to satisfy the `'static` lifetimes of [Opts],
+/// so this is intended to be done once for the lifetime of the program.
+///
+/// Requires `features = ["serde", "alloc"]`.
+#[cfg(feature = "alloc")]
+impl<'de, ID: Deserialize<'de>, const R: usize> Deserialize<'de> for Opts<'static, ID, R> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let spec = OptsSpec::<ID>::deserialize(deserializer)?;
+    spec.validate().map_err(D::Error::custom)?;
+    let options = Vec::leak(spec.options.into_iter().map(OptSpec::into_opt).collect());
+    let mut opts = Self::new_with_capacity(options);
+    if let Some(flag_chars) = spec.flag_chars {
+      opts = opts.with_flag_chars(Box::leak(flag_chars.into_boxed_str()));
+    }
+    if let Some(description) = spec.description {
+      opts = opts.with_description(Box::leak(description.into_boxed_str()));
+    }
+    Ok(opts)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate alloc;
+  use crate::{Opt, OptHide, Opts, ValueKind};
+
+  #[test]
+  fn test_serialize_opts() {
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::help_flag("help", &["-h", "--help"]).hide_usage(OptHide::Short).help_text("Show help"),
+      Opt::positional("file", "file").required(),
+      Opt::value("out", &["-o"], "path").value_kind(ValueKind::Integer),
+    ]).with_description("Test program");
+
+    assert_eq!(serde_json::to_value(&OPTIONS).unwrap(), serde_json::json!({
+      "description": "Test program",
+      "flag_chars": "-",
+      "options": [
+        {
+          "id": "help", "type": "flag", "names": ["-h", "--help"], "value_name": null, "value_kind": "string",
+          "help_text": "Show help",
+          "required": false, "help": true, "visible_short": false, "visible_full": true,
+        },
+        {
+          "id": "file", "type": "positional", "names": ["file"], "value_name": null, "value_kind": "string",
+          "help_text": null,
+          "required": true, "help": false, "visible_short": true, "visible_full": true,
+        },
+        {
+          "id": "out", "type": "value", "names": ["-o"], "value_name": "path", "value_kind": "integer",
+          "help_text": null,
+          "required": false, "help": false, "visible_short": true, "visible_full": true,
+        },
+      ],
+    }));
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn test_deserialize_opts() {
+    use alloc::string::String;
+    use crate::{Choice, MapAction, OptExtras};
+
+    let options: Opts<String> = serde_json::from_str(r#"{
+      "description": "Test program",
+      "options": [
+        { "id": "help", "type": "flag", "names": ["-h", "--help"], "help": true, "visible_short": false },
+        { "id": "file", "type": "positional", "names": ["file"], "required": true, "help_text": "Input" },
+        { "id": "out", "type": "value", "names": ["-o"], "value_name": "path", "value_kind": "float" }
+      ]
+    }"#).unwrap();
+    assert_eq!(options.description, Some("Test program"));
+    assert_eq!(options.flag_chars, "-");
+    assert_eq!(options.options, [
+      Opt::help_flag(String::from("help"), &["-h", "--help"]).hide_usage(OptHide::Short),
+      Opt::positional(String::from("file"), "file").required().help_text("Input"),
+      Opt::value(String::from("out"), &["-o"], "path").value_kind(ValueKind::Float),
+    ]);
+
+    // Serialised tables should round-trip
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::help_flag("help", &["-h", "--help"]).hide_usage(OptHide::All),
+      Opt::flag("debug", &["--debug"]).hide_completion(),
+      Opt::value("color", &["--color"], "when").extras(&OptExtras::new().choices(&[Choice::new("always").synonyms(&["yes"]), Choice::new("never")])),
+      Opt::value("out", &["/o"], "path").required().non_empty(),
+      Opt::value("copy", &["/c"], "src").extras(&OptExtras::new().value_names(&["src", "dst"])),
+      Opt::value("target", &["/t"], "target").or_positional(),
+      Opt::positional("host", "host").group_with_next(),
+      Opt::positional("port", "port"),
+      Opt::positional("rest", "rest").collect_extra(),
+      Opt::flag("format", &["--json"]).extras(&OptExtras::new().constant("json").map_action(MapAction::SetValue)),
+      Opt::flag("verbose", &["-v"]).extras(&OptExtras::new().map_action(MapAction::Count)),
+      Opt::value("tag", &["/T"], "tag").extras(&OptExtras::new().map_action(MapAction::Append)),
+      Opt::flag("dry", &["/n"]).extras(&OptExtras::new().map_action(MapAction::SetTrue)),
+    ]).with_flag_chars("/-");
+    let json = serde_json::to_string(&OPTIONS).unwrap();
+    assert_eq!(serde_json::to_string(&serde_json::from_str::<Opts<String>>(&json).unwrap()).unwrap(), json);
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn test_deserialize_invalid_opts() {
+    use alloc::{format, string::ToString};
+    for (json, expect) in [
+      (r#"[{ "id": 0, "type": "flag", "names": [] }]"#, "Option names cannot be an empty slice"),
+      (r#"[{ "id": 0, "type": "flag", "names": [""] }]"#, "Option names cannot be empty"),
+      (r#"[{ "id": 0, "type": "positional", "names": ["a", "b"] }]"#, "Positional arguments must have exactly one name"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-a"] }, { "id": 1, "type": "flag", "names": ["-a"] }]"#,
+        "Option names must be unique"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["/a"] }]"#, "Option names must start with one of the flag characters"),
+      (r#"[{ "id": 0, "type": "value", "names": ["-a"] }]"#, "Value options must have a value name"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-a"], "value_kind": "integer" }]"#, "Flags don't take a value"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-a"], "value_names": ["a", "b"] }]"#,
+        "Only value options take a value name for each value"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-a"], "choices": [{ "value": "a" }] }]"#, "Flags don't take a value"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-a"], "or_positional": true }]"#,
+        "Only options taking a single value can be given positionally"),
+      (r#"[{ "id": 0, "type": "positional", "names": ["a"], "non_empty": true }]"#,
+        "Only value options can reject empty arguments"),
+      (r#"[{ "id": 0, "type": "value", "names": ["-a"], "value_name": "a", "constant": "b" }]"#,
+        "Only flags can carry a constant"),
+      (r#"[{ "id": 0, "type": "positional", "names": ["a"], "action": "set_true" }]"#, "Only flags can be set to true"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-h"], "help": true, "action": "count" }]"#,
+        "Help flags can't have an action"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-a"], "group_with_next": true }]"#,
+        "Only positional arguments can be grouped"),
+      (r#"[{ "id": 0, "type": "positional", "names": ["a"], "help": true }]"#, "Only flags are allowed to be help options"),
+      (r#"[{ "id": 0, "type": "flag", "names": ["-h"], "help": true, "required": true }]"#,
+        "Help flag cannot be made required"),
+    ] {
+      let json = format!(r#"{{ "options": {json} }}"#);
+      let err = serde_json::from_str::<Opts<u32>>(&json).err().unwrap();
+      assert!(err.to_string().starts_with(expect), "{err}");
+    }
+  }
+}
@@ -0,0 +1,311 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+//! Shell-style tokenizer for parsing command lines from config files, REPL input, or IPC messages.
+//! Follows POSIX shell quoting: single quotes are literal, double quotes allow escaping `\`, `"`, `$`,
+//! and `` ` ``, backslash escapes any character outside quotes, and backslash-newline continues the line.
+//! No expansion or substitution is performed.
+//!
+//! Tokens borrow from the input and are unescaped lazily, so no allocator is required,
+//! and tokens without quotes or escapes can be passed to the parser as is:
+//!
+//! ```
+//! # use jaarg::{Opt, Opts, ParseControl, ParseResult};
+//! # const OPTIONS: Opts<()> = Opts::new(&[Opt::value((), &["--name"], "name")]);
+//! let mut buffer = [""; 8];
+//! let mut len = 0;
+//! for token in jaarg::shlex::tokenize("--name jaarg") {
+//!   buffer[len] = token.unwrap().as_str().expect("needs unescaping");
+//!   len += 1;
+//! }
+//! assert!(matches!(OPTIONS.parse_slice("", &buffer[..len], |ctx| {
+//!   assert_eq!(ctx.arg, "jaarg");
+//!   Ok(ParseControl::Continue)
+//! }, |_, _| {}), ParseResult::ContinueSuccess));
+//! ```
+//!
+//! With an allocator, tokens can be converted with `to_string()`.
+//! [Opts::parse_command_line] does all of this, unescaping into a fixed buffer.
+
+use core::fmt::{Display, Formatter, Write};
+use core::str::Chars;
+use crate::{Opts, ParseControl, ParseError, ParseHandler, ParseResult};
+use crate::argparse::{parse_step, ParserState};
+use crate::utf8::encode_into;
+
+/// Errors from [tokenize].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizeError {
+  /// A quoted string wasn't closed before the end of the input.
+  UnterminatedQuote,
+  /// The input ended with an unescaped backslash.
+  TrailingBackslash,
+}
+
+impl Display for TokenizeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::UnterminatedQuote => write!(f, "Unterminated quoted string"),
+      Self::TrailingBackslash => write!(f, "Unexpected backslash at end of input"),
+    }
+  }
+}
+
+impl core::error::Error for TokenizeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quote {
+  None,
+  Single,
+  Double,
+}
+
+/// Split a command string into argument tokens.
+/// The iterator ends after the first error.
+pub fn tokenize(command: &str) -> Tokenize<'_> {
+  Tokenize { rest: command }
+}
+
+/// Iterator returned by [tokenize].
+#[derive(Debug, Clone)]
+pub struct Tokenize<'a> {
+  rest: &'a str,
+}
+
+impl<'a> Iterator for Tokenize<'a> {
+  type Item = Result<Token<'a>, TokenizeError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    // Skip separating whitespace and line continuations
+    loop {
+      self.rest = self.rest.trim_start();
+      match self.rest.strip_prefix("\\\n") {
+        Some(rest) => self.rest = rest,
+        None if self.rest.is_empty() => return None,
+        None => break,
+      }
+    }
+
+    // Find the end of the token
+    let mut quote = Quote::None;
+    let mut chars = self.rest.char_indices();
+    let end = loop {
+      let Some((i, c)) = chars.next() else {
+        if quote != Quote::None {
+          self.rest = "";
+          return Some(Err(TokenizeError::UnterminatedQuote));
+        }
+        break self.rest.len();
+      };
+      match (quote, c) {
+        (Quote::None, c) if c.is_whitespace() => break i,
+        (Quote::None, '\'') => quote = Quote::Single,
+        (Quote::None, '"') => quote = Quote::Double,
+        (Quote::Single, '\'') | (Quote::Double, '"') => quote = Quote::None,
+        // Skip over the escaped character, failing if there isn't one
+        (Quote::None | Quote::Double, '\\') if chars.next().is_none() => {
+          self.rest = "";
+          return Some(Err(TokenizeError::TrailingBackslash));
+        }
+        _ => {}
+      }
+    };
+
+    let (token, rest) = self.rest.split_at(end);
+    self.rest = rest;
+    Some(Ok(Token(token)))
+  }
+}
+
+/// A single token borrowed from the input of [tokenize], still quoted and escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a>(&'a str);
+
+impl<'a> Token<'a> {
+  /// The token as it appears in the input.
+  pub const fn raw(&self) -> &'a str {
+    self.0
+  }
+
+  /// Returns the token if it doesn't contain any quotes or escapes, which covers most tokens.
+  pub fn as_str(&self) -> Option<&'a str> {
+    (!self.0.contains(['\'', '"', '\\'])).then_some(self.0)
+  }
+
+  /// Iterate the characters of the token with quotes and escapes removed.
+  pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+    Unescape { chars: self.0.chars(), quote: Quote::None }
+  }
+}
+
+impl Display for Token<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    self.chars().try_for_each(|c| f.write_char(c))
+  }
+}
+
+/// Removes quotes and escapes from a token that has already been validated by [Tokenize].
+struct Unescape<'a> {
+  chars: Chars<'a>,
+  quote: Quote,
+}
+
+impl Iterator for Unescape<'_> {
+  type Item = char;
+
+  fn next(&mut self) -> Option<char> {
+    loop {
+      match (self.quote, self.chars.next()?) {
+        (Quote::Single, '\'') | (Quote::Double, '"') => self.quote = Quote::None,
+        (Quote::None, '\'') => self.quote = Quote::Single,
+        (Quote::None, '"') => self.quote = Quote::Double,
+        (Quote::None, '\\') => match self.chars.next()? {
+          '\n' => {}
+          c => return Some(c),
+        }
+        // Inside double quotes backslash only escapes some characters, otherwise it's literal
+        (Quote::Double, '\\') => {
+          let mut lookahead = self.chars.clone();
+          match lookahead.next() {
+            Some('\n') => self.chars = lookahead,
+            Some(c @ ('\\' | '"' | '$' | '`')) => {
+              self.chars = lookahead;
+              return Some(c);
+            }
+            _ => return Some('\\'),
+          }
+        }
+        (_, c) => return Some(c),
+      }
+    }
+  }
+}
+
+/// Quote a string so [tokenize] or a POSIX shell reads it back as a single token.
+/// Strings that don't need quoting are left as is.
+pub fn quote(token: &str) -> Quoted<'_> {
+  Quoted(token)
+}
+
+/// Display wrapper returned by [quote].
+#[derive(Debug, Clone, Copy)]
+pub struct Quoted<'a>(&'a str);
+
+impl Display for Quoted<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !self.0.is_empty() && self.0.chars().all(safe) {
+      return f.write_str(self.0);
+    }
+    // Single quotes can't be escaped inside single quotes, so close the quote and escape it outside
+    f.write_char('\'')?;
+    for (i, part) in self.0.split('\'').enumerate() {
+      if i > 0 {
+        f.write_str("'\\''")?;
+      }
+      f.write_str(part)?;
+    }
+    f.write_char('\'')
+  }
+}
+
+impl<ID, const R: usize> Opts<'_, ID, R> {
+  /// Parses a command line string split into tokens by [tokenize], for environments without an argument
+  /// vector like the browser, or for commands read from a script or a REPL. Tokens containing quotes or escapes
+  /// are unescaped into `buffer`, which must be large enough for the longest one or parsing fails with
+  /// [ParseError::TokenTooLong]; tokens without them are passed to the handler as is.
+  pub fn parse_command_line<'a>(&self, program_name: &str, command_line: &str, buffer: &mut [u8],
+    mut handler: impl ParseHandler<'a, ID>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult {
+    let mut state = ParserState::default();
+    for token in tokenize(command_line) {
+      parse_step!(self, token.map_err(ParseError::InvalidCommandLine)
+        // Tokens without quotes or escapes are passed as is, the rest are unescaped into the buffer
+        .and_then(|token| match token.as_str() {
+          Some(token) => Ok(token),
+          None => encode_into(token.chars().map(Ok), buffer),
+        })
+        .and_then(|token| self.next(&mut state, token, program_name, &mut handler)), program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_tokens<const N: usize>(command: &str, expected: [&str; N]) {
+    let mut tokens = tokenize(command);
+    for expect in expected {
+      assert!(tokens.next().unwrap().unwrap().chars().eq(expect.chars()), "expected '{expect}' in '{command}'");
+    }
+    assert_eq!(tokens.next(), None);
+  }
+
+  #[test]
+  fn test_tokenize() {
+    assert_tokens("", []);
+    assert_tokens("  a  b\tc\n", ["a", "b", "c"]);
+    assert_tokens(r#"'a b' "c d" e\ f"#, ["a b", "c d", "e f"]);
+    assert_tokens(r#"a'b'"c" '' """#, ["abc", "", ""]);
+    assert_tokens(r#"'\n"' "\"\$\x" \'"#, [r#"\n""#, r#""$\x"#, "'"]);
+    assert_tokens("a \\\n b\\\nc \"d\\\ne\"", ["a", "bc", "de"]);
+    assert_eq!(tokenize("a 'b").nth(1), Some(Err(TokenizeError::UnterminatedQuote)));
+    assert_eq!(tokenize("a \"b\\\"").nth(1), Some(Err(TokenizeError::UnterminatedQuote)));
+    assert_eq!(tokenize("a b\\").nth(1), Some(Err(TokenizeError::TrailingBackslash)));
+    let mut tokens = tokenize("'a");
+    assert!(tokens.next().unwrap().is_err());
+    assert_eq!(tokens.next(), None);
+  }
+
+  #[test]
+  fn test_quote() {
+    extern crate alloc;
+    use alloc::string::ToString;
+    for token in ["plain", "-o=a/b.txt", "", "a b", "it's", "'", "$HOME \\ \"q\"", "\n"] {
+      let quoted = quote(token).to_string();
+      let mut tokens = tokenize(&quoted);
+      assert_eq!(tokens.next().unwrap().unwrap().to_string(), token);
+      assert_eq!(tokens.next(), None);
+    }
+    assert_eq!(quote("plain").to_string(), "plain");
+    assert_eq!(quote("it's").to_string(), r#"'it'\''s'"#);
+  }
+
+  #[test]
+  fn test_token() {
+    let mut tokens = tokenize("plain 'quo ted'").flatten();
+    assert_eq!(tokens.next().and_then(|t| t.as_str()), Some("plain"));
+    let quoted = tokens.next().unwrap();
+    assert_eq!((quoted.raw(), quoted.as_str()), ("'quo ted'", None));
+  }
+
+  #[test]
+  fn test_parse_command_line() {
+    use crate::Opt;
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--name"], "name"),
+      Opt::positional(1, "file"),
+    ]);
+    let mut seen = [[0u8; 8]; 2];
+    assert_eq!(OPTIONS.parse_command_line("", r#"--name='a b' c"d""#, &mut [0; 16], |ctx| {
+      seen[*ctx.id as usize][..ctx.arg.len()].copy_from_slice(ctx.arg.as_bytes());
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess);
+    assert_eq!(seen, [*b"a b\0\0\0\0\0", *b"cd\0\0\0\0\0\0"]);
+
+    for (command_line, expected) in [
+      ("--name 'a", ParseError::InvalidCommandLine(TokenizeError::UnterminatedQuote)),
+      ("'--name=abcdefgh'", ParseError::TokenTooLong("--name=a")),
+    ] {
+      let mut failed = false;
+      assert_eq!(OPTIONS.parse_command_line("", command_line, &mut [0; 8], |_| Ok(ParseControl::Continue),
+        |_, error| failed = error == expected), ParseResult::ExitFailure);
+      assert!(failed);
+    }
+  }
+}
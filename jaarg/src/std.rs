@@ -6,73 +6,583 @@
 extern crate std;
 
 use crate::{
-  alloc::ParseMapResult, ErrorUsageWriter, ErrorUsageWriterContext, HandlerResult, HelpWriter, HelpWriterContext,
-  Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult, StandardErrorUsageWriter, StandardFullHelpWriter
+  alloc::ParseMapResult, ErrorUsageWriter, ErrorUsageWriterContext, HandlerResult, HelpWriter, ParseHandler, HelpWriterContext,
+  Multicall, Opt, Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult, StandardErrorUsageWriter, StandardFullHelpWriter
 };
+use crate::argparse::{parse_step, ParserState};
+use crate::utf8::utf8_prefix;
+use crate::option::OptType;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::hash::Hash;
+use std::io::Write;
+use std::string::String;
+use std::vec::Vec;
 use std::path::Path;
+use std::process::ExitCode;
 use std::rc::Rc;
-use std::{env, eprint, print};
+use std::{env, io, write};
 
-impl<ID: 'static> Opts<ID> {
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
   /// Wrapper around [Opts::parse] that gathers arguments from the command line and prints errors to stderr.
   /// The errors are formatted in a standard user-friendly format.
   ///
+  /// With `features = ["wild"]` on Windows, wildcards in positional arguments are expanded
+  /// unless disabled with [Opt::no_wildcards](crate::Opt::no_wildcards).
+  ///
+  /// Handler panics are reported as errors when enabled with [Opts::with_contained_panics].
+  ///
   /// Requires `features = ["std"]`.
-  pub fn parse_easy<'a>(&self, handler: impl FnMut(ParseHandlerContext<ID>) -> HandlerResult<'a, ParseControl>
+  pub fn parse_easy<'a>(&self, handler: impl ParseHandler<'a, ID>
+  ) -> ParseResult {
+    let panic_message = core::cell::Cell::new(None);
+    match self.contain_panics {
+      true => self.parse_easy_args(contain_panics(handler, &panic_message), &panic_message),
+      false => self.parse_easy_args(handler, &panic_message),
+    }
+  }
+
+  fn parse_easy_args<'a>(&self, handler: impl ParseHandler<'a, ID>, panic_message: &core::cell::Cell<Option<String>>
   ) -> ParseResult {
-    let (program_name, argv) = Self::easy_args();
-    self.parse(&program_name, argv, handler,
-      |name, e| self.eprint_usage::<StandardErrorUsageWriter<'_, ID>>(name, e))
+    let (program_name, argv) = easy_args();
+    let error = |name: &str, e: ParseError| fill_panic_message(e, panic_message,
+      |e| self.eprint_usage::<StandardErrorUsageWriter<'_, ID, R>>(name, e));
+    #[cfg(feature = "wild")]
+    if cfg!(windows) {
+      return self.parse_wild(&program_name, argv, handler, error);
+    }
+    self.parse(&program_name, argv, handler, error)
+  }
+
+  /// Catch panics from the handler in [Opts::parse_easy], so a bug in a handler fails like a parse error
+  /// instead of unwinding out of the parser. Stdout is flushed, and the panic message is printed by the
+  /// error writer as [ParseError::HandlerPanic].
+  ///
+  /// The panic hook isn't changed, so the default panic output is still printed first unless the
+  /// application installs its own hook with [std::panic::set_hook]. Panics can't be caught when built
+  /// with `panic = "abort"`, in which case the process aborts as usual.
+  /// ```no_run
+  /// # use jaarg::{Opt, Opts, ParseControl, ParseResult};
+  /// const OPTIONS: Opts<()> = Opts::new(&[Opt::value((), &["--jobs"], "n")]).with_contained_panics();
+  /// // Prints "<program>: Internal error while handling option '--jobs': too many jobs" and the usage,
+  /// // unless built with `panic = "abort"`
+  /// assert_eq!(OPTIONS.parse_easy(|ctx| {
+  ///   assert!(ctx.arg.len() < 3, "too many jobs");
+  ///   Ok(ParseControl::Continue)
+  /// }), ParseResult::ExitFailure);
+  /// ```
+  ///
+  /// Requires `features = ["std"]`.
+  #[inline]
+  pub const fn with_contained_panics(mut self) -> Self {
+    self.contain_panics = true;
+    self
+  }
+
+  /// Wrapper around [Opts::parse_easy] for use in `main`, returning the [ExitCode] to exit with
+  /// when the program shouldn't continue, eg; after showing help or an error.
+  ///
+  /// ```no_run
+  /// # use jaarg::{Opt, Opts, ParseControl};
+  /// # use std::process::ExitCode;
+  /// # const OPTIONS: Opts<()> = Opts::new(&[Opt::help_flag((), &["--help"])]);
+  /// fn main() -> ExitCode {
+  ///   if let Err(code) = OPTIONS.parse_easy_exit(|ctx| {
+  ///     OPTIONS.print_full_help(ctx.program_name);
+  ///     Ok(ParseControl::Quit)
+  ///   }) {
+  ///     return code;
+  ///   }
+  ///   ExitCode::SUCCESS
+  /// }
+  /// ```
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn parse_easy_exit<'a>(&self, handler: impl ParseHandler<'a, ID>
+  ) -> Result<(), ExitCode> {
+    self.parse_easy(handler).into_exit()
   }
 
   /// Prints full help text for the options using the standard full.
   ///
   /// Requires `features = ["std"]`.
   pub fn print_full_help(&self, program_name: &str) {
-    self.print_help::<StandardFullHelpWriter<'_, ID>>(program_name);
+    self.print_help::<StandardFullHelpWriter<'_, ID, R>>(program_name);
   }
 
   /// Print help text to stdout using the provided help writer.
   ///
   /// Requires `features = ["std"]`.
-  pub fn print_help<'a, W: HelpWriter<'a, ID>>(&'a self, program_name: &'a str) {
-    let ctx = HelpWriterContext { options: self, program_name };
-    print!("{}", W::new(ctx));
+  pub fn print_help<'a, W: HelpWriter<'a, ID, R>>(&'a self, program_name: &'a str) {
+    ignore_broken_pipe(self.write_help::<W>(&mut io::stdout(), program_name));
   }
 
   /// Print help text to stderr using the provided help writer.
   ///
   /// Requires `features = ["std"]`.
-  pub fn eprint_help<'a, W: HelpWriter<'a, ID>>(&'a self, program_name: &'a str) {
-    let ctx = HelpWriterContext { options: self, program_name };
-    eprint!("{}", W::new(ctx));
+  pub fn eprint_help<'a, W: HelpWriter<'a, ID, R>>(&'a self, program_name: &'a str) {
+    ignore_broken_pipe(self.write_help::<W>(&mut io::stderr(), program_name));
   }
 
   /// Print error & usage text to stderr using the provided error & usage writer.
   ///
   /// Requires `features = ["std"]`.
-  pub fn eprint_usage<'a, W: ErrorUsageWriter<'a, ID>>(&'a self, program_name: &'a str, error: ParseError<'a>) {
+  pub fn eprint_usage<'a, W: ErrorUsageWriter<'a, ID, R>>(&'a self, program_name: &'a str, error: ParseError<'a>) {
+    ignore_broken_pipe(self.write_usage::<W>(&mut io::stderr(), program_name, error));
+  }
+
+  /// Write help text to an output stream using the provided help writer.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn write_help<'a, W: HelpWriter<'a, ID, R>>(&'a self, out: &mut impl Write, program_name: &'a str
+  ) -> io::Result<()> {
+    let ctx = HelpWriterContext { options: self, program_name };
+    write!(out, "{}", W::new(ctx))
+  }
+
+  /// Write error & usage text to an output stream using the provided error & usage writer.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn write_usage<'a, W: ErrorUsageWriter<'a, ID, R>>(&'a self, out: &mut impl Write, program_name: &'a str,
+    error: ParseError<'a>
+  ) -> io::Result<()> {
     let ctx = ErrorUsageWriterContext { options: self, program_name, error };
-    eprint!("{}", W::new(ctx));
+    write!(out, "{}", W::new(ctx))
   }
 
-  fn easy_args() -> (Rc<str>, env::Args) {
-    let mut argv = env::args();
-    let argv0 = argv.next().unwrap();
-    let program_name = Path::new(&argv0).file_name().unwrap().to_string_lossy();
-    (program_name.into(), argv)
+  /// Wrapper around [Opts::parse_slice] that parses previously collected command line arguments
+  /// and prints errors to stderr, so handlers can keep borrows of the arguments.
+  /// The errors are formatted in a standard user-friendly format.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn parse_easy_slice<'t>(&self, argv: &'t Argv,
+    handler: impl FnMut(ParseHandlerContext<'t, ID>) -> HandlerResult<'t, ParseControl>
+  ) -> ParseResult where 'o: 't {
+    let args: Vec<&str> = argv.args.iter().map(String::as_str).collect();
+    self.parse_slice(&argv.program_name, &args, handler,
+      |name, e| self.eprint_usage::<StandardErrorUsageWriter<'_, ID, R>>(name, e))
   }
 }
 
-impl Opts<&'static str> {
+/// Context passed to the handler of [Opts::parse_os], like [ParseHandlerContext]
+/// except the argument is the raw token, so paths that aren't valid UTF-8 are passed through intact.
+///
+/// Requires `features = ["std"]`.
+#[derive(Debug)]
+pub struct ParseOsHandlerContext<'a, ID> {
+  /// Name of the program, for printing statuses to the user.
+  pub program_name: &'a str,
+  /// The generic argument ID that was matched.
+  pub id: &'a ID,
+  /// The option that was matched by the parser.
+  pub option: &'a Opt<'a, ID>,
+  /// The name of the argument parameter that was matched,
+  /// for option parameters this is the token supplied by the user.
+  pub name: &'a str,
+  /// The argument provided to positional arguments and value options, else "".
+  pub arg: &'a OsStr,
+  /// Which value the argument is for options taking several, see [ParseHandlerContext::value_index].
+  pub value_index: usize,
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Parses an iterator of OS strings as argument tokens. Option names are matched against the
+  /// valid UTF-8 prefix of each token, and arguments are passed to the handler as the raw [OsStr],
+  /// like [Utf8Policy::Raw](crate::Utf8Policy::Raw). For the other policies, use [Opts::parse_bytes]
+  /// with [OsStr::as_encoded_bytes].
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn parse_os<'a, S: AsRef<OsStr> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    mut handler: impl FnMut(ParseOsHandlerContext<ID>) -> HandlerResult<'a, ParseControl>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult {
+    let mut state = ParserState::default();
+    for arg in args {
+      let token = arg.as_ref();
+      let prefix = utf8_prefix(token.as_encoded_bytes());
+      // Invalid UTF-8 can only be passed through in arguments, not option names
+      if prefix.len() < token.len() && self.is_option_name(&state, prefix) {
+        parse_step!(self, Err(ParseError::InvalidUtf8(prefix)), program_name, error);
+      }
+      parse_step!(self, self.next(&mut state, prefix, program_name, &mut |ctx| handler(ParseOsHandlerContext {
+        program_name: ctx.program_name, id: ctx.id, option: ctx.option, name: ctx.name, value_index: ctx.value_index,
+        arg: handler_arg(token, prefix, &ctx),
+      })), program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+
+  /// Wrapper around [Opts::parse_os] that gathers arguments from the command line without requiring
+  /// them to be valid UTF-8, and prints errors to stderr in a standard user-friendly format.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn parse_easy_os<'a>(&self, handler: impl FnMut(ParseOsHandlerContext<ID>) -> HandlerResult<'a, ParseControl>
+  ) -> ParseResult {
+    let mut argv = env::args_os();
+    let program_name = program_name(&argv.next().unwrap_or_default());
+    self.parse_os(&program_name, argv, handler,
+      |name, e| self.eprint_usage::<StandardErrorUsageWriter<'_, ID, R>>(name, e))
+  }
+}
+
+/// Map the argument matched in the UTF-8 prefix of a token back to the raw token.
+fn os_arg<'t, ID>(token: &'t OsStr, prefix: &'t str, ctx: &ParseHandlerContext<ID>) -> &'t OsStr {
+  if matches!(ctx.option.r#type, OptType::Flag) {
+    return OsStr::new("");
+  }
+  // Arguments always run to the end of the token
+  let offset = prefix.len().saturating_sub(ctx.arg.len());
+  let bytes = token.as_encoded_bytes();
+  if offset == 0 {
+    token
+  } else if prefix.len() == bytes.len() {
+    OsStr::new(prefix.get(offset..).unwrap_or_default())
+  } else {
+    match bytes.get(offset..) {
+      // SAFETY: The split is immediately after the non-empty UTF-8 substring `prefix[..offset]`.
+      Some(rest) => unsafe { OsStr::from_encoded_bytes_unchecked(rest) },
+      None => token,
+    }
+  }
+}
+
+/// The argument passed to [Opts::parse_os] handlers, which is the raw token except for choices and constants
+/// that are passed as their canonical value.
+fn handler_arg<'t, ID>(token: &'t OsStr, prefix: &'t str, ctx: &ParseHandlerContext<'t, ID>) -> &'t OsStr {
+  if ctx.option.choices().is_empty() && ctx.option.constant().is_none() {
+    os_arg(token, prefix, ctx)
+  } else {
+    OsStr::new(ctx.arg)
+  }
+}
+
+/// Command line arguments collected once, for use with [Opts::parse_easy_slice].
+///
+/// Requires `features = ["std"]`.
+#[derive(Debug, Clone)]
+pub struct Argv {
+  program_name: String,
+  args: Vec<String>,
+}
+
+impl Argv {
+  /// Collect the arguments from the command line.
+  pub fn from_env() -> Self {
+    let (program_name, args) = easy_args();
+    Self { program_name: program_name.as_ref().into(), args: args.collect() }
+  }
+
+  /// The name of the running program, without leading directories.
+  pub fn program_name(&self) -> &str {
+    &self.program_name
+  }
+}
+
+/// Wraps a handler so a panic is returned as [ParseError::HandlerPanic] instead of unwinding through the parser.
+/// The handler can't return the message borrowed, so it's stored in `message` for [fill_panic_message].
+fn contain_panics<'a, 'm, ID>(mut handler: impl ParseHandler<'a, ID> + 'm,
+  message: &'m core::cell::Cell<Option<String>>,
+) -> impl ParseHandler<'a, ID> + 'm {
+  move |ctx: ParseHandlerContext<'_, ID>| {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| handler(ctx))).unwrap_or_else(|payload| {
+      let _ = io::stdout().flush();
+      message.set(Some(match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => String::from(payload.downcast_ref::<&'static str>().copied().unwrap_or("the handler panicked")),
+      }));
+      Err(ParseError::HandlerPanic("", ""))
+    })
+  }
+}
+
+/// Report `error` with the message of a panic caught by [contain_panics] filled in,
+/// errors returned by the handler itself are reported as-is.
+fn fill_panic_message(error: ParseError, message: &core::cell::Cell<Option<String>>, report: impl FnOnce(ParseError)) {
+  match (error, message.take()) {
+    (ParseError::HandlerPanic(o, _), Some(message)) => report(ParseError::HandlerPanic(o, &message)),
+    (error, _) => report(error),
+  }
+}
+
+/// The program name and remaining command line arguments. Arguments that aren't valid UTF-8 are converted
+/// lossily like [Utf8Policy::Replace](crate::Utf8Policy::Replace), [Opts::parse_easy_os] passes them through.
+fn easy_args() -> (Rc<str>, impl Iterator<Item = String>) {
+  let mut argv = env::args_os();
+  let program_name = program_name(&argv.next().unwrap_or_default());
+  (program_name, argv.map(lossy_string))
+}
+
+fn lossy_string(arg: OsString) -> String {
+  arg.into_string().unwrap_or_else(|arg| arg.to_string_lossy().into_owned())
+}
+
+/// The program name from argv[0] without leading directories. An empty argv, or an argv[0] that
+/// doesn't end in a file name, is used as is rather than treated as an error.
+fn program_name(argv0: &OsStr) -> Rc<str> {
+  Path::new(argv0).file_name().unwrap_or(argv0).to_string_lossy().into()
+}
+
+impl<ID: Ord + Copy, const R: usize> Opts<'_, ID, R> {
   /// Parse arguments from the command line and return the results in a [`alloc::collections::BTreeMap`].
   /// Help and errors are formatted in a standard user-friendly format.
   ///
   /// Requires `features = ["std"]`.
-  pub fn parse_map_easy(&self) -> ParseMapResult {
-    let (program_name, argv) = Self::easy_args();
+  pub fn parse_map_easy(&self) -> ParseMapResult<ID> {
+    let (program_name, argv) = easy_args();
     self.parse_map(&program_name, argv,
       |name| self.print_full_help(name),
-      |name, e| self.eprint_usage::<StandardErrorUsageWriter<'_, &'static str>>(name, e))
+      |name, e| self.eprint_usage::<StandardErrorUsageWriter<'_, ID, R>>(name, e))
+  }
+}
+
+impl<ID: Eq + Hash + Copy, const R: usize> Opts<'_, ID, R> {
+  /// Parse an iterator of strings as arguments and return the results in a [`HashMap`],
+  /// for callers that don't need the ordering of [Opts::parse_map].
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn parse_hashmap<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    help: impl Fn(&str), error: impl FnOnce(&str, ParseError)
+  ) -> ParseHashMapResult<ID> {
+    let mut out = HashMap::new();
+    match self.parse(program_name, args, |ctx| {
+      if ctx.option.is_help() {
+        help(program_name);
+        Ok(ParseControl::Quit)
+      } else {
+        out.insert(*ctx.id, ctx.arg.into());
+        Ok(ParseControl::Continue)
+      }
+    }, error) {
+      ParseResult::ContinueSuccess => ParseHashMapResult::Map(out),
+      ParseResult::ExitSuccess => ParseHashMapResult::ExitSuccess,
+      ParseResult::ExitFailure => ParseHashMapResult::ExitFailure,
+    }
+  }
+
+  /// Parse arguments from the command line and return the results in a [`HashMap`].
+  /// Help and errors are formatted in a standard user-friendly format.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn parse_hashmap_easy(&self) -> ParseHashMapResult<ID> {
+    let (program_name, argv) = easy_args();
+    self.parse_hashmap(&program_name, argv,
+      |name| self.print_full_help(name),
+      |name, e| self.eprint_usage::<StandardErrorUsageWriter<'_, ID, R>>(name, e))
+  }
+}
+
+/// The result of parsing commands with [Opts::parse_hashmap].
+pub enum ParseHashMapResult<ID = &'static str> {
+  Map(HashMap<ID, String>),
+  ExitSuccess, ExitFailure
+}
+
+impl ParseResult {
+  /// Converts the result into the [ExitCode] to exit with, unless parsing succeeded
+  /// and the program should continue.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn into_exit(self) -> Result<(), ExitCode> {
+    match self {
+      ParseResult::ContinueSuccess => Ok(()),
+      ParseResult::ExitSuccess => Err(ExitCode::SUCCESS),
+      ParseResult::ExitFailure => Err(ExitCode::FAILURE),
+    }
+  }
+}
+
+impl<F: Fn(&str, env::ArgsOs) -> ExitCode> Multicall<'_, F> {
+  /// Dispatch to the applet named by the basename of `argv[0]`, passing it the applet name and remaining
+  /// arguments, which may not be valid UTF-8 (see [Opts::parse_os]). When invoked under any other name,
+  /// the first argument names the applet instead (eg; `busybox ls`). Otherwise the available applets
+  /// are printed to stderr.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn dispatch_easy(&self) -> ExitCode {
+    let mut argv = env::args_os();
+    let argv0 = lossy_string(argv.next().unwrap_or_default());
+    let program_name = Multicall::<F>::basename(&argv0);
+    if let Some((name, main)) = self.find(program_name) {
+      return main(name, argv);
+    }
+    let mut stderr = io::stderr();
+    let result = match argv.next().map(lossy_string) {
+      Some(applet) => match self.find(&applet) {
+        Some((name, main)) => return main(name, argv),
+        None => std::writeln!(stderr, "{program_name}: Unknown applet '{applet}'"),
+      }
+      None => std::writeln!(stderr, "Usage: {program_name} <applet> [arguments]..."),
+    };
+    ignore_broken_pipe(result.and_then(|()|
+      std::writeln!(stderr, "Available applets: {}", self.names().collect::<Vec<_>>().join(", "))));
+    ExitCode::FAILURE
+  }
+}
+
+impl crate::query::Arguments {
+  /// Gather arguments from the command line, skipping the program name.
+  ///
+  /// Requires `features = ["std"]`.
+  pub fn from_env() -> Self {
+    Self::from_vec(env::args_os().skip(1).map(lossy_string).collect())
+  }
+}
+
+/// Print the output of a terminal flag or completion script to stdout.
+pub(crate) fn print_output(write: impl FnOnce(&mut dyn core::fmt::Write) -> core::fmt::Result) {
+  let mut text = String::new();
+  let _ = write(&mut text);
+  ignore_broken_pipe(io::stdout().write_all(text.as_bytes()));
+}
+
+/// Discards errors from a closed output pipe (eg; `tool --help | head -1`), other errors panic like `print!` does.
+#[allow(clippy::panic)]
+fn ignore_broken_pipe(result: io::Result<()>) {
+  match result {
+    Err(err) if err.kind() != io::ErrorKind::BrokenPipe => panic!("failed printing help text: {err}"),
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+  use std::vec::Vec;
+
+  const OPTIONS: Opts<()> = Opts::new(&[
+    Opt::help_flag((), &["-h", "--help"]).help_text("Show help"),
+    Opt::positional((), "file").required(),
+  ]);
+
+  #[test]
+  fn test_ignore_broken_pipe() {
+    ignore_broken_pipe(Ok(()));
+    ignore_broken_pipe(Err(io::ErrorKind::BrokenPipe.into()));
+  }
+
+  #[test]
+  #[should_panic(expected = "failed printing help text")]
+  fn test_other_print_errors_panic() {
+    ignore_broken_pipe(Err(io::ErrorKind::PermissionDenied.into()));
+  }
+
+  #[test]
+  fn test_write_help() {
+    let mut out = Vec::new();
+    OPTIONS.write_help::<StandardFullHelpWriter<'_, ()>>(&mut out, "test").unwrap();
+    assert_eq!(str::from_utf8(&out).unwrap(), "Usage: test [-h|--help] <file>\n\n\
+      Positional arguments:\n  file\n\n\
+      Options:\n  -h | --help .. Show help\n");
+  }
+
+  #[test]
+  fn test_write_usage() {
+    let mut out = Vec::new();
+    OPTIONS.write_usage::<StandardErrorUsageWriter<'_, ()>>(&mut out, "test",
+      ParseError::RequiredPositional("file")).unwrap();
+    assert_eq!(str::from_utf8(&out).unwrap(), "test: Missing required positional argument 'file'\n\
+      Usage: test [-h|--help] <file>\n\
+      Run 'test --help' to view all available options.\n");
+  }
+
+  #[test]
+  fn test_parse_hashmap() {
+    const OPTIONS: Opts<&'static str> = Opts::new(&[
+      Opt::flag("flag", &["-f"]),
+      Opt::value("value", &["-v"], "value"),
+      Opt::positional("positional", "positional"),
+    ]);
+    let ParseHashMapResult::Map(map) = OPTIONS.parse_hashmap("", ["-f", "-v", "a", "b"].iter(),
+      |_| panic!("unreachable"), |_, error| panic!("unreachable: {error:?}")) else { panic!() };
+    assert_eq!(map, HashMap::from([("flag", "".into()), ("value", "a".into()), ("positional", "b".into())]));
+  }
+
+  #[test]
+  fn test_into_exit() {
+    assert_eq!(ParseResult::ContinueSuccess.into_exit(), Ok(()));
+    assert_eq!(ParseResult::ExitSuccess.into_exit(), Err(ExitCode::SUCCESS));
+    assert_eq!(ParseResult::ExitFailure.into_exit(), Err(ExitCode::FAILURE));
+  }
+
+  #[test]
+  fn test_contain_panics() {
+    const OPTIONS: Opts<u32> = Opts::new(&[Opt::flag(0, &["-f"]), Opt::value(1, &["--jobs"], "n")]);
+    let message = core::cell::Cell::new(None);
+    for (args, name, expected) in [(&["-f"][..], "-f", "boom"), (&["--jobs", "100"], "--jobs", "too many jobs: 100")] {
+      let mut failed = None;
+      assert_eq!(OPTIONS.parse_slice("test", args, contain_panics(|ctx| match ctx.id {
+        0 => panic!("boom"),
+        _ => panic!("too many jobs: {}", ctx.arg),
+      }, &message), |_, error| fill_panic_message(error, &message,
+        |error| failed = Some(error == ParseError::HandlerPanic(name, expected)))), ParseResult::ExitFailure);
+      assert_eq!(failed, Some(true));
+    }
+
+    // Errors returned by the handler aren't mistaken for a caught panic
+    let mut failed = None;
+    assert_eq!(OPTIONS.parse_slice("test", &["-f"], contain_panics(|_| Err(ParseError::HandlerPanic("", "")), &message),
+      |_, error| fill_panic_message(error, &message, |error| failed = Some(error == ParseError::HandlerPanic("-f", "")))),
+      ParseResult::ExitFailure);
+    assert_eq!(failed, Some(true));
+
+    // Handlers that don't panic are unaffected
+    assert_eq!(OPTIONS.parse_slice("test", &["-f"], contain_panics(|_| Ok(ParseControl::Continue), &message),
+      |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess);
+    assert!(OPTIONS.with_contained_panics().contain_panics);
+  }
+
+  #[test]
+  fn test_parse_easy_slice() {
+    let argv = Argv { program_name: "test".into(), args: ["a.txt"].map(String::from).to_vec() };
+    let mut file = None;
+    assert!(matches!(OPTIONS.parse_easy_slice(&argv, |ctx| {
+      file = Some(ctx.arg);
+      Ok(ParseControl::Continue)
+    }), ParseResult::ContinueSuccess));
+    assert_eq!(file, Some("a.txt"));
+    assert_eq!(argv.program_name(), "test");
+  }
+
+  #[test]
+  fn test_parse_os() {
+    use std::borrow::ToOwned;
+    use std::ffi::OsString;
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::flag(0, &["-f"]),
+      Opt::value(1, &["--out"], "path"),
+      Opt::positional(2, "file"),
+    ]);
+    let mut seen = Vec::new();
+    assert!(matches!(OPTIONS.parse_os("", ["-f", "--out=a", "--out", "b", "c"].iter(), |ctx| {
+      seen.push((*ctx.id, ctx.name.to_owned(), ctx.arg.to_owned()));
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(seen, [(0, "-f", ""), (1, "--out", "a"), (1, "--out", "b"), (2, "file", "c")]
+      .map(|(id, name, arg)| (id, String::from(name), OsString::from(arg))));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_parse_os_non_utf8() {
+    use std::borrow::ToOwned;
+    use std::os::unix::ffi::OsStrExt;
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--out"], "path"),
+      Opt::positional(1, "file"),
+    ]);
+    let args = [OsStr::from_bytes(b"--out=a\xff"), OsStr::from_bytes(b"\xfe.txt")];
+    let mut seen = Vec::new();
+    assert!(matches!(OPTIONS.parse_os("", args.iter(), |ctx| {
+      seen.push(ctx.arg.as_bytes().to_owned());
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(seen, [b"a\xff".as_slice(), b"\xfe.txt"]);
+    assert!(matches!(OPTIONS.parse_os("", [OsStr::from_bytes(b"--o\xffut=a")].iter(),
+      |_| Ok(ParseControl::Continue), |_, error| assert!(matches!(error, ParseError::UnknownOption("--o")))),
+      ParseResult::ExitFailure));
+    assert!(matches!(OPTIONS.parse_os("", [OsStr::from_bytes(b"--out\xff")].iter(),
+      |_| Ok(ParseControl::Continue), |_, error| assert!(matches!(error, ParseError::InvalidUtf8("--out")))),
+      ParseResult::ExitFailure));
   }
 }
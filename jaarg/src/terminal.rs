@@ -0,0 +1,137 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{OptExtras, Opts};
+
+/// What a terminal flag prints before parsing exits successfully, set with [OptExtras::print_and_exit].
+#[derive(Debug, Clone, Copy)]
+pub enum Terminal<'o> {
+  /// Text printed as-is, eg; a version string or a list of supported formats.
+  Text(&'o str),
+  /// Output written by a function that is passed the program name, eg; for generating a config schema.
+  Writer(fn(&mut dyn core::fmt::Write, &str) -> core::fmt::Result),
+}
+
+impl Terminal<'_> {
+  /// Write the output of the terminal flag to `w`.
+  pub fn write_to(&self, w: &mut dyn core::fmt::Write, program_name: &str) -> core::fmt::Result {
+    match self {
+      Self::Text(text) => w.write_str(text),
+      Self::Writer(writer) => writer(w, program_name),
+    }
+  }
+}
+
+/// Writers are compared by address so [Opt](crate::Opt) can still derive [PartialEq].
+impl PartialEq for Terminal<'_> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::Text(lhs), Self::Text(rhs)) => lhs == rhs,
+      (Self::Writer(lhs), Self::Writer(rhs)) => core::ptr::fn_addr_eq(*lhs, *rhs),
+      _ => false,
+    }
+  }
+}
+
+/// A sink set with [Opts::with_output] or [Opts::with_help_handler], compared by address so [Opts] can still derive
+/// [PartialEq].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OutputSink(pub(crate) fn(&str));
+
+impl PartialEq for OutputSink {
+  fn eq(&self, other: &Self) -> bool {
+    core::ptr::fn_addr_eq(self.0, other.0)
+  }
+}
+
+impl core::fmt::Write for OutputSink {
+  fn write_str(&mut self, s: &str) -> core::fmt::Result {
+    (self.0)(s);
+    Ok(())
+  }
+}
+
+impl<'o, ID> OptExtras<'o, ID> {
+  /// Makes a flag print `output` and end parsing with [ParseResult::ExitSuccess](crate::ParseResult::ExitSuccess)
+  /// when matched, without calling the handler, eg; for `--list-formats` or `--print-config-schema`.
+  /// Output goes to the sink set with [Opts::with_output], or to stdout with `features = ["std"]`.
+  #[inline]
+  pub const fn print_and_exit(mut self, output: Terminal<'o>) -> Self {
+    self.terminal = Some(output);
+    self
+  }
+}
+
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Send the output of flags declared with [OptExtras::print_and_exit] to `sink`, instead of stdout.
+  /// Without the `std` feature the output is discarded unless a sink is set.
+  /// ```
+  /// # use jaarg::{Opt, OptExtras, Opts, ParseResult, Terminal};
+  /// const OPTIONS: Opts<()> = Opts::new(&[
+  ///   Opt::flag((), &["--list-formats"]).extras(&OptExtras::new().print_and_exit(Terminal::Text("json\ntoml\n"))),
+  /// ]).with_output(|text| eprint!("{text}"));
+  /// let result = OPTIONS.parse("", ["--list-formats"].iter(), |_| unreachable!(), |_, _| {});
+  /// assert_eq!(result, ParseResult::ExitSuccess);
+  /// ```
+  pub const fn with_output(mut self, sink: fn(&str)) -> Self {
+    self.sinks.output = Some(OutputSink(sink));
+    self
+  }
+
+  /// Print the output of a terminal flag or completion script to the output sink, or to stdout if there isn't one.
+  pub(crate) fn print_output(&self, write: impl FnOnce(&mut dyn core::fmt::Write) -> core::fmt::Result) {
+    match self.sinks.output {
+      Some(mut sink) => { let _ = write(&mut sink); }
+      #[cfg(feature = "std")]
+      None => crate::std::print_output(write),
+      #[cfg(not(feature = "std"))]
+      None => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate std;
+  use std::cell::RefCell;
+  use std::string::String;
+  use crate::{Opt, ParseControl, ParseResult};
+  use super::*;
+
+  std::thread_local! {
+    static OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
+  }
+
+  fn print_schema(w: &mut dyn core::fmt::Write, program_name: &str) -> core::fmt::Result {
+    writeln!(w, "{{\"title\": \"{program_name}\"}}")
+  }
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::flag(0, &["-v"]),
+    Opt::flag(1, &["--list-formats"]).extras(&OptExtras::new().print_and_exit(Terminal::Text("json\ntoml\n"))),
+    Opt::flag(2, &["--print-config-schema"]).extras(&OptExtras::new().print_and_exit(Terminal::Writer(print_schema))),
+    Opt::positional(3, "file").required(),
+  ]).with_output(|text| OUTPUT.with_borrow_mut(|output| output.push_str(text)));
+
+  #[test]
+  fn test_print_and_exit() {
+    for (arg, expected) in [("--list-formats", "json\ntoml\n"), ("--print-config-schema", "{\"title\": \"tool\"}\n")] {
+      let mut handled = 0;
+      // Missing required positionals aren't reported, and following tokens aren't parsed
+      assert_eq!(OPTIONS.parse("tool", ["-v", arg, "--unknown"].iter(), |ctx| {
+        handled |= 1 << *ctx.id;
+        Ok(ParseControl::Continue)
+      }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ExitSuccess);
+      assert_eq!(handled, 1);
+      assert_eq!(OUTPUT.take(), expected);
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "Only flags can print and exit")]
+  fn test_print_and_exit_value_disallowed() {
+    Opt::value((), &["-o"], "out").extras(&OptExtras::new().print_and_exit(Terminal::Text("")));
+  }
+}
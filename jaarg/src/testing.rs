@@ -0,0 +1,143 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+//! Helpers for unit testing command line definitions without writing handlers.
+//!
+//! ```
+//! use jaarg::{Opt, Opts, ParseError, ParseResult};
+//! use jaarg::testing::Event;
+//!
+//! const OPTIONS: Opts<u32> = Opts::new(&[
+//!   Opt::flag(0, &["-v", "--verbose"]),
+//!   Opt::positional(1, "file").required(),
+//! ]);
+//!
+//! let recording = OPTIONS.record(&["--verbose", "in.txt"]);
+//! assert_eq!(recording.result, ParseResult::ContinueSuccess);
+//! assert_eq!(recording.events, [
+//!   Event { id: 0, name: "--verbose", arg: "" },
+//!   Event { id: 1, name: "file", arg: "in.txt" },
+//! ]);
+//! assert_eq!(OPTIONS.record(&["-v"]).error, Some(ParseError::RequiredPositional("file")));
+//! ```
+//!
+//! Requires `features = ["testing"]`.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use crate::{HandlerResult, Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult};
+use crate::argparse::{parse_step, ParserState};
+
+/// A single call to the parse handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event<'t, ID> {
+  /// The ID of the matched option.
+  pub id: ID,
+  /// The name of the matched option, as supplied by the user for flag and value options.
+  pub name: &'t str,
+  /// The argument provided to positional arguments and value options, else "".
+  pub arg: &'t str,
+}
+
+/// Everything that happened while parsing, returned by [Opts::record].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording<'t, ID> {
+  /// How the program would proceed after parsing.
+  pub result: ParseResult,
+  /// Every handler call in order, including the one that returned an error.
+  pub events: Vec<Event<'t, ID>>,
+  /// The error that ended parsing, if any.
+  pub error: Option<ParseError<'t>>,
+}
+
+impl<'t, ID> Recording<'t, ID> {
+  /// The IDs of every handler call in order.
+  pub fn ids(&self) -> impl Iterator<Item = &ID> {
+    self.events.iter().map(|event| &event.id)
+  }
+}
+
+impl<'o, ID: Clone, const R: usize> Opts<'o, ID, R> {
+  /// Parse a slice of argument tokens and record every handler call and the error, if any.
+  /// Every handler call continues parsing, see [Opts::record_with] for testing help and early exits.
+  ///
+  /// Requires `features = ["testing"]`.
+  pub fn record<'t>(&self, args: &[&'t str]) -> Recording<'t, ID> where 'o: 't {
+    self.record_with(args, |_| Ok(ParseControl::Continue))
+  }
+
+  /// Like [Opts::record], except `control` decides how parsing proceeds after each handler call,
+  /// as a real handler would.
+  ///
+  /// Requires `features = ["testing"]`.
+  pub fn record_with<'t>(&self, args: &[&'t str],
+    mut control: impl FnMut(&Event<'t, ID>) -> HandlerResult<'t, ParseControl>,
+  ) -> Recording<'t, ID> where 'o: 't {
+    let mut events = Vec::new();
+    let mut error = None;
+    let result = self.record_dyn(args, &mut |ctx| {
+      let event = Event { id: ctx.id.clone(), name: ctx.name, arg: ctx.arg };
+      let control = control(&event);
+      events.push(event);
+      control
+    }, &mut |err| error = Some(err));
+    Recording { result, events, error }
+  }
+
+  fn record_dyn<'t>(&self, args: &[&'t str],
+    handler: &mut dyn FnMut(ParseHandlerContext<'t, ID>) -> HandlerResult<'t, ParseControl>,
+    error: &mut dyn FnMut(ParseError<'t>),
+  ) -> ParseResult where 'o: 't {
+    let mut state = ParserState::default();
+    let mut error = |_: &str, err| error(err);
+    for &arg in args {
+      parse_step!(self, self.next(&mut state, arg, "", handler), "", error);
+    }
+    self.finish(state, "", error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Opt, ParseErrorKind};
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::help_flag(0, &["-h"]),
+    Opt::value(1, &["-n"], "count"),
+    Opt::positional(2, "file").required(),
+  ]);
+
+  #[test]
+  fn test_record() {
+    let recording = OPTIONS.record(&["-n", "4", "in"]);
+    assert_eq!(recording.result, ParseResult::ContinueSuccess);
+    assert_eq!(recording.ids().copied().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(recording.events[0], Event { id: 1, name: "-n", arg: "4" });
+    assert_eq!(recording.error, None);
+
+    let recording = OPTIONS.record(&["in", "extra"]);
+    assert_eq!(recording.result, ParseResult::ExitFailure);
+    assert_eq!(recording.error, Some(ParseError::UnexpectedToken("extra")));
+    assert_eq!(OPTIONS.record(&["-n"]).error, Some(ParseError::ExpectArgument("-n")));
+  }
+
+  #[test]
+  fn test_record_with() {
+    let control = |event: &Event<u32>| match event.id {
+      0 => Ok(ParseControl::Quit),
+      1 => Ok(event.arg.parse::<u32>().map(|_| ParseControl::Continue)?),
+      _ => Ok(ParseControl::Continue),
+    };
+    let recording = OPTIONS.record_with(&["-h", "in"], control);
+    assert_eq!(recording.result, ParseResult::ExitSuccess);
+    assert_eq!(recording.events, [Event { id: 0, name: "-h", arg: "" }]);
+
+    let recording = OPTIONS.record_with(&["-n", "x"], control);
+    assert_eq!(recording.events.len(), 1);
+    assert_eq!(recording.error, Some(ParseError::ArgumentError("-n", "x", ParseErrorKind::InvalidInteger)));
+  }
+}
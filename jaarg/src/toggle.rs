@@ -0,0 +1,110 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{ParseError, ParseErrorKind};
+
+/// A tri-state switch for options like `--color=auto`, which can be forced on, forced off,
+/// or left for the program to decide. Declare options taking one with [Opt::toggle](crate::Opt::toggle).
+/// ```
+/// use jaarg::{Opt, Opts, ParseControl, Toggle};
+///
+/// const OPTIONS: Opts<()> = Opts::new(&[Opt::toggle((), &["--color"])]);
+/// let mut color = Toggle::Auto;
+/// OPTIONS.parse("", ["--color=never"].iter(), |ctx| {
+///   color = ctx.arg.parse()?;
+///   Ok(ParseControl::Continue)
+/// }, |_, _| {});
+/// assert_eq!(color, Toggle::Off);
+/// assert!(!color.resolve(true));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Toggle {
+  /// `off`, or any of `no`, `false`, `never`, `disable`, `0`.
+  Off,
+  /// `on`, or any of `yes`, `true`, `always`, `enable`, `1`.
+  On,
+  /// `auto` (the default).
+  #[default]
+  Auto,
+}
+
+impl Toggle {
+  /// The value name of options declared with [Opt::toggle](crate::Opt::toggle), listing the canonical spellings.
+  pub const VALUE_NAME: &'static str = "on|off|auto";
+
+  /// Whether the switch is on, deciding [Toggle::Auto] with `auto`, eg; whether output is a terminal.
+  pub const fn resolve(self, auto: bool) -> bool {
+    match self {
+      Self::Off => false,
+      Self::On => true,
+      Self::Auto => auto,
+    }
+  }
+
+  /// The canonical spelling of the switch.
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      Self::Off => "off",
+      Self::On => "on",
+      Self::Auto => "auto",
+    }
+  }
+}
+
+impl core::str::FromStr for Toggle {
+  type Err = ParseError<'static>;
+
+  /// Parses any accepted spelling ignoring ASCII case,
+  /// errors are [ParseErrorKind::InvalidToggle] with the fields filled in by the parser.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    const SPELLINGS: [(&str, Toggle); 13] = [
+      ("off", Toggle::Off), ("no", Toggle::Off), ("false", Toggle::Off), ("never", Toggle::Off),
+      ("disable", Toggle::Off), ("0", Toggle::Off),
+      ("on", Toggle::On), ("yes", Toggle::On), ("true", Toggle::On), ("always", Toggle::On),
+      ("enable", Toggle::On), ("1", Toggle::On),
+      ("auto", Toggle::Auto),
+    ];
+    SPELLINGS.iter().find(|(spelling, _)| spelling.eq_ignore_ascii_case(s)).map(|&(_, toggle)| toggle)
+      .ok_or(ParseError::ArgumentError("", "", ParseErrorKind::InvalidToggle))
+  }
+}
+
+impl core::fmt::Display for Toggle {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Opt, Opts, ParseControl, ParseResult};
+  use super::*;
+
+  #[test]
+  fn test_toggle_from_str() {
+    for (s, toggle) in [("on", Toggle::On), ("YES", Toggle::On), ("1", Toggle::On), ("Never", Toggle::Off),
+        ("false", Toggle::Off), ("auto", Toggle::Auto)] {
+      assert_eq!(s.parse(), Ok(toggle), "'{s}'");
+    }
+    for s in ["", "maybe", "onn", "2"] {
+      assert_eq!(s.parse::<Toggle>(), Err(ParseError::ArgumentError("", "", ParseErrorKind::InvalidToggle)));
+    }
+    for toggle in [Toggle::On, Toggle::Off, Toggle::Auto] {
+      assert_eq!(toggle.as_str().parse(), Ok(toggle));
+    }
+  }
+
+  #[test]
+  fn test_toggle_option() {
+    const OPTIONS: Opts<()> = Opts::new(&[Opt::toggle((), &["--progress"])]);
+    let mut error = None;
+    assert_eq!(OPTIONS.parse("", ["--progress", "sometimes"].iter(), |ctx| {
+      ctx.arg.parse::<Toggle>()?;
+      Ok(ParseControl::Continue)
+    }, |_, e| error = Some(e == ParseError::ArgumentError("--progress", "sometimes", ParseErrorKind::InvalidToggle))),
+      ParseResult::ExitFailure);
+    assert_eq!(error, Some(true));
+  }
+}
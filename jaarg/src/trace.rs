@@ -0,0 +1,110 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+#[cfg(feature = "trace")]
+use crate::Opts;
+
+/// A decision made by the parser, passed to the sink set with [Opts::with_trace].
+/// Options are identified by their index in the options table.
+///
+/// Requires `features = ["trace"]`.
+#[cfg(feature = "trace")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent<'a> {
+  /// A token was received from the argument stream.
+  Token(&'a str),
+  /// A flag or value option was matched by `name`, with the value attached by an equals sign if any.
+  Option { index: usize, name: &'a str, value: Option<&'a str> },
+  /// Follows [TraceEvent::Option] when a value option was matched without a value,
+  /// the next token will be taken as its value.
+  AwaitValue { index: usize, name: &'a str },
+  /// The token was taken as the value of the value option matched by the previous token.
+  Value { index: usize, name: &'a str, value: &'a str },
+  /// The token was assigned to a positional argument, the following tokens fill the next positional.
+  Positional { index: usize, name: &'a str, value: &'a str },
+  /// The token looked like an option but didn't match any option names.
+  Unmatched(&'a str),
+  /// The token isn't an option and every positional argument has already been filled.
+  NoPositional(&'a str),
+}
+
+/// The sink set with [Opts::with_trace], compared by address so [Opts] can still derive [PartialEq].
+#[cfg(feature = "trace")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TraceSink(pub(crate) fn(TraceEvent));
+
+#[cfg(feature = "trace")]
+impl PartialEq for TraceSink {
+  fn eq(&self, other: &Self) -> bool {
+    core::ptr::fn_addr_eq(self.0, other.0)
+  }
+}
+
+/// Send an event to the trace sink, if there is one.
+/// Expands to nothing unless the `trace` feature is enabled, so it can be used unconditionally.
+macro_rules! trace {
+  ($opts:expr, $event:expr) => {
+    #[cfg(feature = "trace")]
+    if let Some(sink) = $opts.sinks.trace {
+      (sink.0)($event);
+    }
+  };
+}
+pub(crate) use trace;
+
+#[cfg(feature = "trace")]
+impl<'o, ID, const R: usize> Opts<'o, ID, R> {
+  /// Send every parsing decision to `sink`, for debugging unexpected parse results.
+  /// ```
+  /// # use jaarg::{Opt, Opts, TraceEvent};
+  /// const OPTIONS: Opts<()> = Opts::new(&[Opt::flag((), &["-v"])])
+  ///   .with_trace(|event| eprintln!("{event:?}"));
+  /// ```
+  ///
+  /// Requires `features = ["trace"]`.
+  pub const fn with_trace(mut self, sink: fn(TraceEvent)) -> Self {
+    self.sinks.trace = Some(TraceSink(sink));
+    self
+  }
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod tests {
+  extern crate std;
+  use std::cell::RefCell;
+  use std::vec::Vec;
+  use std::string::String;
+  use crate::{Opt, ParseControl};
+  use super::*;
+
+  std::thread_local! {
+    static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+  }
+
+  #[test]
+  fn test_trace() {
+    const OPTIONS: Opts<()> = Opts::new(&[
+      Opt::flag((), &["-v"]),
+      Opt::value((), &["-o"], "out"),
+      Opt::positional((), "file"),
+    ]).with_trace(|event| EVENTS.with_borrow_mut(|events| events.push(std::format!("{event:?}"))));
+
+    let _ = OPTIONS.parse("", ["-v", "-o", "x", "-o=y", "in", "-q", "extra"].iter(),
+      |_| Ok(ParseControl::Continue), |_, _| {});
+    let _ = OPTIONS.parse("", ["a", "b"].iter(), |_| Ok(ParseControl::Continue), |_, _| {});
+    let expected = [
+      TraceEvent::Token("-v"), TraceEvent::Option { index: 0, name: "-v", value: None },
+      TraceEvent::Token("-o"), TraceEvent::Option { index: 1, name: "-o", value: None },
+      TraceEvent::AwaitValue { index: 1, name: "-o" },
+      TraceEvent::Token("x"), TraceEvent::Value { index: 1, name: "-o", value: "x" },
+      TraceEvent::Token("-o=y"), TraceEvent::Option { index: 1, name: "-o", value: Some("y") },
+      TraceEvent::Token("in"), TraceEvent::Positional { index: 2, name: "file", value: "in" },
+      TraceEvent::Token("-q"), TraceEvent::Unmatched("-q"),
+      TraceEvent::Token("a"), TraceEvent::Positional { index: 2, name: "file", value: "a" },
+      TraceEvent::Token("b"), TraceEvent::NoPositional("b"),
+    ].map(|event| std::format!("{event:?}"));
+    assert_eq!(EVENTS.take(), expected);
+  }
+}
@@ -0,0 +1,77 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{Opts, ParseControl, ParseError, ParseHandler, ParseResult};
+use crate::argparse::{parse_step, ParserState};
+use crate::utf8::encode_into;
+
+/// How unpaired surrogates are handled when decoding UTF-16 argument tokens with [Opts::parse_utf16].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Utf16Policy {
+  /// Replace each unpaired surrogate with U+FFFD REPLACEMENT CHARACTER.
+  #[default]
+  Replace,
+  /// Fail parsing with [ParseError::UnpairedSurrogate].
+  Reject,
+}
+
+impl<ID, const R: usize> Opts<'_, ID, R> {
+  /// Parses an iterator of UTF-16 code unit arrays as argument tokens, such as those obtained from
+  /// Windows APIs. Each token is decoded into `buffer`, which must be large enough for the longest token
+  /// or parsing fails with [ParseError::TokenTooLong]; unpaired surrogates are handled according to `policy`.
+  pub fn parse_utf16<'a, T: AsRef<[u16]>, I: Iterator<Item = T>>(&self, program_name: &str, args: I,
+    policy: Utf16Policy, buffer: &mut [u8],
+    mut handler: impl ParseHandler<'a, ID>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult {
+    let mut state = ParserState::default();
+    for arg in args {
+      let chars = char::decode_utf16(arg.as_ref().iter().copied()).map(|c| match (c, policy) {
+        (Ok(c), _) => Ok(c),
+        (Err(_), Utf16Policy::Replace) => Ok(char::REPLACEMENT_CHARACTER),
+        (Err(_), Utf16Policy::Reject) => Err(ParseError::UnpairedSurrogate as fn(_) -> _),
+      });
+      parse_step!(self, encode_into(chars, buffer)
+        .and_then(|token| self.next(&mut state, token, program_name, &mut handler)), program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+
+  fn utf16<const N: usize>(s: &str) -> [u16; N] {
+    let mut out = [0; N];
+    s.encode_utf16().zip(out.iter_mut()).for_each(|(c, o)| *o = c);
+    out
+  }
+
+  #[test]
+  fn test_parse_utf16() {
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["--name"], "name"),
+      Opt::positional(1, "file"),
+    ]);
+    let args: [&[u16]; 3] = [&utf16::<6>("--name"), &utf16::<5>("pizza"), &[0x66, 0xD800, 0x3D, 0xD83C, 0xDF55]];
+    let (mut name, mut file) = ([0; 8], [0; 16]);
+    assert!(matches!(OPTIONS.parse_utf16("", args.iter(), Utf16Policy::Replace, &mut [0; 16], |ctx| {
+      let dest = if *ctx.id == 0 { &mut name[..] } else { &mut file[..] };
+      dest[..ctx.arg.len()].copy_from_slice(ctx.arg.as_bytes());
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(&name[..5], b"pizza");
+    assert_eq!(&file[..9], "f\u{FFFD}=\u{1F355}".as_bytes());
+
+    assert!(matches!(OPTIONS.parse_utf16("", args.iter(), Utf16Policy::Reject, &mut [0; 16],
+      |_| Ok(ParseControl::Continue), |_, error| assert!(matches!(error, ParseError::UnpairedSurrogate("f")))),
+      ParseResult::ExitFailure));
+    assert!(matches!(OPTIONS.parse_utf16("", args.iter(), Utf16Policy::Replace, &mut [0; 4],
+      |_| Ok(ParseControl::Continue), |_, error| assert!(matches!(error, ParseError::TokenTooLong("--na")))),
+      ParseResult::ExitFailure));
+  }
+}
@@ -0,0 +1,187 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::{HandlerResult, Opt, Opts, ParseControl, ParseError, ParseHandlerContext, ParseResult};
+use crate::argparse::{parse_step, ParserState};
+use crate::option::OptType;
+
+/// How tokens that aren't valid UTF-8 are handled by [Opts::parse_bytes].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Utf8Policy {
+  /// Replace each invalid sequence with U+FFFD REPLACEMENT CHARACTER.
+  #[default]
+  Replace,
+  /// Fail parsing with [ParseError::InvalidUtf8].
+  Reject,
+  /// Match option names against the valid UTF-8 prefix of each token,
+  /// and pass arguments through to the handler as the raw bytes.
+  Raw,
+}
+
+/// Context passed to the handler of [Opts::parse_bytes], like [ParseHandlerContext]
+/// except the argument is bytes, which are only guaranteed to be UTF-8 if the policy isn't [Utf8Policy::Raw].
+#[derive(Debug)]
+pub struct ParseBytesHandlerContext<'a, ID> {
+  /// Name of the program, for printing statuses to the user.
+  pub program_name: &'a str,
+  /// The generic argument ID that was matched.
+  pub id: &'a ID,
+  /// The option that was matched by the parser.
+  pub option: &'a Opt<'a, ID>,
+  /// The name of the argument parameter that was matched,
+  /// for option parameters this is the token supplied by the user.
+  pub name: &'a str,
+  /// The argument provided to positional arguments and value options, else empty.
+  pub arg: &'a [u8],
+  /// Which value the argument is for options taking several, see [ParseHandlerContext::value_index].
+  pub value_index: usize,
+}
+
+impl<'a, ID> ParseBytesHandlerContext<'a, ID> {
+  /// The argument as a string, or [None] if it isn't valid UTF-8.
+  pub fn arg_str(&self) -> Option<&'a str> {
+    core::str::from_utf8(self.arg).ok()
+  }
+}
+
+/// The longest prefix of a token that is valid UTF-8.
+pub(crate) fn utf8_prefix(token: &[u8]) -> &str {
+  token.utf8_chunks().next().map_or("", |chunk| chunk.valid())
+}
+
+/// Encode the characters of a decoded or unescaped token into the caller's buffer, for parsers whose tokens
+/// aren't borrowed `str`s. Errors hold the part of the token that was encoded, either [ParseError::TokenTooLong]
+/// if the buffer is too small, or the error made by a character that failed to decode.
+pub(crate) fn encode_into<'b>(chars: impl IntoIterator<Item = Result<char, fn(&'b str) -> ParseError<'b>>>,
+  buffer: &'b mut [u8],
+) -> HandlerResult<'b, &'b str> {
+  let mut len = 0;
+  let mut failure = None;
+  for c in chars {
+    let c = match c {
+      Ok(c) => c,
+      Err(e) => {
+        failure = Some(e);
+        break;
+      }
+    };
+    let Some(dest) = buffer.get_mut(len..len + c.len_utf8()) else {
+      failure = Some(ParseError::TokenTooLong);
+      break;
+    };
+    c.encode_utf8(dest);
+    len += c.len_utf8();
+  }
+  let encoded = buffer.get(..len).and_then(|b| core::str::from_utf8(b).ok()).unwrap_or_default();
+  failure.map_or(Ok(encoded), |e| Err(e(encoded)))
+}
+
+impl<ID, const R: usize> Opts<'_, ID, R> {
+  /// Parses an iterator of byte strings as argument tokens, such as those passed over FFI,
+  /// handling tokens that aren't valid UTF-8 according to `policy`. With [Utf8Policy::Replace] each token
+  /// is decoded into `buffer`, which must be large enough for the longest token or parsing fails with
+  /// [ParseError::TokenTooLong]; the buffer is unused by the other policies.
+  pub fn parse_bytes<'a, T: AsRef<[u8]>, I: Iterator<Item = T>>(&self, program_name: &str, args: I,
+    policy: Utf8Policy, buffer: &mut [u8],
+    mut handler: impl FnMut(ParseBytesHandlerContext<ID>) -> HandlerResult<'a, ParseControl>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult {
+    let mut state = ParserState::default();
+    for arg in args {
+      let token = arg.as_ref();
+      let mut bytes_handler = |ctx: ParseHandlerContext<ID>, raw: Option<&[u8]>| handler(ParseBytesHandlerContext {
+        program_name: ctx.program_name, id: ctx.id, option: ctx.option, name: ctx.name, value_index: ctx.value_index,
+        // Choices are passed as the canonical value rather than the raw token
+        arg: match raw {
+          Some(raw) if ctx.option.choices().is_empty() => raw_arg(raw, &ctx),
+          _ => ctx.arg.as_bytes(),
+        },
+      });
+      parse_step!(self, match policy {
+        // Each invalid sequence is replaced by a single replacement character
+        Utf8Policy::Replace => encode_into(token.utf8_chunks().flat_map(|chunk| chunk.valid().chars()
+          .chain((!chunk.invalid().is_empty()).then_some(char::REPLACEMENT_CHARACTER)).map(Ok)), buffer)
+          .and_then(|token| self.next(&mut state, token, program_name, &mut |ctx| bytes_handler(ctx, None))),
+        Utf8Policy::Reject => match core::str::from_utf8(token) {
+          Ok(token) => self.next(&mut state, token, program_name, &mut |ctx| bytes_handler(ctx, None)),
+          Err(_) => Err(ParseError::InvalidUtf8(utf8_prefix(token))),
+        },
+        Utf8Policy::Raw => match utf8_prefix(token) {
+          // Invalid bytes can only be passed through in arguments, not option names
+          prefix if prefix.len() < token.len() && self.is_option_name(&state, prefix) => Err(ParseError::InvalidUtf8(prefix)),
+          prefix => self.next(&mut state, prefix, program_name, &mut |ctx| bytes_handler(ctx, Some(token))),
+        },
+      }, program_name, error);
+    }
+    self.finish(state, program_name, error)
+  }
+}
+
+/// Map the argument matched in the UTF-8 prefix of a token back to the raw token.
+/// Arguments always run to the end of the token.
+fn raw_arg<'t, ID>(token: &'t [u8], ctx: &ParseHandlerContext<ID>) -> &'t [u8] {
+  if matches!(ctx.option.r#type, OptType::Flag) {
+    return &[];
+  }
+  let prefix_len = utf8_prefix(token).len();
+  token.get(prefix_len - ctx.arg.len()..).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const OPTIONS: Opts<u32> = Opts::new(&[
+    Opt::value(0, &["--name"], "name"),
+    Opt::flag(1, &["-v"]),
+    Opt::positional(2, "file"),
+  ]);
+  const ARGS: [&[u8]; 4] = [b"--name", b"pi\xFFza", b"-v", b"--name=\xC3x"];
+
+  /// Parse [ARGS] and collect the arguments to `--name`.
+  fn names(policy: Utf8Policy, buffer: &mut [u8]) -> [[u8; 16]; 2] {
+    let mut args = [[0u8; 16]; 2];
+    let mut count = 0;
+    assert!(matches!(OPTIONS.parse_bytes("", ARGS.iter(), policy, buffer, |ctx| {
+      if *ctx.id == 0 {
+        args[count][..ctx.arg.len()].copy_from_slice(ctx.arg);
+        count += 1;
+      }
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    args
+  }
+
+  #[test]
+  fn test_parse_bytes() {
+    let args = names(Utf8Policy::Replace, &mut [0; 16]);
+    assert_eq!(&args[0][..7], "pi\u{FFFD}za".as_bytes());
+    assert_eq!(&args[1][..4], "\u{FFFD}x".as_bytes());
+    let args = names(Utf8Policy::Raw, &mut []);
+    assert_eq!(&args[0][..5], b"pi\xFFza");
+    assert_eq!(&args[1][..2], b"\xC3x");
+  }
+
+  #[test]
+  fn test_parse_bytes_errors() {
+    let mut failed = false;
+    assert!(matches!(OPTIONS.parse_bytes("", ARGS.iter(), Utf8Policy::Reject, &mut [],
+      |_| Ok(ParseControl::Continue), |_, error| failed = error == ParseError::InvalidUtf8("pi")),
+      ParseResult::ExitFailure));
+    assert!(failed);
+    assert!(matches!(OPTIONS.parse_bytes("", ARGS.iter(), Utf8Policy::Replace, &mut [0; 4],
+      |_| Ok(ParseControl::Continue), |_, error| failed = error == ParseError::TokenTooLong("--na")),
+      ParseResult::ExitFailure));
+    assert!(failed);
+    // Invalid bytes aren't silently dropped from option names
+    for token in [&b"-v\xFF"[..], b"--name\xFF"] {
+      failed = false;
+      assert!(matches!(OPTIONS.parse_bytes("", [token].iter(), Utf8Policy::Raw, &mut [],
+        |_| Ok(ParseControl::Continue), |_, error| failed = matches!(error, ParseError::InvalidUtf8(_))),
+        ParseResult::ExitFailure));
+      assert!(failed);
+    }
+  }
+}
@@ -0,0 +1,130 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+use crate::Opt;
+
+/// Verbosity level accumulated from repeated `-v`/`-q` flags.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Verbosity(i32);
+
+impl Verbosity {
+  /// Create a verbosity counter starting at a given base level.
+  #[inline]
+  pub const fn new(level: i32) -> Self {
+    Self(level)
+  }
+
+  /// Standard counting flag (`-v`, `--verbose`) that should call [Verbosity::increase] each time it's matched.
+  pub const fn verbose_flag<ID>(id: ID) -> Opt<'static, ID> {
+    Opt::flag(id, &["-v", "--verbose"])
+      .help_text("Increase verbosity, can be repeated.")
+  }
+
+  /// Standard counting flag (`-q`, `--quiet`) that should call [Verbosity::decrease] each time it's matched.
+  pub const fn quiet_flag<ID>(id: ID) -> Opt<'static, ID> {
+    Opt::flag(id, &["-q", "--quiet"])
+      .help_text("Decrease verbosity, can be repeated.")
+  }
+
+  /// Increments the verbosity level by one.
+  #[inline]
+  pub const fn increase(&mut self) {
+    self.0 = self.0.saturating_add(1);
+  }
+
+  /// Decrements the verbosity level by one.
+  #[inline]
+  pub const fn decrease(&mut self) {
+    self.0 = self.0.saturating_sub(1);
+  }
+
+  /// Gets the current verbosity level, where 0 is the base level, positive is more verbose and negative is quieter.
+  #[inline(always)]
+  pub const fn level(&self) -> i32 {
+    self.0
+  }
+}
+
+#[cfg(feature = "log")]
+impl Verbosity {
+  /// Maps the verbosity level to a [log::LevelFilter], the base level of 0 maps to [log::LevelFilter::Warn].
+  ///
+  /// Requires `features = ["log"]`.
+  pub const fn level_filter(&self) -> log::LevelFilter {
+    match self.0 {
+      ..=-2 => log::LevelFilter::Off,
+      -1    => log::LevelFilter::Error,
+      0     => log::LevelFilter::Warn,
+      1     => log::LevelFilter::Info,
+      2     => log::LevelFilter::Debug,
+      3..   => log::LevelFilter::Trace,
+    }
+  }
+
+  /// Installs `logger` as the global logger and sets the maximum log level from the verbosity level.
+  ///
+  /// Requires `features = ["log"]`.
+  pub fn init_log(&self, logger: &'static dyn log::Log) -> Result<(), log::SetLoggerError> {
+    log::set_logger(logger)?;
+    log::set_max_level(self.level_filter());
+    Ok(())
+  }
+}
+
+#[cfg(feature = "log")]
+impl From<Verbosity> for log::LevelFilter {
+  fn from(verbosity: Verbosity) -> Self {
+    verbosity.level_filter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Opts, ParseControl, ParseResult};
+
+  #[test]
+  fn test_verbosity_flags() {
+    enum Arg { Verbose, Quiet }
+    const OPTIONS: Opts<Arg> = Opts::new(&[
+      Verbosity::verbose_flag(Arg::Verbose),
+      Verbosity::quiet_flag(Arg::Quiet),
+    ]);
+
+    let mut verbosity = Verbosity::default();
+    assert!(matches!(OPTIONS.parse("", ["-v", "--verbose", "-q", "-v"].iter(), |ctx| {
+      match ctx.id {
+        Arg::Verbose => verbosity.increase(),
+        Arg::Quiet   => verbosity.decrease(),
+      }
+      Ok(ParseControl::Continue)
+    }, |_, error| {
+      panic!("unreachable: {error:?}");
+    }), ParseResult::ContinueSuccess));
+    assert_eq!(verbosity.level(), 2);
+  }
+
+  #[test]
+  fn test_verbosity_saturates() {
+    let mut verbosity = Verbosity::new(i32::MAX);
+    verbosity.increase();
+    assert_eq!(verbosity.level(), i32::MAX);
+    let mut verbosity = Verbosity::new(i32::MIN);
+    verbosity.decrease();
+    assert_eq!(verbosity.level(), i32::MIN);
+  }
+
+  #[test]
+  #[cfg(feature = "log")]
+  fn test_level_filter() {
+    use log::LevelFilter;
+    for (level, expect) in [
+      (-3, LevelFilter::Off), (-2, LevelFilter::Off), (-1, LevelFilter::Error), (0, LevelFilter::Warn),
+      (1, LevelFilter::Info), (2, LevelFilter::Debug), (3, LevelFilter::Trace), (4, LevelFilter::Trace),
+    ] {
+      assert_eq!(Verbosity::new(level).level_filter(), expect);
+    }
+  }
+}
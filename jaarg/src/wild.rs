@@ -0,0 +1,117 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+extern crate std;
+
+use crate::{Opts, ParseControl, ParseError, ParseHandler, ParseResult};
+use crate::argparse::{parse_step, ParserState};
+use std::string::{String, ToString};
+use std::vec::Vec;
+use std::{fs, vec};
+
+/// Match a file name against a pattern of `*` and `?` wildcards, ignoring case like Windows does.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+  let (pattern, name): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+  let (mut p, mut n) = (0, 0);
+  // Position of the last star and the name position it was tried at, for backtracking
+  let mut star = None;
+  while let Some(name_char) = name.get(n) {
+    match pattern.get(p) {
+      Some('*') => { star = Some((p, n)); p += 1; }
+      Some(&c) if c == '?' || c.to_lowercase().eq(name_char.to_lowercase()) => { p += 1; n += 1; }
+      _ => match star {
+        Some((sp, sn)) => { star = Some((sp, sn + 1)); p = sp + 1; n = sn + 1; }
+        None => return false,
+      }
+    }
+  }
+  pattern.iter().skip(p).all(|&c| c == '*')
+}
+
+/// Expand wildcards in the last path component of a token into the sorted list of matching paths,
+/// tokens without wildcards or that don't match anything are passed through unchanged like `wild` does.
+fn expand(token: &str) -> Vec<String> {
+  let (dir, pattern) = token.rfind(['/', '\\']).map_or(("", token), |i| token.split_at(i + 1));
+  if !pattern.contains(['*', '?']) {
+    return vec![token.to_string()];
+  }
+  let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+    return vec![token.to_string()];
+  };
+  let mut paths: Vec<String> = entries.flatten()
+    .filter_map(|entry| entry.file_name().into_string().ok())
+    .filter(|name| matches_pattern(pattern, name))
+    .map(|name| [dir, &name].concat())
+    .collect();
+  if paths.is_empty() {
+    paths.push(token.to_string());
+  }
+  paths.sort();
+  paths
+}
+
+impl<ID, const R: usize> Opts<'_, ID, R> {
+  /// Parses argument tokens like [Opts::parse], expanding wildcards in positional arguments
+  /// that haven't opted out with [Opt::no_wildcards](crate::Opt::no_wildcards).
+  pub(crate) fn parse_wild<'a, S: AsRef<str> + 'a, I: Iterator<Item = S>>(&self, program_name: &str, args: I,
+    mut handler: impl ParseHandler<'a, ID>,
+    error: impl FnOnce(&str, ParseError),
+  ) -> ParseResult {
+    let mut state = ParserState::default();
+    'outer: for arg in args {
+      let arg = arg.as_ref();
+      let tokens = match self.next_positional(&state, arg) {
+        Some(option) if option.expands_wildcards() => expand(arg),
+        _ => vec![arg.to_string()],
+      };
+      for token in &tokens {
+        parse_step!(self, self.next(&mut state, token, program_name, &mut handler), program_name, error, 'outer);
+      }
+    }
+    self.finish(state, program_name, error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Opt;
+  use std::env;
+
+  #[test]
+  fn test_matches_pattern() {
+    assert!(matches_pattern("*.txt", "a.TXT"));
+    assert!(matches_pattern("a?c*", "abc"));
+    assert!(matches_pattern("*a*b", "xaab"));
+    assert!(!matches_pattern("*.txt", "a.txt.bak"));
+    assert!(!matches_pattern("a?", "a"));
+  }
+
+  #[test]
+  fn test_parse_wild() {
+    let dir = env::temp_dir().join("jaarg-test-parse-wild");
+    fs::create_dir_all(&dir).unwrap();
+    for name in ["b.txt", "a.txt", "c.log"] {
+      fs::write(dir.join(name), "").unwrap();
+    }
+    let dir = [dir.to_str().unwrap(), "/"].concat();
+
+    const OPTIONS: Opts<u32> = Opts::new(&[
+      Opt::value(0, &["-o"], "out"),
+      Opt::positional(1, "literal").no_wildcards(),
+      Opt::positional(2, "a"),
+      Opt::positional(3, "b"),
+      Opt::positional(4, "c"),
+    ]);
+    let (glob, none) = ([dir.as_str(), "*.txt"].concat(), [dir.as_str(), "*.md"].concat());
+    let mut seen = Vec::new();
+    assert!(matches!(OPTIONS.parse_wild("", ["-o", "*.txt", "*.txt", &glob, &none].iter(), |ctx| {
+      seen.push((*ctx.id, ctx.arg.to_string()));
+      Ok(ParseControl::Continue)
+    }, |_, error| panic!("unreachable: {error:?}")), ParseResult::ContinueSuccess));
+    assert_eq!(seen, [(0, "*.txt".into()), (1, "*.txt".into()),
+      (2, [dir.as_str(), "a.txt"].concat()), (3, [dir.as_str(), "b.txt"].concat()), (4, none)]);
+  }
+}
@@ -0,0 +1,135 @@
+/* jaarg - Argument parser
+ * SPDX-FileCopyrightText: (C) 2025 Gay Pizza Specifications
+ * SPDX-License-Identifier: MIT OR Apache-2.0
+ */
+
+//! Windows command line handling that doesn't depend on Windows APIs, for contexts such as services
+//! or GUI subsystem apps where only the raw command line string is available.
+//!
+//! Requires `features = ["alloc"]`.
+
+extern crate alloc;
+
+use alloc::string::String;
+use core::iter::Peekable;
+use core::str::Chars;
+
+/// Iterator splitting a Windows command line into arguments using the same rules as `CommandLineToArgvW`,
+/// the first argument is the program name.
+///
+/// ```
+/// # use jaarg::{Opt, Opts, ParseControl};
+/// # use jaarg::windows::CommandLineArgs;
+/// # const OPTIONS: Opts<()> = Opts::new(&[Opt::positional((), "file")]);
+/// let mut args = CommandLineArgs::new(r#""C:\Program Files\tool.exe" "my file.txt""#);
+/// let program_name = args.next().unwrap_or_default();
+/// OPTIONS.parse(&program_name, args, |ctx| {
+///   assert_eq!(ctx.arg, "my file.txt");
+///   Ok(ParseControl::Continue)
+/// }, |_, _| {});
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandLineArgs<'a> {
+  chars: Peekable<Chars<'a>>,
+  program_name: bool,
+}
+
+impl<'a> CommandLineArgs<'a> {
+  /// Split a command line, as returned by `GetCommandLineW`.
+  pub fn new(command_line: &'a str) -> Self {
+    Self { chars: command_line.chars().peekable(), program_name: true }
+  }
+
+  /// The program name is taken literally, running from an opening quote to the next quote,
+  /// or else up to the first whitespace.
+  fn next_program_name(&mut self) -> String {
+    if self.chars.next_if_eq(&'"').is_some() {
+      self.chars.by_ref().take_while(|&c| c != '"').collect()
+    } else {
+      let mut arg = String::new();
+      while let Some(c) = self.chars.next_if(|c| !matches!(c, ' ' | '\t')) {
+        arg.push(c);
+      }
+      arg
+    }
+  }
+
+  /// Arguments may be quoted, quotes can be escaped by an odd number of preceding backslashes.
+  /// A doubled quote inside quotes is a literal quote that also ends the quoted section.
+  /// Backslashes are only special before a quote.
+  fn next_argument(&mut self) -> Option<String> {
+    // Skip separating whitespace
+    while self.chars.next_if(|c| matches!(c, ' ' | '\t')).is_some() {}
+    self.chars.peek()?;
+
+    let mut arg = String::new();
+    let mut in_quotes = false;
+    let mut backslashes = 0;
+    while let Some(c) = self.chars.next() {
+      if c == '\\' {
+        backslashes += 1;
+        continue;
+      }
+      if c == '"' {
+        // Backslashes preceding a quote are halved, an odd one out escapes the quote
+        arg.extend(core::iter::repeat_n('\\', backslashes / 2));
+        if backslashes % 2 == 1 {
+          arg.push('"');
+        } else if in_quotes && self.chars.next_if_eq(&'"').is_some() {
+          arg.push('"');
+          in_quotes = false;
+        } else {
+          in_quotes = !in_quotes;
+        }
+      } else {
+        arg.extend(core::iter::repeat_n('\\', backslashes));
+        if matches!(c, ' ' | '\t') && !in_quotes {
+          return Some(arg);
+        }
+        arg.push(c);
+      }
+      backslashes = 0;
+    }
+    arg.extend(core::iter::repeat_n('\\', backslashes));
+    Some(arg)
+  }
+}
+
+impl Iterator for CommandLineArgs<'_> {
+  type Item = String;
+
+  fn next(&mut self) -> Option<String> {
+    if core::mem::take(&mut self.program_name) {
+      self.chars.peek()?;
+      Some(self.next_program_name())
+    } else {
+      self.next_argument()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use alloc::vec::Vec;
+
+  fn split(command_line: &str) -> Vec<String> {
+    CommandLineArgs::new(command_line).collect()
+  }
+
+  #[test]
+  fn test_command_line_args() {
+    assert_eq!(split(""), [""; 0]);
+    assert_eq!(split(r#""C:\Program Files\a.exe"  b"#), [r"C:\Program Files\a.exe", "b"]);
+    assert_eq!(split(r#"C:\a\"b c"#), [r#"C:\a\"b"#, "c"]);
+    assert_eq!(split(r#""C:\a\"b c"#), [r"C:\a\", "b", "c"]);
+    assert_eq!(split(r#"C:\a"b c"#), [r#"C:\a"b"#, "c"]);
+    assert_eq!(split(r#"a "abc" d e "#), ["a", "abc", "d", "e"]);
+    assert_eq!(split(r#"a a\\b d"e f"g h"#), ["a", r"a\\b", "de fg", "h"]);
+    assert_eq!(split(r#"a a\\\"b c d"#), ["a", r#"a\"b"#, "c", "d"]);
+    assert_eq!(split(r#"a a\\\\"b c" d e"#), ["a", r"a\\b c", "d", "e"]);
+    assert_eq!(split(r#"a a"b"" c d"#), ["a", r#"ab""#, "c", "d"]);
+    assert_eq!(split(r#"a "b""c d"#), ["a", r#"b"c"#, "d"]);
+    assert_eq!(split("a \"\" \t b\\"), ["a", "", "b\\"]);
+  }
+}